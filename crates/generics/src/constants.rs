@@ -12,6 +12,24 @@ pub const DEFAULT_MAX_PORT: usize = 2;
 pub const INVALID_CONTROLLER_PORT: i16 = -1;
 pub const SAVE_IMAGE_EXTENSION_FILE: &str = "png";
 pub const SAVE_EXTENSION_FILE: &str = "save";
+pub const SAVE_RAM_EXTENSION_FILE: &str = "srm";
+pub const RTC_EXTENSION_FILE: &str = "rtc";
+pub const CHEAT_EXTENSION_FILE: &str = "cht";
+pub const M3U_EXTENSION_FILE: &str = "m3u";
+#[doc = "intervalo em segundos entre cada salvamento automático da memoria da bateria (SRAM/RTC)"]
+pub const BATTERY_SAVE_INTERVAL: u64 = 10;
+pub const LOG_EXTENSION_FILE: &str = "log";
+pub const LOG_DIR_NAME: &str = "logs";
+#[doc = "tamanho em bytes que um arquivo de log pode ter antes de ser rotacionado"]
+pub const MAX_LOG_FILE_SIZE: u64 = 1024 * 1024;
+#[doc = "quantidade de arquivos de log guardados para cada rom, contando com o atual"]
+pub const MAX_LOG_FILES: usize = 3;
+#[doc = "quantidade de mensagens do core mantidas na memoria para a interface"]
+pub const MEMORY_LOG_CAPACITY: usize = 500;
+#[doc = "memoria em bytes usada por padrão para guardar os states do rewind"]
+pub const DEFAULT_REWIND_BUFFER_SIZE: usize = 64 * 1024 * 1024;
+#[doc = "por padrão um state do rewind e guardado a cada frame"]
+pub const DEFAULT_REWIND_GRANULARITY: u32 = 1;
 
 //URLS
 pub const CORE_INFOS_URL: &str = "https://buildbot.libretro.com/assets/frontend/info.zip";
@@ -13,6 +13,7 @@ pub struct RetroPaths {
     pub temps: Arc<String>,
     pub cores: Arc<String>,
     pub infos: Arc<String>,
+    pub cheats: Arc<String>,
 }
 
 impl PartialEq for RetroPaths {
@@ -22,6 +23,7 @@ impl PartialEq for RetroPaths {
 }
 
 impl RetroPaths {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         system: String,
         save: String,
@@ -30,6 +32,7 @@ impl RetroPaths {
         temps: String,
         cores: String,
         infos: String,
+        cheats: String,
     ) -> Result<Self, ErroHandle> {
         if Path::new(&system).exists().not() && fs::create_dir_all(&system).is_err() {
             return Err(ErroHandle {
@@ -73,6 +76,12 @@ impl RetroPaths {
             });
         }
 
+        if Path::new(&cheats).exists().not() && fs::create_dir_all(&cheats).is_err() {
+            return Err(ErroHandle {
+                message: "Não foi possível criar a pasta cheats".to_owned(),
+            });
+        }
+
         Ok(Self {
             system: Arc::new(system),
             opt: Arc::new(opt),
@@ -81,6 +90,7 @@ impl RetroPaths {
             temps: Arc::new(temps),
             cores: Arc::new(cores),
             infos: Arc::new(infos),
+            cheats: Arc::new(cheats),
         })
     }
 }
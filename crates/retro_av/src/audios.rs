@@ -2,7 +2,10 @@ use generics::{
     erro_handle::ErroHandle,
     types::{ArcTMuxte, TMutex},
 };
-use retro_core::{av_info::AvInfo, RetroAudioEnvCallbacks};
+use retro_core::{
+    audio_callback::AudioBufferStatus, av_info::AvInfo, speed::EmulationSpeed,
+    RetroAudioEnvCallbacks,
+};
 use rodio::{buffer::SamplesBuffer, OutputStream, OutputStreamHandle, Sink};
 use std::{
     cell::UnsafeCell,
@@ -10,6 +13,9 @@ use std::{
     sync::Arc,
 };
 
+//quantidade de frames de áudio na fila do rodio que e considerada um buffer cheio
+const MAX_QUEUED_FRAMES: usize = 4;
+
 pub struct AudioNewFrame {
     pub data: *const i16,
     pub frames: usize,
@@ -21,6 +27,7 @@ pub struct RetroAudio {
     _stream: OutputStream,
     sink: Sink,
     buffer: ArcTMuxte<UnsafeCell<AudioNewFrame>>,
+    muted: bool,
 }
 
 impl RetroAudio {
@@ -52,14 +59,31 @@ impl RetroAudio {
                 frames: 0,
                 channel: 2,
             })),
+            muted: false,
         })
     }
 
+    #[doc = "o áudio e reproduzido mais rápido ou mais devagar junto com o jogo, o que também muda o tom.
+    sem limite de velocidade ou fora de [retro_core::speed::AUDIBLE_SPEED_RANGE] o áudio fica mudo"]
+    pub fn set_speed(&mut self, speed: EmulationSpeed) {
+        match speed.get_audio_multiplier() {
+            Some(multiplier) => {
+                self.muted = false;
+                self.sink.set_speed(multiplier as f32);
+            }
+            None => {
+                self.muted = true;
+                self.sink.clear();
+                self.sink.play();
+            }
+        }
+    }
+
     pub fn resume_new_frame(&mut self, av_info: &Arc<AvInfo>) -> Result<(), ErroHandle> {
         if let Ok(sample_rate) = av_info.timing.sample_rate.read() {
             let buffer = unsafe { self.buffer.try_load()?.get().read() };
 
-            if buffer.data.is_null() {
+            if buffer.data.is_null() || self.muted {
                 Ok(())
             } else {
                 let data = unsafe { &*slice_from_raw_parts(buffer.data, buffer.frames * 2) };
@@ -75,6 +99,16 @@ impl RetroAudio {
         }
     }
 
+    pub fn get_buffer_status(&self) -> AudioBufferStatus {
+        let queued = self.sink.len().min(MAX_QUEUED_FRAMES);
+
+        AudioBufferStatus {
+            active: true,
+            occupancy: (queued * 100 / MAX_QUEUED_FRAMES) as u32,
+            underrun_likely: queued <= 1,
+        }
+    }
+
     pub fn get_core_cb(&self) -> RetroAudioCb {
         RetroAudioCb {
             buffer: self.buffer.clone(),
@@ -0,0 +1,61 @@
+use generics::erro_handle::ErroHandle;
+use retro_core::{
+    audio_callback::AudioBufferStatus, av_info::AvInfo, message::RetroMessage,
+    speed::EmulationSpeed, RetroAudioEnvCallbacks, RetroVideoEnvCallbacks,
+};
+use sdl2::EventPump;
+use std::{path::Path, sync::Arc};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RetroAvMode {
+    #[doc = "cria uma janela GL e uma saída de áudio usando o SDL e o rodio"]
+    #[default]
+    Window,
+    #[doc = "nao cria nenhuma janela ou saída de áudio, mas continua respeitando o fps do core"]
+    Headless,
+    #[doc = "igual ao Headless, mas executa os frames o mais rápido possível"]
+    HeadlessFreeRun,
+}
+
+/// Tudo que a thread do jogo precisa para exibir os frames e reproduzir o áudio gerados pelo core.
+///
+/// Implementado por [crate::RetroAv] (janela GL + rodio) e por [crate::RetroNullAv] (sem display).
+pub trait RetroAvCtx {
+    fn get_core_cb(
+        &self,
+    ) -> (
+        Box<dyn RetroVideoEnvCallbacks>,
+        Box<dyn RetroAudioEnvCallbacks>,
+    );
+
+    #[doc = "prepara o contexto para exibir os frames. so retorna um EventPump se houve uma janela"]
+    fn build(&mut self, av_info: &Arc<AvInfo>) -> Result<Option<EventPump>, ErroHandle>;
+
+    #[doc = "retorna true quando ja e hora de pedir um novo frame para o core"]
+    fn sync(&mut self) -> bool;
+
+    #[doc = "muda o ritmo de [RetroAvCtx::sync]. o áudio acompanha a nova velocidade"]
+    fn set_speed(&mut self, speed: EmulationSpeed);
+
+    fn get_new_frame(&mut self) -> Result<(), ErroHandle>;
+
+    fn print_screen(&self, out_path: &Path, av_info: &Arc<AvInfo>) -> Result<(), ErroHandle>;
+
+    fn enable_full_screen(&self) -> Result<(), ErroHandle>;
+
+    fn disable_full_screen(&self) -> Result<(), ErroHandle>;
+
+    #[doc = "exibe uma mensagem do core na tela. sem uma janela a mensagem e ignorada"]
+    fn show_message(&self, message: &RetroMessage) -> Result<(), ErroHandle>;
+
+    #[doc = "quanto do buffer de áudio ainda falta ser reproduzido"]
+    fn get_audio_buffer_status(&self) -> AudioBufferStatus;
+}
+
+pub fn create_av_ctx(mode: RetroAvMode) -> Result<Box<dyn RetroAvCtx>, ErroHandle> {
+    match mode {
+        RetroAvMode::Window => Ok(Box::new(crate::RetroAv::new()?)),
+        RetroAvMode::Headless => Ok(Box::new(crate::RetroNullAv::new(false))),
+        RetroAvMode::HeadlessFreeRun => Ok(Box::new(crate::RetroNullAv::new(true))),
+    }
+}
@@ -0,0 +1,282 @@
+use crate::{av_ctx::RetroAvCtx, print_scree::PrintScree, sync::RetroSync, video::RawTextureData};
+use generics::{
+    erro_handle::ErroHandle,
+    types::{ArcTMuxte, TMutex},
+};
+use retro_core::{
+    audio_callback::AudioBufferStatus, av_info::AvInfo, message::RetroMessage,
+    speed::EmulationSpeed, RetroAudioEnvCallbacks, RetroVideoEnvCallbacks,
+};
+use sdl2::EventPump;
+use std::{
+    cell::UnsafeCell,
+    ffi::c_void,
+    path::{Path, PathBuf},
+    ptr::{null, slice_from_raw_parts},
+    sync::Arc,
+};
+
+#[derive(Clone, Debug, Default)]
+pub struct NullFrame {
+    pub data: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+    pub pitch: usize,
+}
+
+/// Substitui o [crate::RetroAv] quando nao ha um display ou uma saída de áudio disponível.
+///
+/// Os frames e o áudio gerados pelo core sao apenas copiados para a memoria.
+pub struct RetroNullAv {
+    frame: ArcTMuxte<NullFrame>,
+    samples: ArcTMuxte<Vec<i16>>,
+    sync: RetroSync,
+    speed: EmulationSpeed,
+    free_run: bool,
+    frame_count: u64,
+    av_info: Option<Arc<AvInfo>>,
+}
+
+impl RetroNullAv {
+    #[doc = "se *free_run* for true os frames serão pedidos ao core sem respeitar o fps dele"]
+    pub fn new(free_run: bool) -> Self {
+        Self {
+            frame: TMutex::new(NullFrame::default()),
+            samples: TMutex::new(Vec::new()),
+            sync: RetroSync::default(),
+            speed: EmulationSpeed::default(),
+            free_run,
+            frame_count: 0,
+            av_info: None,
+        }
+    }
+
+    pub fn get_last_frame(&self) -> Result<NullFrame, ErroHandle> {
+        Ok(self.frame.try_load()?.clone())
+    }
+
+    pub fn get_frame_count(&self) -> u64 {
+        self.frame_count
+    }
+
+    pub fn get_core_null_cb(&self) -> (NullVideoCb, NullAudioCb) {
+        (
+            NullVideoCb {
+                frame: self.frame.clone(),
+            },
+            NullAudioCb {
+                samples: self.samples.clone(),
+            },
+        )
+    }
+}
+
+impl RetroAvCtx for RetroNullAv {
+    fn get_core_cb(
+        &self,
+    ) -> (
+        Box<dyn RetroVideoEnvCallbacks>,
+        Box<dyn RetroAudioEnvCallbacks>,
+    ) {
+        let (video_cb, audio_cb) = self.get_core_null_cb();
+
+        (Box::new(video_cb), Box::new(audio_cb))
+    }
+
+    fn build(&mut self, av_info: &Arc<AvInfo>) -> Result<Option<EventPump>, ErroHandle> {
+        self.av_info.replace(av_info.clone());
+
+        Ok(None)
+    }
+
+    fn sync(&mut self) -> bool {
+        if let Some(av_info) = &self.av_info {
+            if self.free_run {
+                return true;
+            }
+
+            let fps = av_info.timing.fps.read().unwrap().abs();
+
+            match self.speed.get_target_fps(fps) {
+                Some(fps) => self.sync.sync(fps),
+                None => true,
+            }
+        } else {
+            false
+        }
+    }
+
+    fn set_speed(&mut self, speed: EmulationSpeed) {
+        self.speed = speed;
+    }
+
+    fn get_new_frame(&mut self) -> Result<(), ErroHandle> {
+        //nao ha onde reproduzir o áudio, então ele e apenas descartado
+        self.samples.try_load()?.clear();
+        self.frame_count += 1;
+
+        Ok(())
+    }
+
+    fn print_screen(&self, out_path: &Path, av_info: &Arc<AvInfo>) -> Result<(), ErroHandle> {
+        let frame = self.frame.try_load()?;
+
+        if frame.data.is_empty() {
+            return Err(ErroHandle::new("Nenhum frame foi gerado pelo core ainda"));
+        }
+
+        let raw_texture = UnsafeCell::new(RawTextureData {
+            data: frame.data.as_ptr() as *const c_void,
+            width: frame.width,
+            height: frame.height,
+            pitch: frame.pitch,
+        });
+
+        PrintScree::take(&raw_texture, av_info, &mut PathBuf::from(out_path))
+    }
+
+    fn enable_full_screen(&self) -> Result<(), ErroHandle> {
+        Ok(())
+    }
+
+    fn disable_full_screen(&self) -> Result<(), ErroHandle> {
+        Ok(())
+    }
+
+    fn show_message(&self, _message: &RetroMessage) -> Result<(), ErroHandle> {
+        Ok(())
+    }
+
+    //sem uma saída de áudio o buffer nunca e usado
+    fn get_audio_buffer_status(&self) -> AudioBufferStatus {
+        AudioBufferStatus::default()
+    }
+}
+
+pub struct NullVideoCb {
+    frame: ArcTMuxte<NullFrame>,
+}
+
+impl RetroVideoEnvCallbacks for NullVideoCb {
+    fn video_refresh_callback(
+        &self,
+        data: *const c_void,
+        width: u32,
+        height: u32,
+        pitch: usize,
+    ) -> Result<(), ErroHandle> {
+        //null indica um frame repetido e -1 (RETRO_HW_FRAME_BUFFER_VALID) um frame renderizado
+        //na gpu. Em ambos os casos não ha nada para copiar.
+        if data.is_null() || data as isize == -1 {
+            return Ok(());
+        }
+
+        let mut frame = self.frame.try_load()?;
+        let src = unsafe { &*slice_from_raw_parts(data as *const u8, pitch * height as usize) };
+
+        frame.data.clear();
+        frame.data.extend_from_slice(src);
+        frame.width = width;
+        frame.height = height;
+        frame.pitch = pitch;
+
+        Ok(())
+    }
+
+    fn context_reset(&self) -> Result<(), ErroHandle> {
+        Ok(())
+    }
+
+    fn get_proc_address(&self, _proc_name: &str) -> Result<*const (), ErroHandle> {
+        Ok(null())
+    }
+
+    fn context_destroy(&self) -> Result<(), ErroHandle> {
+        Ok(())
+    }
+}
+
+pub struct NullAudioCb {
+    samples: ArcTMuxte<Vec<i16>>,
+}
+
+impl RetroAudioEnvCallbacks for NullAudioCb {
+    fn audio_sample_callback(&self, left: i16, right: i16) -> Result<(), ErroHandle> {
+        let mut samples = self.samples.try_load()?;
+        samples.push(left);
+        samples.push(right);
+
+        Ok(())
+    }
+
+    fn audio_sample_batch_callback(
+        &self,
+        data: *const i16,
+        frames: usize,
+    ) -> Result<usize, ErroHandle> {
+        if data.is_null() {
+            return Ok(0);
+        }
+
+        let data = unsafe { &*slice_from_raw_parts(data, frames * 2) };
+        self.samples.try_load()?.extend_from_slice(data);
+
+        Ok(frames)
+    }
+}
+
+#[cfg(test)]
+mod test_headless {
+    use super::RetroNullAv;
+    use crate::av_ctx::RetroAvCtx;
+    use retro_core::{av_info::AvInfo, graphic_api::GraphicApi, speed::EmulationSpeed};
+    use std::{ffi::c_void, sync::Arc};
+
+    #[test]
+    fn copy_frame_to_memory() {
+        let null_av = RetroNullAv::new(true);
+        let (video_cb, _) = null_av.get_core_cb();
+
+        let pixels: Vec<u8> = (0..32).collect();
+        let data = pixels.as_ptr() as *const c_void;
+
+        video_cb.video_refresh_callback(data, 2, 2, 8).unwrap();
+
+        let frame = null_av.get_last_frame().unwrap();
+        assert_eq!(frame.data, pixels[..16]);
+        assert_eq!((frame.width, frame.height, frame.pitch), (2, 2, 8));
+
+        //frames repetidos mantêm o ultimo frame recebido
+        video_cb
+            .video_refresh_callback(std::ptr::null(), 2, 2, 8)
+            .unwrap();
+        assert_eq!(null_av.get_last_frame().unwrap().data, pixels[..16]);
+    }
+
+    #[test]
+    fn free_run_never_waits() {
+        let mut null_av = RetroNullAv::new(true);
+
+        assert!(!null_av.sync());
+
+        null_av
+            .build(&Arc::new(AvInfo::new(GraphicApi::default())))
+            .unwrap();
+
+        assert!(null_av.sync());
+        assert!(null_av.sync());
+    }
+
+    #[test]
+    fn unlimited_speed_never_waits() {
+        let mut null_av = RetroNullAv::new(false);
+
+        null_av
+            .build(&Arc::new(AvInfo::new(GraphicApi::default())))
+            .unwrap();
+        null_av.set_speed(EmulationSpeed::Unlimited);
+
+        assert!(null_av.sync());
+        assert!(null_av.sync());
+    }
+}
@@ -0,0 +1,92 @@
+use generics::erro_handle::ErroHandle;
+use retro_core::camera::{CameraFrame, CameraSource};
+use std::{
+    fs,
+    path::Path,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+/// Câmera virtual que envia ao core as imagens PNG de uma pasta, em ordem alfabética.
+///
+/// Cada imagem e exibida por *hold_frames* quadros e depois da ultima a sequencia recomeça.
+/// As imagens sao carregadas uma única vez, em [ImageDirCamera::new].
+#[derive(Debug)]
+pub struct ImageDirCamera {
+    frames: Vec<CameraFrame>,
+    hold_frames: u64,
+    frame_count: AtomicU64,
+}
+
+impl ImageDirCamera {
+    pub fn new(dir: &Path, hold_frames: u64) -> Result<Self, ErroHandle> {
+        let mut paths = Vec::new();
+
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+
+            let is_png = path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("png"));
+
+            if is_png {
+                paths.push(path);
+            }
+        }
+
+        if paths.is_empty() {
+            return Err(ErroHandle {
+                message: format!("Nenhuma imagem png foi encontrada em {}", dir.display()),
+            });
+        }
+
+        paths.sort();
+
+        let mut frames = Vec::with_capacity(paths.len());
+
+        for path in paths {
+            frames.push(Self::load_frame(&path)?);
+        }
+
+        Ok(Self {
+            frames,
+            hold_frames: hold_frames.max(1),
+            frame_count: AtomicU64::new(0),
+        })
+    }
+
+    fn load_frame(path: &Path) -> Result<CameraFrame, ErroHandle> {
+        let img = image::open(path)
+            .map_err(|e| ErroHandle {
+                message: format!("{}: {}", path.display(), e),
+            })?
+            .to_rgb8();
+
+        let pixels = img
+            .pixels()
+            .map(|pixel| {
+                let [r, g, b] = pixel.0;
+                (r as u32) << 16 | (g as u32) << 8 | b as u32
+            })
+            .collect();
+
+        CameraFrame::new(img.width(), img.height(), pixels)
+    }
+}
+
+impl CameraSource for ImageDirCamera {
+    fn start(&self) -> Result<bool, ErroHandle> {
+        self.frame_count.store(0, Ordering::SeqCst);
+        Ok(true)
+    }
+
+    fn stop(&self) -> Result<(), ErroHandle> {
+        Ok(())
+    }
+
+    fn next_frame(&self, _width: u32, _height: u32) -> Result<Option<CameraFrame>, ErroHandle> {
+        let count = self.frame_count.fetch_add(1, Ordering::SeqCst);
+        let index = (count / self.hold_frames) as usize % self.frames.len();
+
+        Ok(Some(self.frames[index].clone()))
+    }
+}
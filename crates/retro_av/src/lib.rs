@@ -6,6 +6,9 @@ extern crate rodio;
 extern crate sdl2;
 
 mod audios;
+mod av_ctx;
+mod headless;
+mod image_camera;
 mod print_scree;
 mod retro_gl;
 mod sync;
@@ -14,6 +17,9 @@ mod video;
 mod retro_av;
 
 pub use audios::RetroAudioCb;
+pub use av_ctx::{create_av_ctx, RetroAvCtx, RetroAvMode};
+pub use headless::{NullAudioCb, NullFrame, NullVideoCb, RetroNullAv};
+pub use image_camera::ImageDirCamera;
 pub use retro_av::RetroAv;
 pub use sdl2::event::{Event, WindowEvent};
 pub use sdl2::keyboard::Keycode;
@@ -1,16 +1,22 @@
 use crate::audios::RetroAudioCb;
+use crate::av_ctx::RetroAvCtx;
 use crate::sync::RetroSync;
 use crate::video::RetroVideo;
 use crate::{audios::RetroAudio, video::RetroVideoCb};
 use generics::erro_handle::ErroHandle;
-use retro_core::av_info::AvInfo;
+use retro_core::{
+    audio_callback::AudioBufferStatus, av_info::AvInfo, message::RetroMessage,
+    speed::EmulationSpeed, RetroAudioEnvCallbacks, RetroVideoEnvCallbacks,
+};
 use sdl2::{EventPump, Sdl};
+use std::path::Path;
 use std::sync::Arc;
 
 pub struct RetroAv {
     pub video: RetroVideo,
     pub audio: RetroAudio,
     sync: RetroSync,
+    speed: EmulationSpeed,
     av_info: Option<Arc<AvInfo>>,
     _sdl: Sdl,
 }
@@ -33,6 +39,7 @@ impl RetroAv {
             audio,
             _sdl,
             sync: RetroSync::default(),
+            speed: EmulationSpeed::default(),
             av_info: None,
         })
     }
@@ -49,28 +56,84 @@ impl RetroAv {
         Ok(event_pump)
     }
 
-    pub fn get_new_frame(&mut self) -> Result<(), ErroHandle> {
+    pub fn get_core_av_cb(&self) -> (RetroVideoCb, RetroAudioCb) {
+        let video_cb = self.video.get_core_cb();
+        let audio_cb = self.audio.get_core_cb();
+
+        (video_cb, audio_cb)
+    }
+}
+
+impl RetroAvCtx for RetroAv {
+    fn get_core_cb(
+        &self,
+    ) -> (
+        Box<dyn RetroVideoEnvCallbacks>,
+        Box<dyn RetroAudioEnvCallbacks>,
+    ) {
+        let (video_cb, audio_cb) = self.get_core_av_cb();
+
+        (Box::new(video_cb), Box::new(audio_cb))
+    }
+
+    fn build(&mut self, av_info: &Arc<AvInfo>) -> Result<Option<EventPump>, ErroHandle> {
+        Ok(Some(self.build_window(av_info)?))
+    }
+
+    fn get_new_frame(&mut self) -> Result<(), ErroHandle> {
         if let Some(av_info) = &self.av_info {
             self.audio.resume_new_frame(av_info)?;
+
+            //sem limite de velocidade o core gera mais frames do que a tela consegue exibir
+            if self.speed == EmulationSpeed::Unlimited {
+                let fps = av_info.timing.fps.read()?.abs();
+
+                if !self.sync.present(fps) {
+                    return Ok(());
+                }
+            }
+
             self.video.draw_new_frame()?;
         }
 
         Ok(())
     }
 
-    pub fn sync(&mut self) -> bool {
+    fn sync(&mut self) -> bool {
         if let Some(av_info) = &self.av_info {
             let fps = av_info.timing.fps.read().unwrap().abs();
-            self.sync.sync(fps)
+
+            match self.speed.get_target_fps(fps) {
+                Some(fps) => self.sync.sync(fps),
+                None => true,
+            }
         } else {
             false
         }
     }
 
-    pub fn get_core_cb(&self) -> (RetroVideoCb, RetroAudioCb) {
-        let video_cb = self.video.get_core_cb();
-        let audio_cb = self.audio.get_core_cb();
+    fn set_speed(&mut self, speed: EmulationSpeed) {
+        self.speed = speed;
+        self.audio.set_speed(speed);
+    }
 
-        (video_cb, audio_cb)
+    fn print_screen(&self, out_path: &Path, av_info: &Arc<AvInfo>) -> Result<(), ErroHandle> {
+        self.video.print_screen(out_path, av_info)
+    }
+
+    fn enable_full_screen(&self) -> Result<(), ErroHandle> {
+        self.video.enable_full_screen()
+    }
+
+    fn disable_full_screen(&self) -> Result<(), ErroHandle> {
+        self.video.disable_full_screen()
+    }
+
+    fn show_message(&self, message: &RetroMessage) -> Result<(), ErroHandle> {
+        self.video.show_message(message)
+    }
+
+    fn get_audio_buffer_status(&self) -> AudioBufferStatus {
+        self.audio.get_buffer_status()
     }
 }
@@ -1,6 +1,8 @@
 mod frame_buffer;
 pub mod gl;
 mod gl_buffer;
+mod osd;
+mod osd_font;
 mod pixel;
 mod render;
 mod render_buffer;
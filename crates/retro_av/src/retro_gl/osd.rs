@@ -0,0 +1,347 @@
+use super::{
+    gl::gl::{self, types::GLuint},
+    gl_buffer::GlBuffer,
+    osd_font::{get_glyph, glyph_pixel, GLYPH_HEIGHT, GLYPH_WIDTH},
+    shader::Shader,
+    shader_program::ShaderProgram,
+    vertex_array::VertexArray,
+};
+use generics::erro_handle::ErroHandle;
+use libretro_sys::binding_libretro::retro_message_type;
+use retro_core::message::RetroMessage;
+use std::{
+    mem::size_of,
+    rc::Rc,
+    time::{Duration, Instant},
+};
+
+const MAX_MESSAGES: usize = 4;
+const FONT_SCALE: usize = 2;
+const PADDING: usize = 4;
+const MARGIN: f32 = 16.0;
+
+const TEXT_COLOR: [u8; 4] = [255, 255, 255, 255];
+const BACKGROUND_COLOR: [u8; 4] = [0, 0, 0, 160];
+
+type OsdVertex = [f32; 4];
+
+struct OsdMessage {
+    text: String,
+    priority: u32,
+    type_: retro_message_type,
+    expires_at: Instant,
+}
+
+/// Guarda as mensagens que estão na tela e decide quais devem ser exibidas.
+#[derive(Default)]
+pub struct OsdQueue {
+    messages: Vec<OsdMessage>,
+    changed: bool,
+}
+
+impl OsdQueue {
+    pub fn push(&mut self, message: &RetroMessage) {
+        let text = if message.type_ == retro_message_type::RETRO_MESSAGE_TYPE_PROGRESS
+            && message.progress >= 0
+        {
+            format!("{} {}%", message.msg, message.progress)
+        } else {
+            message.msg.clone()
+        };
+
+        //uma barra de progresso e atualizada varias vezes, entao a anterior deve ser substituída
+        if message.type_ == retro_message_type::RETRO_MESSAGE_TYPE_PROGRESS {
+            self.messages.retain(|m| m.type_ != message.type_);
+        }
+
+        self.messages.insert(
+            0,
+            OsdMessage {
+                text,
+                priority: message.priority,
+                type_: message.type_,
+                expires_at: Instant::now() + Duration::from_millis(message.duration as u64),
+            },
+        );
+
+        //as mensagens mais importantes ficam em cima e, entre elas, as mais novas vem primeiro
+        self.messages.sort_by(|a, b| b.priority.cmp(&a.priority));
+        self.messages.truncate(MAX_MESSAGES);
+        self.changed = true;
+    }
+
+    #[doc = "remove as mensagens expiradas e retorna true se o texto da tela mudou"]
+    pub fn update(&mut self, now: Instant) -> bool {
+        let len = self.messages.len();
+        self.messages.retain(|m| m.expires_at > now);
+
+        let changed = self.changed || len != self.messages.len();
+        self.changed = false;
+
+        changed
+    }
+
+    pub fn lines(&self) -> Vec<&str> {
+        self.messages.iter().map(|m| m.text.as_str()).collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+}
+
+#[doc = "desenha as linhas em uma imagem RGBA. retorna (pixels, largura, altura)"]
+pub fn rasterize(lines: &[&str]) -> (Vec<u8>, usize, usize) {
+    let char_w = (GLYPH_WIDTH + 1) * FONT_SCALE;
+    let line_h = (GLYPH_HEIGHT + 2) * FONT_SCALE;
+
+    let max_chars = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+
+    let width = max_chars * char_w + PADDING * 2;
+    let height = lines.len() * line_h + PADDING * 2;
+
+    let mut pixels: Vec<u8> = BACKGROUND_COLOR
+        .iter()
+        .copied()
+        .cycle()
+        .take(width * height * 4)
+        .collect();
+
+    for (line_index, line) in lines.iter().enumerate() {
+        for (char_index, c) in line.chars().enumerate() {
+            let glyph = get_glyph(c);
+            let origin_x = PADDING + char_index * char_w;
+            let origin_y = PADDING + line_index * line_h;
+
+            for y in 0..GLYPH_HEIGHT * FONT_SCALE {
+                for x in 0..GLYPH_WIDTH * FONT_SCALE {
+                    if !glyph_pixel(&glyph, x / FONT_SCALE, y / FONT_SCALE) {
+                        continue;
+                    }
+
+                    let index = ((origin_y + y) * width + origin_x + x) * 4;
+                    pixels[index..index + 4].copy_from_slice(&TEXT_COLOR);
+                }
+            }
+        }
+    }
+
+    (pixels, width, height)
+}
+
+/// Exibe as mensagens do core por cima do frame do jogo.
+pub struct Osd {
+    queue: OsdQueue,
+    program: ShaderProgram,
+    texture: GLuint,
+    texture_size: (usize, usize),
+    vao: VertexArray,
+    vbo: GlBuffer,
+    i_pos: GLuint,
+    i_tex_pos: GLuint,
+    gl: Rc<gl::Gl>,
+}
+
+impl Drop for Osd {
+    fn drop(&mut self) {
+        unsafe { self.gl.DeleteTextures(1, &self.texture) }
+    }
+}
+
+impl Osd {
+    pub fn new(gl: Rc<gl::Gl>) -> Result<Osd, ErroHandle> {
+        let vertex_shader_src = "
+        #version 330 core
+        in vec2 i_pos;
+        in vec2 i_tex_pos;
+
+        out vec2 f_t_pos;
+
+        void main() {
+            f_t_pos = i_tex_pos;
+            gl_Position = vec4(i_pos, 0.0, 1.0);
+        }
+        ";
+
+        let fragment_shader_src = "
+        #version 330 core
+        in vec2 f_t_pos;
+
+        out vec4 FragColor;
+
+        uniform sampler2D u_tex;
+
+        void main() {
+            FragColor = texture(u_tex, f_t_pos);
+        }
+        ";
+
+        let vertex_shader = Shader::new(gl::VERTEX_SHADER, vertex_shader_src, gl.clone())?;
+        let frag_shader = Shader::new(gl::FRAGMENT_SHADER, fragment_shader_src, gl.clone())?;
+        let program = ShaderProgram::new(&[vertex_shader, frag_shader], gl.clone())?;
+
+        let i_pos = program.get_attribute("i_pos") as GLuint;
+        let i_tex_pos = program.get_attribute("i_tex_pos") as GLuint;
+
+        let mut texture = 0;
+
+        unsafe {
+            gl.GenTextures(1, &mut texture);
+            gl.BindTexture(gl::TEXTURE_2D, texture);
+            gl.TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::NEAREST as i32);
+            gl.TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::NEAREST as i32);
+            gl.BindTexture(gl::TEXTURE_2D, 0);
+        }
+
+        Ok(Osd {
+            queue: OsdQueue::default(),
+            program,
+            texture,
+            texture_size: (0, 0),
+            vao: VertexArray::new(gl.clone()),
+            vbo: GlBuffer::new(gl::ARRAY_BUFFER, gl.clone()),
+            i_pos,
+            i_tex_pos,
+            gl,
+        })
+    }
+
+    pub fn push(&mut self, message: &RetroMessage) {
+        self.queue.push(message);
+    }
+
+    fn upload_texture(&mut self) {
+        let (pixels, width, height) = rasterize(&self.queue.lines());
+
+        unsafe {
+            self.gl.BindTexture(gl::TEXTURE_2D, self.texture);
+            //o Texture2D do jogo altera o UNPACK_ROW_LENGTH, entao ele precisa ser restaurado aqui
+            self.gl.PixelStorei(gl::UNPACK_ROW_LENGTH, 0);
+            self.gl.TexImage2D(
+                gl::TEXTURE_2D,
+                0,
+                gl::RGBA8 as i32,
+                width as i32,
+                height as i32,
+                0,
+                gl::RGBA,
+                gl::UNSIGNED_BYTE,
+                pixels.as_ptr().cast(),
+            );
+            self.gl.BindTexture(gl::TEXTURE_2D, 0);
+        }
+
+        self.texture_size = (width, height);
+    }
+
+    #[doc = "deve ser chamado depois que o frame do jogo for desenhado e antes do swap da janela"]
+    pub fn draw(&mut self, win_width: i32, win_height: i32) {
+        if self.queue.update(Instant::now()) && !self.queue.is_empty() {
+            self.upload_texture();
+        }
+
+        if self.queue.is_empty() || win_width <= 0 || win_height <= 0 {
+            return;
+        }
+
+        //converte o tamanho da imagem em pixels para as coordenadas do OpenGL (-1 ate 1)
+        let (width, height) = self.texture_size;
+        let left = -1.0 + MARGIN * 2.0 / win_width as f32;
+        let bottom = -1.0 + MARGIN * 2.0 / win_height as f32;
+        let right = left + width as f32 * 2.0 / win_width as f32;
+        let top = bottom + height as f32 * 2.0 / win_height as f32;
+
+        let vertex: [OsdVertex; 4] = [
+            [left, bottom, 0.0, 1.0],
+            [left, top, 0.0, 0.0],
+            [right, bottom, 1.0, 1.0],
+            [right, top, 1.0, 0.0],
+        ];
+
+        self.vao.bind();
+        self.vbo.set_data(vertex);
+        self.vao.set_attribute::<OsdVertex>(self.i_pos, 2, 0);
+        self.vao
+            .set_attribute::<OsdVertex>(self.i_tex_pos, 2, (size_of::<f32>() * 2) as i32);
+
+        unsafe {
+            self.gl.Enable(gl::BLEND);
+            self.gl.BlendFunc(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA);
+
+            self.program.use_program();
+            self.gl.ActiveTexture(gl::TEXTURE0);
+            self.gl.BindTexture(gl::TEXTURE_2D, self.texture);
+
+            self.gl.DrawArrays(gl::TRIANGLE_STRIP, 0, 4);
+
+            self.gl.BindTexture(gl::TEXTURE_2D, 0);
+            self.program.un_use_program();
+            self.gl.Disable(gl::BLEND);
+        }
+
+        self.vao.un_bind();
+        self.vbo.un_bind();
+    }
+}
+
+#[cfg(test)]
+mod test_osd {
+    use super::{rasterize, OsdQueue, BACKGROUND_COLOR, PADDING, TEXT_COLOR};
+    use libretro_sys::binding_libretro::{
+        retro_log_level, retro_message_target, retro_message_type,
+    };
+    use retro_core::message::RetroMessage;
+    use std::time::{Duration, Instant};
+
+    fn message(msg: &str, type_: retro_message_type, progress: i8) -> RetroMessage {
+        RetroMessage {
+            msg: msg.to_string(),
+            duration: 1000,
+            priority: 1,
+            level: retro_log_level::RETRO_LOG_INFO,
+            target: retro_message_target::RETRO_MESSAGE_TARGET_ALL,
+            type_,
+            progress,
+        }
+    }
+
+    #[test]
+    fn queue() {
+        let mut queue = OsdQueue::default();
+
+        queue.push(&message(
+            "Disk 2 inserted",
+            retro_message_type::RETRO_MESSAGE_TYPE_NOTIFICATION,
+            -1,
+        ));
+        queue.push(&message(
+            "Loading",
+            retro_message_type::RETRO_MESSAGE_TYPE_PROGRESS,
+            10,
+        ));
+        queue.push(&message(
+            "Loading",
+            retro_message_type::RETRO_MESSAGE_TYPE_PROGRESS,
+            50,
+        ));
+
+        assert!(queue.update(Instant::now()));
+        assert_eq!(queue.lines(), vec!["Loading 50%", "Disk 2 inserted"]);
+        assert!(!queue.update(Instant::now()));
+
+        assert!(queue.update(Instant::now() + Duration::from_secs(2)));
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn rasterize_text() {
+        let (pixels, width, height) = rasterize(&["!"]);
+
+        assert_eq!(pixels.len(), width * height * 4);
+        assert_eq!(pixels[0..4], BACKGROUND_COLOR);
+
+        //a coluna do meio do '!' começa na linha de cima
+        let index = (PADDING * width + PADDING + 2 * 2) * 4;
+        assert_eq!(pixels[index..index + 4], TEXT_COLOR);
+    }
+}
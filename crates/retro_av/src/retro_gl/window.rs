@@ -1,10 +1,10 @@
-use super::{gl::gl, render::Render};
+use super::{gl::gl, osd::Osd, render::Render};
 use crate::video::{RawTextureData, RetroVideoAPi};
 use generics::erro_handle::ErroHandle;
 use libretro_sys::binding_libretro::retro_hw_context_type::{
     RETRO_HW_CONTEXT_NONE, RETRO_HW_CONTEXT_OPENGL, RETRO_HW_CONTEXT_OPENGL_CORE,
 };
-use retro_core::av_info::AvInfo;
+use retro_core::{av_info::AvInfo, message::RetroMessage};
 use sdl2::video::FullScreenType;
 use sdl2::{
     video::{GLContext, GLProfile, Window},
@@ -18,6 +18,7 @@ pub struct GlWindow {
     window: Window,
     gl_ctx: Option<GLContext>,
     render: Render,
+    osd: Osd,
     av_info: Arc<AvInfo>,
 }
 
@@ -44,7 +45,7 @@ impl RetroVideoAPi for GlWindow {
         self.window.id()
     }
 
-    fn draw_new_frame(&self, texture: &UnsafeCell<RawTextureData>) {
+    fn draw_new_frame(&mut self, texture: &UnsafeCell<RawTextureData>) {
         let (width, height) = self.window.size();
 
         self.render.draw_new_frame(
@@ -54,9 +55,16 @@ impl RetroVideoAPi for GlWindow {
             height as i32,
         );
 
+        //as mensagens ficam por cima do frame do jogo
+        self.osd.draw(width as i32, height as i32);
+
         self.window.gl_swap_window();
     }
 
+    fn show_message(&mut self, message: &RetroMessage) {
+        self.osd.push(message);
+    }
+
     fn resize(&mut self, (width, height): (u32, u32)) {
         self.window.set_size(width, height).unwrap();
     }
@@ -151,12 +159,14 @@ impl GlWindow {
                 }
 
                 let render = Render::new(av_info, gl.clone())?;
+                let osd = Osd::new(gl.clone())?;
 
                 Ok(GlWindow {
                     video,
                     window,
                     gl_ctx: Some(gl_ctx),
                     render,
+                    osd,
                     av_info: av_info.clone(),
                 })
             }
@@ -4,6 +4,7 @@ use std::time::{Duration, Instant};
 #[derive(Default)]
 pub struct RetroSync {
     last_time: Option<Instant>,
+    last_present: Option<Instant>,
 }
 
 impl RetroSync {
@@ -34,4 +35,19 @@ impl RetroSync {
 
         false
     }
+
+    #[doc = "usado quando a velocidade nao tem limite. retorna true quando ja e hora de exibir um novo
+    frame, os outros sao pulados para que a exibição continue no fps do core"]
+    pub fn present(&mut self, fps: f64) -> bool {
+        let now = Instant::now();
+        let core_default_duration = Duration::from_millis(self.fps_to_millis(fps) as u64);
+
+        match self.last_present {
+            Some(last_present) if now - last_present < core_default_duration => false,
+            _ => {
+                self.last_present = Some(now);
+                true
+            }
+        }
+    }
 }
@@ -6,7 +6,7 @@ use generics::{
 use libretro_sys::binding_libretro::retro_hw_context_type::{
     RETRO_HW_CONTEXT_NONE, RETRO_HW_CONTEXT_OPENGL, RETRO_HW_CONTEXT_OPENGL_CORE,
 };
-use retro_core::{av_info::AvInfo, RetroVideoEnvCallbacks};
+use retro_core::{av_info::AvInfo, message::RetroMessage, RetroVideoEnvCallbacks};
 use sdl2::Sdl;
 use std::{
     cell::UnsafeCell,
@@ -26,7 +26,10 @@ pub struct RawTextureData {
 pub trait RetroVideoAPi {
     fn get_window_id(&self) -> u32;
 
-    fn draw_new_frame(&self, texture: &UnsafeCell<RawTextureData>);
+    fn draw_new_frame(&mut self, texture: &UnsafeCell<RawTextureData>);
+
+    #[doc = "exibe uma mensagem do core por cima do jogo"]
+    fn show_message(&mut self, message: &RetroMessage);
 
     #[doc = "define um novo tamanho para a janela.
         ```
@@ -97,6 +100,16 @@ impl RetroVideo {
         Ok(())
     }
 
+    pub fn show_message(&self, message: &RetroMessage) -> Result<(), ErroHandle> {
+        let mut window = self.window_ctx.try_load()?;
+
+        if let Some(window) = &mut *window {
+            window.show_message(message);
+        }
+
+        Ok(())
+    }
+
     pub fn get_window_id(&self) -> Result<u32, ErroHandle> {
         let mut window = self.window_ctx.try_load()?;
 
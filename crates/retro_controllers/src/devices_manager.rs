@@ -69,6 +69,9 @@ pub trait DeviceListener: Debug + Send {
     fn connected(&self, device: Device);
     fn disconnected(&self, device: Device);
     fn button_pressed(&self, button: String, device: Device);
+    #[doc = "o core mudou o estado de um LED, como as luzes dos botões de um fliperama.
+    *led* e o índice definido pelo core e *state* geralmente e 0 (apagado) ou 1 (aceso)"]
+    fn led_state_changed(&self, _led: i32, _state: i32) {}
 }
 
 impl DevicesManager {
@@ -123,6 +126,19 @@ impl DevicesManager {
         0
     }
 
+    pub fn get_right_stick(&self, port: i16) -> Option<(f32, f32)> {
+        self.connected_gamepads
+            .load_or(Vec::new())
+            .iter()
+            .find(|gamepad| gamepad.retro_port == port)
+            .map(|gamepad| gamepad.right_stick)
+    }
+
+    pub fn notify_led_state(&self, led: i32, state: i32) -> Result<(), ErroHandle> {
+        self.listener.try_load()?.led_state_changed(led, state);
+        Ok(())
+    }
+
     pub fn apply_rumble(&self, rubble: DeviceRubble) -> bool {
         println!("{:?}", rubble);
         true
@@ -0,0 +1,79 @@
+use crate::devices_manager::DevicesManager;
+use generics::erro_handle::ErroHandle;
+use libretro_sys::binding_libretro::{
+    retro_sensor_action, RETRO_SENSOR_ACCELEROMETER_X, RETRO_SENSOR_ACCELEROMETER_Y,
+    RETRO_SENSOR_ACCELEROMETER_Z, RETRO_SENSOR_GYROSCOPE_Z,
+};
+use retro_core::sensor::{sensor_action_group, sensor_group, SensorProvider, STANDARD_GRAVITY};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+#[doc = "velocidade de rotação em rad/s informada quando o analógico esta todo para um lado"]
+const MAX_GYRO_SPEED: f32 = 4.0;
+
+/// Simula os sensores de movimento com o analógico direito do gamepad conectado a porta.
+///
+/// O gilrs nao expõe o acelerômetro nem o giroscópio dos controles, então inclinar o analógico
+/// equivale a inclinar o aparelho: o eixo X controla o acelerômetro X e o giroscópio Z e o
+/// eixo Y controla o acelerômetro Y. A iluminação nao e suportada.
+#[derive(Debug)]
+pub struct GamepadTiltSensor {
+    manager: Arc<DevicesManager>,
+    enabled: [AtomicBool; 2],
+}
+
+impl GamepadTiltSensor {
+    pub fn new(manager: Arc<DevicesManager>) -> Self {
+        Self {
+            manager,
+            enabled: [AtomicBool::new(false), AtomicBool::new(false)],
+        }
+    }
+}
+
+impl SensorProvider for GamepadTiltSensor {
+    fn set_sensor_state(
+        &self,
+        _port: u32,
+        action: retro_sensor_action,
+        _rate: u32,
+    ) -> Result<bool, ErroHandle> {
+        match sensor_action_group(action) {
+            Some((group, enabled)) if group < self.enabled.len() => {
+                self.enabled[group].store(enabled, Ordering::SeqCst);
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    fn get_sensor_input(&self, port: u32, id: u32) -> Result<f32, ErroHandle> {
+        let enabled = sensor_group(id)
+            .and_then(|group| self.enabled.get(group))
+            .is_some_and(|enabled| enabled.load(Ordering::SeqCst));
+
+        if !enabled {
+            return Ok(0.0);
+        }
+
+        let (x, y) = match self.manager.get_right_stick(port as i16) {
+            Some(stick) => stick,
+            None => return Ok(0.0),
+        };
+
+        let value = match id {
+            RETRO_SENSOR_ACCELEROMETER_X => x * STANDARD_GRAVITY,
+            RETRO_SENSOR_ACCELEROMETER_Y => y * STANDARD_GRAVITY,
+            //a gravidade se divide entre os eixos conforme o aparelho e inclinado
+            RETRO_SENSOR_ACCELEROMETER_Z => {
+                (1.0 - (x * x + y * y).min(1.0)).sqrt() * STANDARD_GRAVITY
+            }
+            RETRO_SENSOR_GYROSCOPE_Z => x * MAX_GYRO_SPEED,
+            _ => 0.0,
+        };
+
+        Ok(value)
+    }
+}
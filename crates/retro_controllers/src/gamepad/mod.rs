@@ -1,3 +1,4 @@
 pub mod gamepad_key_map;
+pub mod gamepad_sensor;
 pub mod retro_gamepad;
 mod update_gamepad_state_handle;
@@ -4,7 +4,7 @@ use super::{
 };
 use crate::devices_manager::{DeviceStateListener, DevicesRequiredFunctions};
 use generics::{erro_handle::ErroHandle, types::ArcTMuxte};
-use gilrs::{Event, GamepadId, Gilrs};
+use gilrs::{Axis, Event, GamepadId, Gilrs};
 use std::sync::{atomic::AtomicUsize, Arc};
 use uuid::Uuid;
 
@@ -23,6 +23,8 @@ pub struct RetroGamePad {
     #[doc = "padrão RETRO_DEVICE_JOYPAD"]
     pub retro_type: u32,
     pub key_map: Vec<GamepadKeyMap>,
+    #[doc = "posição do analógico direito (x, y) de -1.0 a 1.0, usada para simular a inclinação do aparelho"]
+    pub right_stick: (f32, f32),
 }
 
 impl RetroGamePad {
@@ -39,6 +41,7 @@ impl RetroGamePad {
             retro_port,
             retro_type,
             key_map: GamepadKeyMap::get_default_key_maps(),
+            right_stick: (0.0, 0.0),
         }
     }
 
@@ -48,6 +51,11 @@ impl RetroGamePad {
         for key_info in &mut self.key_map {
             key_info.pressed = gamepad.is_pressed(key_info.native);
         }
+
+        self.right_stick = (
+            gamepad.value(Axis::RightStickX),
+            gamepad.value(Axis::RightStickY),
+        );
     }
 
     pub fn update(
@@ -7,5 +7,6 @@ mod retro_controller;
 mod state_thread;
 
 pub use gamepad::gamepad_key_map::GamepadKeyMap;
+pub use gamepad::gamepad_sensor::GamepadTiltSensor;
 pub mod devices_manager;
 pub use retro_controller::{RetroController, RetroControllerCb};
@@ -1,7 +1,8 @@
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 
 use crate::devices_manager::{DeviceListener, DeviceRubble, DevicesManager};
-use crate::gamepad::retro_gamepad::RetroGamePad;
+use crate::gamepad::{gamepad_sensor::GamepadTiltSensor, retro_gamepad::RetroGamePad};
 use crate::state_thread::EventThread;
 use generics::erro_handle::ErroHandle;
 use libretro_sys::binding_libretro::retro_rumble_effect;
@@ -60,11 +61,19 @@ impl RetroController {
     pub fn get_core_cb(&self) -> RetroControllerCb {
         RetroControllerCb {
             manager: self.manager.clone(),
+            led_states: Mutex::new(HashMap::new()),
         }
     }
+
+    #[doc = "sensores de movimento simulados pelo analógico direito de cada gamepad"]
+    pub fn get_sensor_cb(&self) -> GamepadTiltSensor {
+        GamepadTiltSensor::new(self.manager.clone())
+    }
 }
 pub struct RetroControllerCb {
     manager: Arc<DevicesManager>,
+    #[doc = "ultimo estado enviado pelo core para cada LED"]
+    led_states: Mutex<HashMap<i32, i32>>,
 }
 
 impl RetroControllerEnvCallbacks for RetroControllerCb {
@@ -95,4 +104,13 @@ impl RetroControllerEnvCallbacks for RetroControllerCb {
             strength,
         }))
     }
+
+    //o listener so e avisado quando o estado do LED mudar
+    fn set_led_state(&self, led: i32, state: i32) -> Result<(), ErroHandle> {
+        if self.led_states.lock()?.insert(led, state) == Some(state) {
+            return Ok(());
+        }
+
+        self.manager.notify_led_state(led, state)
+    }
 }
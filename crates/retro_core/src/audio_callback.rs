@@ -0,0 +1,98 @@
+use generics::erro_handle::ErroHandle;
+use libretro_sys::binding_libretro::{
+    retro_audio_buffer_status_callback, retro_audio_buffer_status_callback_t, retro_audio_callback,
+};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    RwLock,
+};
+
+#[doc = "estado do buffer de áudio do frontend que e informado ao core"]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct AudioBufferStatus {
+    #[doc = "false quando nao ha nenhuma saída de áudio"]
+    pub active: bool,
+    #[doc = "de 0 (vazio) a 100 (cheio)"]
+    pub occupancy: u32,
+    pub underrun_likely: bool,
+}
+
+/// Callbacks enviadas pelo core em RETRO_ENVIRONMENT_SET_AUDIO_CALLBACK e
+/// RETRO_ENVIRONMENT_SET_AUDIO_BUFFER_STATUS_CALLBACK.
+///
+/// Cores que usam essa callback geram o áudio fora do retro_run, sempre que o frontend pedir com
+/// [AudioCallback::run].
+#[derive(Debug, Default)]
+pub struct AudioCallback {
+    callback: RwLock<Option<retro_audio_callback>>,
+    buffer_status: RwLock<retro_audio_buffer_status_callback_t>,
+    enabled: AtomicBool,
+}
+
+impl AudioCallback {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_callback(&self, raw_cb: &retro_audio_callback) -> Result<(), ErroHandle> {
+        self.callback.write()?.replace(*raw_cb);
+        self.enabled.store(false, Ordering::SeqCst);
+
+        Ok(())
+    }
+
+    #[doc = "o core pode enviar NULL para parar de receber o estado do buffer"]
+    pub fn set_buffer_status_callback(
+        &self,
+        raw_cb: Option<&retro_audio_buffer_status_callback>,
+    ) -> Result<(), ErroHandle> {
+        *self.buffer_status.write()? = raw_cb.and_then(|cb| cb.callback);
+
+        Ok(())
+    }
+
+    pub fn is_supported(&self) -> bool {
+        self.callback
+            .read()
+            .is_ok_and(|cb| cb.is_some_and(|cb| cb.callback.is_some()))
+    }
+
+    #[doc = "avisa o core que a saída de áudio foi iniciada ou pausada. so chama o core se o estado mudar"]
+    pub fn set_state(&self, enabled: bool) -> Result<(), ErroHandle> {
+        let set_state = match &*self.callback.read()? {
+            Some(cb) => cb.set_state,
+            None => return Ok(()),
+        };
+
+        if self.enabled.swap(enabled, Ordering::SeqCst) == enabled {
+            return Ok(());
+        }
+
+        if let Some(set_state) = set_state {
+            unsafe { set_state(enabled) }
+        }
+
+        Ok(())
+    }
+
+    #[doc = "pede ao core para gerar mais áudio. nada acontece enquanto o áudio estiver pausado"]
+    pub fn run(&self) -> Result<(), ErroHandle> {
+        if !self.enabled.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        if let Some(Some(callback)) = self.callback.read()?.map(|cb| cb.callback) {
+            unsafe { callback() }
+        }
+
+        Ok(())
+    }
+
+    pub fn report_buffer_status(&self, status: AudioBufferStatus) -> Result<(), ErroHandle> {
+        if let Some(callback) = *self.buffer_status.read()? {
+            unsafe { callback(status.active, status.occupancy, status.underrun_likely) }
+        }
+
+        Ok(())
+    }
+}
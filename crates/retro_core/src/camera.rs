@@ -0,0 +1,286 @@
+use generics::erro_handle::ErroHandle;
+use libretro_sys::binding_libretro::{
+    retro_camera_buffer, retro_camera_callback, retro_camera_frame_raw_framebuffer_t,
+    retro_camera_lifetime_status_t,
+};
+use std::{
+    fmt::Debug,
+    mem,
+    sync::{
+        atomic::{AtomicBool, AtomicU32, Ordering},
+        RwLock,
+    },
+};
+
+#[doc = "resolução usada quando o core nao informa o tamanho que deseja"]
+pub const DEFAULT_CAMERA_WIDTH: u32 = 320;
+pub const DEFAULT_CAMERA_HEIGHT: u32 = 240;
+
+#[doc = "quadro da câmera no formato XRGB8888, com o primeiro pixel no canto superior esquerdo"]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CameraFrame {
+    pub width: u32,
+    pub height: u32,
+    pub pixels: Vec<u32>,
+}
+
+impl CameraFrame {
+    pub fn new(width: u32, height: u32, pixels: Vec<u32>) -> Result<Self, ErroHandle> {
+        if pixels.len() != (width * height) as usize {
+            return Err(ErroHandle::new(
+                "O tamanho do quadro da câmera nao corresponde a sua resolução",
+            ));
+        }
+
+        Ok(Self {
+            width,
+            height,
+            pixels,
+        })
+    }
+
+    #[doc = "redimensiona o quadro usando o pixel mais próximo"]
+    pub fn resize(&self, width: u32, height: u32) -> CameraFrame {
+        if self.width == width && self.height == height {
+            return self.clone();
+        }
+
+        if self.pixels.is_empty() {
+            return CameraFrame {
+                width,
+                height,
+                pixels: vec![0; (width * height) as usize],
+            };
+        }
+
+        let mut pixels = Vec::with_capacity((width * height) as usize);
+
+        for y in 0..height {
+            let src_y = (y as u64 * self.height as u64 / height as u64) as u32;
+
+            for x in 0..width {
+                let src_x = (x as u64 * self.width as u64 / width as u64) as u32;
+                pixels.push(self.pixels[(src_y * self.width + src_x) as usize]);
+            }
+        }
+
+        CameraFrame {
+            width,
+            height,
+            pixels,
+        }
+    }
+}
+
+/// Fonte das imagens enviadas aos cores que usam RETRO_ENVIRONMENT_GET_CAMERA_INTERFACE,
+/// como o Game Boy Camera.
+pub trait CameraSource: Debug {
+    #[doc = "chamada quando o core liga a câmera. retorne false se ela nao estiver disponível"]
+    fn start(&self) -> Result<bool, ErroHandle>;
+
+    fn stop(&self) -> Result<(), ErroHandle>;
+
+    #[doc = "próximo quadro, de preferencia com o tamanho pedido pelo core. chamada antes de cada
+    retro_run enquanto a câmera estiver ligada. None quando nao houver um quadro novo"]
+    fn next_frame(&self, width: u32, height: u32) -> Result<Option<CameraFrame>, ErroHandle>;
+}
+
+/// Barras coloridas fixas, usadas para testar os cores sem uma câmera de verdade.
+#[derive(Debug, Default)]
+pub struct TestPatternCamera;
+
+//branco, amarelo, ciano, verde, magenta, vermelho, azul e preto
+const TEST_PATTERN_COLORS: [u32; 8] = [
+    0xFFFFFF, 0xFFFF00, 0x00FFFF, 0x00FF00, 0xFF00FF, 0xFF0000, 0x0000FF, 0x000000,
+];
+
+impl TestPatternCamera {
+    pub fn new() -> Self {
+        Self
+    }
+
+    pub fn create_frame(width: u32, height: u32) -> CameraFrame {
+        let bar_count = TEST_PATTERN_COLORS.len() as u32;
+        let mut pixels = Vec::with_capacity((width * height) as usize);
+
+        for _ in 0..height {
+            for x in 0..width {
+                let bar = (x * bar_count / width.max(1)).min(bar_count - 1);
+                pixels.push(TEST_PATTERN_COLORS[bar as usize]);
+            }
+        }
+
+        CameraFrame {
+            width,
+            height,
+            pixels,
+        }
+    }
+}
+
+impl CameraSource for TestPatternCamera {
+    fn start(&self) -> Result<bool, ErroHandle> {
+        Ok(true)
+    }
+
+    fn stop(&self) -> Result<(), ErroHandle> {
+        Ok(())
+    }
+
+    fn next_frame(&self, width: u32, height: u32) -> Result<Option<CameraFrame>, ErroHandle> {
+        Ok(Some(Self::create_frame(width, height)))
+    }
+}
+
+/// Callbacks e resolução enviadas pelo core em RETRO_ENVIRONMENT_GET_CAMERA_INTERFACE.
+///
+/// Os frames da [CameraSource] so sao enviados ao core enquanto ele mantiver a câmera ligada.
+#[derive(Debug, Default)]
+pub struct Camera {
+    frame_raw_framebuffer: RwLock<retro_camera_frame_raw_framebuffer_t>,
+    initialized: RwLock<retro_camera_lifetime_status_t>,
+    deinitialized: RwLock<retro_camera_lifetime_status_t>,
+    width: AtomicU32,
+    height: AtomicU32,
+    #[doc = "o core ligou a câmera"]
+    running: AtomicBool,
+    active: AtomicBool,
+}
+
+impl Camera {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_callbacks(&self, raw_cb: &retro_camera_callback) -> Result<(), ErroHandle> {
+        let raw_framebuffer = 1 << retro_camera_buffer::RETRO_CAMERA_BUFFER_RAW_FRAMEBUFFER as u64;
+
+        //as texturas OpenGL ainda nao sao suportadas
+        if raw_cb.caps & raw_framebuffer == 0 {
+            return Err(ErroHandle::new(
+                "O core so aceita quadros da câmera como texturas OpenGL",
+            ));
+        }
+
+        *self.frame_raw_framebuffer.write()? = raw_cb.frame_raw_framebuffer;
+        *self.initialized.write()? = raw_cb.initialized;
+        *self.deinitialized.write()? = raw_cb.deinitialized;
+
+        let (width, height) = match (raw_cb.width, raw_cb.height) {
+            (0, _) | (_, 0) => (DEFAULT_CAMERA_WIDTH, DEFAULT_CAMERA_HEIGHT),
+            size => size,
+        };
+
+        self.width.store(width, Ordering::SeqCst);
+        self.height.store(height, Ordering::SeqCst);
+
+        Ok(())
+    }
+
+    pub fn get_size(&self) -> (u32, u32) {
+        (
+            self.width.load(Ordering::SeqCst),
+            self.height.load(Ordering::SeqCst),
+        )
+    }
+
+    pub fn start(&self, source: &dyn CameraSource) -> Result<bool, ErroHandle> {
+        let started = source.start()?;
+        self.running.store(started, Ordering::SeqCst);
+
+        Ok(started)
+    }
+
+    pub fn stop(&self, source: &dyn CameraSource) -> Result<(), ErroHandle> {
+        self.running.store(false, Ordering::SeqCst);
+        source.stop()
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+
+    #[doc = "envia ao core o próximo quadro da fonte, com a resolução que ele pediu"]
+    pub fn push_frame(&self, source: &dyn CameraSource) -> Result<(), ErroHandle> {
+        if !self.is_running() {
+            return Ok(());
+        }
+
+        let frame_raw_framebuffer = match *self.frame_raw_framebuffer.read()? {
+            Some(callback) => callback,
+            None => return Ok(()),
+        };
+
+        let (width, height) = self.get_size();
+
+        let frame = match source.next_frame(width, height)? {
+            Some(frame) => frame.resize(width, height),
+            None => return Ok(()),
+        };
+
+        unsafe {
+            frame_raw_framebuffer(
+                frame.pixels.as_ptr(),
+                frame.width,
+                frame.height,
+                frame.width as usize * mem::size_of::<u32>(),
+            )
+        }
+
+        Ok(())
+    }
+
+    #[doc = "avisa o core que a câmera ja pode ser ligada. so chama o core uma vez"]
+    pub fn notify_initialized(&self) -> Result<(), ErroHandle> {
+        if self.active.swap(true, Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        if let Some(initialized) = *self.initialized.read()? {
+            unsafe { initialized() }
+        }
+
+        Ok(())
+    }
+
+    pub fn notify_deinitialized(&self, source: &dyn CameraSource) -> Result<(), ErroHandle> {
+        if !self.active.swap(false, Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        if self.is_running() {
+            self.stop(source)?;
+        }
+
+        if let Some(deinitialized) = *self.deinitialized.read()? {
+            unsafe { deinitialized() }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_camera {
+    use super::{CameraFrame, TestPatternCamera};
+
+    #[test]
+    fn test_pattern() {
+        let frame = TestPatternCamera::create_frame(16, 2);
+
+        assert_eq!(frame.pixels.len(), 32);
+        assert_eq!(frame.pixels[0], 0xFFFFFF);
+        assert_eq!(frame.pixels[15], 0x000000);
+        //as duas linhas sao iguais
+        assert_eq!(frame.pixels[0..16], frame.pixels[16..32]);
+    }
+
+    #[test]
+    fn resize_frame() {
+        let frame = CameraFrame::new(2, 1, vec![1, 2]).unwrap();
+        let resized = frame.resize(4, 2);
+
+        assert_eq!(resized.pixels, vec![1, 1, 2, 2, 1, 1, 2, 2]);
+        assert!(CameraFrame::new(2, 2, vec![1]).is_err());
+    }
+}
@@ -0,0 +1,60 @@
+use super::get_core_ctx;
+use crate::RetroCore;
+use libretro_sys::binding_libretro::{
+    retro_camera_callback, RETRO_ENVIRONMENT_GET_CAMERA_INTERFACE,
+};
+use std::{ffi::c_uint, os::raw::c_void};
+
+unsafe extern "C" fn camera_start() -> bool {
+    match get_core_ctx() {
+        Some(core_ctx) => match &core_ctx.callbacks.camera {
+            Some(source) => match core_ctx.camera.start(source.as_ref()) {
+                Ok(started) => started,
+                Err(e) => {
+                    println!("camera_start -> {:?}", e);
+                    false
+                }
+            },
+            None => false,
+        },
+        None => false,
+    }
+}
+
+unsafe extern "C" fn camera_stop() {
+    if let Some(core_ctx) = get_core_ctx() {
+        if let Some(source) = &core_ctx.callbacks.camera {
+            if let Err(e) = core_ctx.camera.stop(source.as_ref()) {
+                println!("camera_stop -> {:?}", e);
+            }
+        }
+    }
+}
+
+pub unsafe fn env_cb_camera(core_ctx: &RetroCore, cmd: c_uint, data: *mut c_void) -> bool {
+    match cmd {
+        RETRO_ENVIRONMENT_GET_CAMERA_INTERFACE => {
+            #[cfg(feature = "core_ev_logs")]
+            println!("RETRO_ENVIRONMENT_GET_CAMERA_INTERFACE -> ok");
+
+            if core_ctx.callbacks.camera.is_none() || data.is_null() {
+                return false;
+            }
+
+            let camera = &mut *(data as *mut retro_camera_callback);
+
+            match core_ctx.camera.set_callbacks(camera) {
+                Ok(_) => {
+                    camera.start = Some(camera_start);
+                    camera.stop = Some(camera_stop);
+                    true
+                }
+                Err(e) => {
+                    println!("RETRO_ENVIRONMENT_GET_CAMERA_INTERFACE -> {:?}", e);
+                    false
+                }
+            }
+        }
+        _ => false,
+    }
+}
@@ -1,16 +1,16 @@
-use crate::{tools::ffi_tools::make_c_string, RetroCoreIns};
+use crate::{tools::ffi_tools::make_c_string, RetroCore};
 use generics::constants::MAX_CORE_SUBSYSTEM_INFO;
 use libretro_sys::{
     binding_libretro::{
         retro_subsystem_info, RETRO_ENVIRONMENT_GET_CORE_ASSETS_DIRECTORY,
         RETRO_ENVIRONMENT_GET_SAVE_DIRECTORY, RETRO_ENVIRONMENT_GET_SYSTEM_DIRECTORY,
-        RETRO_ENVIRONMENT_GET_VFS_INTERFACE, RETRO_ENVIRONMENT_SET_SUBSYSTEM_INFO,
+        RETRO_ENVIRONMENT_SET_SUBSYSTEM_INFO,
     },
     binding_log_interface,
 };
 use std::{ffi::c_uint, os::raw::c_void};
 
-pub unsafe fn env_cb_directory(core_ctx: &RetroCoreIns, cmd: c_uint, data: *mut c_void) -> bool {
+pub unsafe fn env_cb_directory(core_ctx: &RetroCore, cmd: c_uint, data: *mut c_void) -> bool {
     match cmd {
         RETRO_ENVIRONMENT_GET_SYSTEM_DIRECTORY => {
             #[cfg(feature = "core_ev_logs")]
@@ -51,12 +51,6 @@ pub unsafe fn env_cb_directory(core_ctx: &RetroCoreIns, cmd: c_uint, data: *mut
 
             true
         }
-        RETRO_ENVIRONMENT_GET_VFS_INTERFACE => {
-            #[cfg(feature = "core_ev_logs")]
-            println!("RETRO_ENVIRONMENT_GET_VFS_INTERFACE -> OK");
-
-            true
-        }
         _ => false,
     }
 }
@@ -0,0 +1,51 @@
+use crate::RetroCore;
+use libretro_sys::binding_libretro::{
+    retro_disk_control_callback, retro_disk_control_ext_callback,
+    RETRO_ENVIRONMENT_GET_DISK_CONTROL_INTERFACE_VERSION,
+    RETRO_ENVIRONMENT_SET_DISK_CONTROL_EXT_INTERFACE, RETRO_ENVIRONMENT_SET_DISK_CONTROL_INTERFACE,
+};
+use std::{ffi::c_uint, os::raw::c_void};
+
+pub unsafe fn env_cb_disk(core_ctx: &RetroCore, cmd: c_uint, data: *mut c_void) -> bool {
+    match cmd {
+        RETRO_ENVIRONMENT_GET_DISK_CONTROL_INTERFACE_VERSION => {
+            #[cfg(feature = "core_ev_logs")]
+            println!("RETRO_ENVIRONMENT_GET_DISK_CONTROL_INTERFACE_VERSION -> ok");
+
+            if data.is_null() {
+                return false;
+            }
+
+            *(data as *mut c_uint) = 1;
+
+            true
+        }
+        RETRO_ENVIRONMENT_SET_DISK_CONTROL_INTERFACE => {
+            #[cfg(feature = "core_ev_logs")]
+            println!("RETRO_ENVIRONMENT_SET_DISK_CONTROL_INTERFACE -> ok");
+
+            if data.is_null() {
+                return false;
+            }
+
+            core_ctx
+                .disk_control
+                .set_callbacks(&*(data as *const retro_disk_control_callback))
+                .is_ok()
+        }
+        RETRO_ENVIRONMENT_SET_DISK_CONTROL_EXT_INTERFACE => {
+            #[cfg(feature = "core_ev_logs")]
+            println!("RETRO_ENVIRONMENT_SET_DISK_CONTROL_EXT_INTERFACE -> ok");
+
+            if data.is_null() {
+                return false;
+            }
+
+            core_ctx
+                .disk_control
+                .set_ext_callbacks(&*(data as *const retro_disk_control_ext_callback))
+                .is_ok()
+        }
+        _ => false,
+    }
+}
@@ -1,21 +1,25 @@
 use crate::{
-    core_env::environment::CORE_CONTEXT,
+    core_env::environment::get_core_ctx,
     generics::constants::MAX_CORE_CONTROLLER_INFO_TYPES,
     libretro_sys::binding_libretro::{
-        retro_controller_info, retro_rumble_effect, retro_rumble_interface,
-        RETRO_ENVIRONMENT_GET_INPUT_BITMASKS, RETRO_ENVIRONMENT_GET_RUMBLE_INTERFACE,
+        retro_controller_info, retro_input_descriptor, retro_led_interface, retro_rumble_effect,
+        retro_rumble_interface, RETRO_ENVIRONMENT_GET_INPUT_BITMASKS,
+        RETRO_ENVIRONMENT_GET_LED_INTERFACE, RETRO_ENVIRONMENT_GET_RUMBLE_INTERFACE,
         RETRO_ENVIRONMENT_SET_CONTROLLER_INFO, RETRO_ENVIRONMENT_SET_INPUT_DESCRIPTORS,
     },
-    RetroCoreIns,
+    RetroCore,
+};
+use std::{
+    ffi::{c_int, c_uint},
+    os::raw::c_void,
 };
-use std::{ffi::c_uint, os::raw::c_void, ptr::addr_of};
 
 unsafe extern "C" fn rumble_callback(
     port: c_uint,
     effect: retro_rumble_effect,
     strength: u16,
 ) -> bool {
-    let retro_core = match &*addr_of!(CORE_CONTEXT) {
+    let retro_core = match get_core_ctx() {
         Some(core_ctx) => core_ctx,
         None => return false,
     };
@@ -35,8 +39,17 @@ unsafe extern "C" fn rumble_callback(
     }
 }
 
+//os cores costumam enviar o estado dos LEDs a cada frame, então um erro aqui nao encerra o jogo
+unsafe extern "C" fn set_led_state(led: c_int, state: c_int) {
+    if let Some(core_ctx) = get_core_ctx() {
+        if let Err(e) = core_ctx.callbacks.controller.set_led_state(led, state) {
+            println!("set_led_state -> {:?}", e);
+        }
+    }
+}
+
 pub unsafe extern "C" fn input_poll_callback() {
-    let retro_core = match &*addr_of!(CORE_CONTEXT) {
+    let retro_core = match get_core_ctx() {
         Some(core_ctx) => core_ctx,
         None => return,
     };
@@ -53,17 +66,20 @@ pub unsafe extern "C" fn input_state_callback(
     index: c_uint,
     id: c_uint,
 ) -> i16 {
-    let retro_core = match &*addr_of!(CORE_CONTEXT) {
+    let retro_core = match get_core_ctx() {
         Some(core_ctx) => core_ctx,
         None => return 0,
     };
 
-    let res = retro_core.callbacks.controller.input_state_callback(
-        port as i16,
-        device as i16,
-        index as i16,
-        id as i16,
-    );
+    let (port, device, index, id) = (port as i16, device as i16, index as i16, id as i16);
+
+    //durante a reprodução de um filme os inputs do jogador sao ignorados
+    let res = retro_core.movie.input_state(port, device, index, id, || {
+        retro_core
+            .callbacks
+            .controller
+            .input_state_callback(port, device, index, id)
+    });
 
     match res {
         Ok(v) => v,
@@ -75,7 +91,7 @@ pub unsafe extern "C" fn input_state_callback(
     }
 }
 
-pub unsafe fn env_cb_gamepad_io(core_ctx: &RetroCoreIns, cmd: c_uint, data: *mut c_void) -> bool {
+pub unsafe fn env_cb_gamepad_io(core_ctx: &RetroCore, cmd: c_uint, data: *mut c_void) -> bool {
     match cmd {
         RETRO_ENVIRONMENT_GET_INPUT_BITMASKS => {
             #[cfg(feature = "core_ev_logs")]
@@ -96,7 +112,17 @@ pub unsafe fn env_cb_gamepad_io(core_ctx: &RetroCoreIns, cmd: c_uint, data: *mut
         RETRO_ENVIRONMENT_SET_INPUT_DESCRIPTORS => {
             #[cfg(feature = "core_ev_logs")]
             println!("RETRO_ENVIRONMENT_SET_INPUT_DESCRIPTORS -> ok");
-            false
+
+            match core_ctx
+                .system
+                .get_input_descriptors(data as *const retro_input_descriptor)
+            {
+                Ok(_) => true,
+                Err(e) => {
+                    println!("{:?}", e);
+                    false
+                }
+            }
         }
         RETRO_ENVIRONMENT_GET_RUMBLE_INTERFACE => {
             #[cfg(feature = "core_ev_logs")]
@@ -107,6 +133,19 @@ pub unsafe fn env_cb_gamepad_io(core_ctx: &RetroCoreIns, cmd: c_uint, data: *mut
 
             true
         }
+        RETRO_ENVIRONMENT_GET_LED_INTERFACE => {
+            #[cfg(feature = "core_ev_logs")]
+            println!("RETRO_ENVIRONMENT_GET_LED_INTERFACE -> ok");
+
+            if data.is_null() {
+                return false;
+            }
+
+            let led = &mut *(data as *mut retro_led_interface);
+            led.set_led_state = Some(set_led_state);
+
+            true
+        }
 
         _ => false,
     }
@@ -0,0 +1,102 @@
+use super::get_core_ctx;
+use crate::{location::LocationProvider, RetroCore};
+use libretro_sys::binding_libretro::{
+    retro_location_callback, RETRO_ENVIRONMENT_GET_LOCATION_INTERFACE,
+};
+use std::{ffi::c_uint, os::raw::c_void};
+
+fn with_location<R>(or: R, f: impl FnOnce(&dyn LocationProvider) -> R) -> R {
+    match get_core_ctx() {
+        Some(core_ctx) => match &core_ctx.callbacks.location {
+            Some(location) => f(location.as_ref()),
+            None => or,
+        },
+        None => or,
+    }
+}
+
+unsafe extern "C" fn location_start() -> bool {
+    with_location(false, |location| {
+        location.start().unwrap_or_else(|e| {
+            println!("location_start -> {:?}", e);
+            false
+        })
+    })
+}
+
+unsafe extern "C" fn location_stop() {
+    with_location((), |location| {
+        if let Err(e) = location.stop() {
+            println!("location_stop -> {:?}", e);
+        }
+    })
+}
+
+unsafe extern "C" fn location_get_position(
+    lat: *mut f64,
+    lon: *mut f64,
+    horiz_accuracy: *mut f64,
+    vert_accuracy: *mut f64,
+) -> bool {
+    let position = with_location(None, |location| {
+        location.get_position().unwrap_or_else(|e| {
+            println!("location_get_position -> {:?}", e);
+            None
+        })
+    });
+
+    let found = position.is_some();
+
+    //a libretro pede que todos os valores sejam 0 quando nao houver uma posição
+    let position = position.unwrap_or_default();
+
+    for (ptr, value) in [
+        (lat, position.latitude),
+        (lon, position.longitude),
+        (horiz_accuracy, position.horizontal_accuracy),
+        (vert_accuracy, position.vertical_accuracy),
+    ] {
+        if let Some(ptr) = ptr.as_mut() {
+            *ptr = value;
+        }
+    }
+
+    found
+}
+
+unsafe extern "C" fn location_set_interval(interval_ms: c_uint, interval_distance: c_uint) {
+    with_location((), |location| {
+        if let Err(e) = location.set_interval(interval_ms, interval_distance) {
+            println!("location_set_interval -> {:?}", e);
+        }
+    })
+}
+
+pub unsafe fn env_cb_location(core_ctx: &RetroCore, cmd: c_uint, data: *mut c_void) -> bool {
+    match cmd {
+        RETRO_ENVIRONMENT_GET_LOCATION_INTERFACE => {
+            #[cfg(feature = "core_ev_logs")]
+            println!("RETRO_ENVIRONMENT_GET_LOCATION_INTERFACE -> ok");
+
+            if core_ctx.callbacks.location.is_none() || data.is_null() {
+                return false;
+            }
+
+            let location = &mut *(data as *mut retro_location_callback);
+            location.start = Some(location_start);
+            location.stop = Some(location_stop);
+            location.get_position = Some(location_get_position);
+            location.set_interval = Some(location_set_interval);
+
+            //initialized e deinitialized sao enviadas pelo core
+            match core_ctx.location.set_callbacks(location) {
+                Ok(_) => true,
+                Err(e) => {
+                    println!("RETRO_ENVIRONMENT_GET_LOCATION_INTERFACE -> {:?}", e);
+                    false
+                }
+            }
+        }
+        _ => false,
+    }
+}
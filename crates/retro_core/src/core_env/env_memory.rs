@@ -0,0 +1,24 @@
+use crate::RetroCore;
+use libretro_sys::binding_libretro::{retro_memory_map, RETRO_ENVIRONMENT_SET_MEMORY_MAPS};
+use std::{ffi::c_uint, os::raw::c_void};
+
+pub unsafe fn env_cb_memory(core_ctx: &RetroCore, cmd: c_uint, data: *mut c_void) -> bool {
+    match cmd {
+        RETRO_ENVIRONMENT_SET_MEMORY_MAPS => {
+            #[cfg(feature = "core_ev_logs")]
+            println!("RETRO_ENVIRONMENT_SET_MEMORY_MAPS -> ok");
+
+            match core_ctx
+                .memory
+                .set_memory_maps(data as *const retro_memory_map)
+            {
+                Ok(_) => true,
+                Err(e) => {
+                    println!("{:?}", e);
+                    false
+                }
+            }
+        }
+        _ => false,
+    }
+}
@@ -0,0 +1,55 @@
+use crate::{message::RetroMessage, RetroCore};
+use libretro_sys::binding_libretro::{
+    retro_message, retro_message_ext, RETRO_ENVIRONMENT_GET_MESSAGE_INTERFACE_VERSION,
+    RETRO_ENVIRONMENT_SET_MESSAGE, RETRO_ENVIRONMENT_SET_MESSAGE_EXT,
+};
+use std::{ffi::c_uint, os::raw::c_void};
+
+pub unsafe fn env_cb_message(core_ctx: &RetroCore, cmd: c_uint, data: *mut c_void) -> bool {
+    match cmd {
+        RETRO_ENVIRONMENT_GET_MESSAGE_INTERFACE_VERSION => {
+            #[cfg(feature = "core_ev_logs")]
+            println!("RETRO_ENVIRONMENT_GET_MESSAGE_INTERFACE_VERSION -> ok");
+
+            *(data as *mut c_uint) = 1;
+
+            true
+        }
+        RETRO_ENVIRONMENT_SET_MESSAGE => {
+            #[cfg(feature = "core_ev_logs")]
+            println!("RETRO_ENVIRONMENT_SET_MESSAGE -> ok");
+
+            if data.is_null() {
+                return false;
+            }
+
+            let fps = core_ctx.av_info.timing.fps.read().map_or(0.0, |fps| *fps);
+            let message = RetroMessage::from_raw(&*(data as *const retro_message), fps);
+
+            send_message(core_ctx, message)
+        }
+        RETRO_ENVIRONMENT_SET_MESSAGE_EXT => {
+            #[cfg(feature = "core_ev_logs")]
+            println!("RETRO_ENVIRONMENT_SET_MESSAGE_EXT -> ok");
+
+            if data.is_null() {
+                return false;
+            }
+
+            let message = RetroMessage::from_raw_ext(&*(data as *const retro_message_ext));
+
+            send_message(core_ctx, message)
+        }
+        _ => false,
+    }
+}
+
+fn send_message(core_ctx: &RetroCore, message: RetroMessage) -> bool {
+    match core_ctx.callbacks.message.message_callback(message) {
+        Ok(_) => true,
+        Err(e) => {
+            println!("message_callback -> {:?}", e);
+            false
+        }
+    }
+}
@@ -1,12 +1,14 @@
 use libretro_sys::{
     binding_libretro::{
-        retro_core_option_display, retro_core_options_v2_intl, retro_variable,
-        RETRO_ENVIRONMENT_GET_CORE_OPTIONS_VERSION, RETRO_ENVIRONMENT_GET_VARIABLE,
-        RETRO_ENVIRONMENT_GET_VARIABLE_UPDATE, RETRO_ENVIRONMENT_SET_CORE_OPTIONS_DISPLAY,
+        retro_core_option_definition, retro_core_option_display, retro_core_options_intl,
+        retro_core_options_update_display_callback, retro_core_options_v2,
+        retro_core_options_v2_intl, retro_variable, RETRO_ENVIRONMENT_GET_CORE_OPTIONS_VERSION,
+        RETRO_ENVIRONMENT_GET_VARIABLE, RETRO_ENVIRONMENT_GET_VARIABLE_UPDATE,
+        RETRO_ENVIRONMENT_SET_CORE_OPTIONS, RETRO_ENVIRONMENT_SET_CORE_OPTIONS_DISPLAY,
         RETRO_ENVIRONMENT_SET_CORE_OPTIONS_INTL,
         RETRO_ENVIRONMENT_SET_CORE_OPTIONS_UPDATE_DISPLAY_CALLBACK,
-        RETRO_ENVIRONMENT_SET_CORE_OPTIONS_V2_INTL, RETRO_ENVIRONMENT_SET_VARIABLE,
-        RETRO_ENVIRONMENT_SET_VARIABLES,
+        RETRO_ENVIRONMENT_SET_CORE_OPTIONS_V2, RETRO_ENVIRONMENT_SET_CORE_OPTIONS_V2_INTL,
+        RETRO_ENVIRONMENT_SET_VARIABLE, RETRO_ENVIRONMENT_SET_VARIABLES,
     },
     binding_log_interface,
 };
@@ -14,28 +16,69 @@ use std::{ffi::c_uint, os::raw::c_void, sync::atomic::Ordering};
 
 use crate::{
     tools::ffi_tools::{get_str_from_ptr, make_c_string},
-    RetroCoreIns,
+    RetroCore,
 };
 
-pub unsafe fn env_cb_option(core_ctx: &RetroCoreIns, cmd: c_uint, data: *mut c_void) -> bool {
+pub unsafe fn env_cb_option(core_ctx: &RetroCore, cmd: c_uint, data: *mut c_void) -> bool {
     match cmd {
         RETRO_ENVIRONMENT_GET_CORE_OPTIONS_VERSION => {
             #[cfg(feature = "core_ev_logs")]
             println!("RETRO_ENVIRONMENT_GET_CORE_OPTIONS_VERSION -> ok");
 
+            //todos os formatos (v0, v1 e v2) sao convertidos para o mesmo modelo do OptionManager
             *(data as *mut u32) = 2;
 
             true
         }
+        RETRO_ENVIRONMENT_SET_CORE_OPTIONS => {
+            #[cfg(feature = "core_ev_logs")]
+            println!("RETRO_ENVIRONMENT_SET_CORE_OPTIONS -> ok");
+
+            let _ = core_ctx
+                .options
+                .convert_option_v1(data as *const retro_core_option_definition);
+            let _ = core_ctx.options.try_reload_pref_option();
+
+            true
+        }
         RETRO_ENVIRONMENT_SET_CORE_OPTIONS_INTL => {
-            println!("RETRO_ENVIRONMENT_SET_CORE_OPTIONS_INTL");
+            #[cfg(feature = "core_ev_logs")]
+            println!("RETRO_ENVIRONMENT_SET_CORE_OPTIONS_INTL -> ok");
+
+            if data.is_null() {
+                return false;
+            }
+
+            let option_intl = *(data as *mut retro_core_options_intl);
+
+            let _ = core_ctx.options.convert_option_v1_intl(option_intl);
+            let _ = core_ctx.options.try_reload_pref_option();
+
+            true
+        }
+        RETRO_ENVIRONMENT_SET_CORE_OPTIONS_V2 => {
+            #[cfg(feature = "core_ev_logs")]
+            println!("RETRO_ENVIRONMENT_SET_CORE_OPTIONS_V2 -> ok");
+
+            if data.is_null() {
+                return false;
+            }
+
+            let option_v2 = *(data as *mut retro_core_options_v2);
+
+            let _ = core_ctx.options.convert_option_v2(option_v2);
+            let _ = core_ctx.options.try_reload_pref_option();
 
-            false
+            true
         }
         RETRO_ENVIRONMENT_SET_CORE_OPTIONS_V2_INTL => {
             #[cfg(feature = "core_ev_logs")]
             println!("RETRO_ENVIRONMENT_SET_CORE_OPTIONS_V2_INTL -> ok");
 
+            if data.is_null() {
+                return false;
+            }
+
             let option_intl_v2 = *(data as *mut retro_core_options_v2_intl);
 
             let _ = core_ctx.options.convert_option_v2_intl(option_intl_v2);
@@ -47,6 +90,10 @@ pub unsafe fn env_cb_option(core_ctx: &RetroCoreIns, cmd: c_uint, data: *mut c_v
             #[cfg(feature = "core_ev_logs")]
             println!("RETRO_ENVIRONMENT_SET_CORE_OPTIONS_DISPLAY -> ok");
 
+            if data.is_null() {
+                return false;
+            }
+
             let option = *(data as *mut retro_core_option_display);
 
             let _ = core_ctx
@@ -57,8 +104,16 @@ pub unsafe fn env_cb_option(core_ctx: &RetroCoreIns, cmd: c_uint, data: *mut c_v
         }
         RETRO_ENVIRONMENT_SET_CORE_OPTIONS_UPDATE_DISPLAY_CALLBACK => {
             #[cfg(feature = "core_ev_logs")]
-            println!("RETRO_ENVIRONMENT_SET_CORE_OPTIONS_UPDATE_DISPLAY_CALLBACK -> need");
-            false
+            println!("RETRO_ENVIRONMENT_SET_CORE_OPTIONS_UPDATE_DISPLAY_CALLBACK -> ok");
+
+            //o core pode enviar null para remover a callback
+            let callback = if data.is_null() {
+                None
+            } else {
+                (*(data as *const retro_core_options_update_display_callback)).callback
+            };
+
+            core_ctx.options.set_display_callback(callback).is_ok()
         }
         RETRO_ENVIRONMENT_GET_VARIABLE_UPDATE => {
             #[cfg(feature = "core_ev_logs")]
@@ -72,13 +127,37 @@ pub unsafe fn env_cb_option(core_ctx: &RetroCoreIns, cmd: c_uint, data: *mut c_v
         }
         RETRO_ENVIRONMENT_SET_VARIABLES => {
             #[cfg(feature = "core_ev_logs")]
-            println!("RETRO_ENVIRONMENT_SET_VARIABLES -> needed");
-            false
+            println!("RETRO_ENVIRONMENT_SET_VARIABLES -> ok");
+
+            let _ = core_ctx
+                .options
+                .convert_variables(data as *const retro_variable);
+            let _ = core_ctx.options.try_reload_pref_option();
+
+            true
         }
         RETRO_ENVIRONMENT_SET_VARIABLE => {
             #[cfg(feature = "core_ev_logs")]
-            println!("RETRO_ENVIRONMENT_SET_VARIABLE -> needed");
-            false
+            println!("RETRO_ENVIRONMENT_SET_VARIABLE -> ok");
+
+            //o core envia null apenas para saber se essa função e suportada
+            if data.is_null() {
+                return true;
+            }
+
+            let raw_variable = *(data as *const retro_variable);
+
+            if raw_variable.key.is_null() || raw_variable.value.is_null() {
+                return false;
+            }
+
+            core_ctx
+                .options
+                .update_opt(
+                    &get_str_from_ptr(raw_variable.key),
+                    &get_str_from_ptr(raw_variable.value),
+                )
+                .is_ok()
         }
         RETRO_ENVIRONMENT_GET_VARIABLE => {
             #[cfg(feature = "core_ev_logs")]
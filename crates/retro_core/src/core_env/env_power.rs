@@ -0,0 +1,34 @@
+use crate::RetroCore;
+use libretro_sys::binding_libretro::{retro_device_power, RETRO_ENVIRONMENT_GET_DEVICE_POWER};
+use std::{ffi::c_uint, os::raw::c_void};
+
+pub unsafe fn env_cb_power(core_ctx: &RetroCore, cmd: c_uint, data: *mut c_void) -> bool {
+    match cmd {
+        RETRO_ENVIRONMENT_GET_DEVICE_POWER => {
+            #[cfg(feature = "core_ev_logs")]
+            println!("RETRO_ENVIRONMENT_GET_DEVICE_POWER -> ok");
+
+            let provider = match &core_ctx.callbacks.power {
+                Some(provider) => provider,
+                None => return false,
+            };
+
+            let power = match provider.get_power() {
+                Ok(Some(power)) => power,
+                Ok(None) => return false,
+                Err(e) => {
+                    println!("RETRO_ENVIRONMENT_GET_DEVICE_POWER -> {:?}", e);
+                    return false;
+                }
+            };
+
+            //o core pode enviar NULL para saber se o frontend suporta essa função
+            if !data.is_null() {
+                *(data as *mut retro_device_power) = power.to_raw();
+            }
+
+            true
+        }
+        _ => false,
+    }
+}
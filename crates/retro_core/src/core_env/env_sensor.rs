@@ -0,0 +1,63 @@
+use super::get_core_ctx;
+use crate::RetroCore;
+use libretro_sys::binding_libretro::{
+    retro_sensor_action, retro_sensor_interface, RETRO_ENVIRONMENT_GET_SENSOR_INTERFACE,
+};
+use std::{ffi::c_uint, os::raw::c_void};
+
+unsafe extern "C" fn set_sensor_state(
+    port: c_uint,
+    action: retro_sensor_action,
+    rate: c_uint,
+) -> bool {
+    match get_core_ctx() {
+        Some(core_ctx) => match &core_ctx.callbacks.sensor {
+            Some(sensor) => match sensor.set_sensor_state(port, action, rate) {
+                Ok(supported) => supported,
+                Err(e) => {
+                    println!("set_sensor_state -> {:?}", e);
+                    false
+                }
+            },
+            None => false,
+        },
+        None => false,
+    }
+}
+
+unsafe extern "C" fn get_sensor_input(port: c_uint, id: c_uint) -> f32 {
+    match get_core_ctx() {
+        Some(core_ctx) => match &core_ctx.callbacks.sensor {
+            Some(sensor) => match sensor.get_sensor_input(port, id) {
+                Ok(value) => value,
+                Err(e) => {
+                    println!("get_sensor_input -> {:?}", e);
+                    0.0
+                }
+            },
+            None => 0.0,
+        },
+        None => 0.0,
+    }
+}
+
+pub unsafe fn env_cb_sensor(core_ctx: &RetroCore, cmd: c_uint, data: *mut c_void) -> bool {
+    match cmd {
+        RETRO_ENVIRONMENT_GET_SENSOR_INTERFACE => {
+            #[cfg(feature = "core_ev_logs")]
+            println!("RETRO_ENVIRONMENT_GET_SENSOR_INTERFACE -> ok");
+
+            //sem um provedor o core deve usar os controles no lugar dos sensores
+            if core_ctx.callbacks.sensor.is_none() || data.is_null() {
+                return false;
+            }
+
+            let sensor = &mut *(data as *mut retro_sensor_interface);
+            sensor.set_sensor_state = Some(set_sensor_state);
+            sensor.get_sensor_input = Some(get_sensor_input);
+
+            true
+        }
+        _ => false,
+    }
+}
@@ -0,0 +1,112 @@
+use crate::RetroCore;
+use libretro_sys::binding_libretro::{
+    retro_audio_buffer_status_callback, retro_audio_callback, retro_frame_time_callback,
+    retro_throttle_state, RETRO_ENVIRONMENT_GET_FASTFORWARDING,
+    RETRO_ENVIRONMENT_GET_TARGET_REFRESH_RATE, RETRO_ENVIRONMENT_GET_THROTTLE_STATE,
+    RETRO_ENVIRONMENT_SET_AUDIO_BUFFER_STATUS_CALLBACK, RETRO_ENVIRONMENT_SET_AUDIO_CALLBACK,
+    RETRO_ENVIRONMENT_SET_FRAME_TIME_CALLBACK,
+};
+use std::{ffi::c_uint, os::raw::c_void};
+
+pub unsafe fn env_cb_timing(core_ctx: &RetroCore, cmd: c_uint, data: *mut c_void) -> bool {
+    match cmd {
+        RETRO_ENVIRONMENT_SET_FRAME_TIME_CALLBACK => {
+            #[cfg(feature = "core_ev_logs")]
+            println!("RETRO_ENVIRONMENT_SET_FRAME_TIME_CALLBACK -> ok");
+
+            if data.is_null() {
+                return false;
+            }
+
+            core_ctx
+                .frame_time
+                .set_callback(&*(data as *const retro_frame_time_callback))
+                .is_ok()
+        }
+        RETRO_ENVIRONMENT_SET_AUDIO_CALLBACK => {
+            #[cfg(feature = "core_ev_logs")]
+            println!("RETRO_ENVIRONMENT_SET_AUDIO_CALLBACK -> ok");
+
+            if data.is_null() {
+                return false;
+            }
+
+            core_ctx
+                .audio_callback
+                .set_callback(&*(data as *const retro_audio_callback))
+                .is_ok()
+        }
+        RETRO_ENVIRONMENT_SET_AUDIO_BUFFER_STATUS_CALLBACK => {
+            #[cfg(feature = "core_ev_logs")]
+            println!("RETRO_ENVIRONMENT_SET_AUDIO_BUFFER_STATUS_CALLBACK -> ok");
+
+            //data nulo desativa a callback
+            let raw_cb = (data as *const retro_audio_buffer_status_callback).as_ref();
+
+            core_ctx
+                .audio_callback
+                .set_buffer_status_callback(raw_cb)
+                .is_ok()
+        }
+        RETRO_ENVIRONMENT_GET_FASTFORWARDING => {
+            #[cfg(feature = "core_ev_logs")]
+            println!("RETRO_ENVIRONMENT_GET_FASTFORWARDING -> ok");
+
+            if data.is_null() {
+                return true;
+            }
+
+            match core_ctx.speed.get_speed() {
+                Ok(speed) => {
+                    *(data as *mut bool) = speed.is_fast_forward();
+                    true
+                }
+                Err(_) => false,
+            }
+        }
+        RETRO_ENVIRONMENT_GET_TARGET_REFRESH_RATE => {
+            #[cfg(feature = "core_ev_logs")]
+            println!("RETRO_ENVIRONMENT_GET_TARGET_REFRESH_RATE -> ok");
+
+            if data.is_null() {
+                return true;
+            }
+
+            let fps = match core_ctx.av_info.timing.fps.read() {
+                Ok(fps) => fps.abs(),
+                Err(_) => return false,
+            };
+
+            //sem limite de velocidade os frames continuam sendo exibidos no fps do core
+            match core_ctx.speed.get_speed() {
+                Ok(speed) => {
+                    *(data as *mut f32) = speed.get_target_fps(fps).unwrap_or(fps) as f32;
+                    true
+                }
+                Err(_) => false,
+            }
+        }
+        RETRO_ENVIRONMENT_GET_THROTTLE_STATE => {
+            #[cfg(feature = "core_ev_logs")]
+            println!("RETRO_ENVIRONMENT_GET_THROTTLE_STATE -> ok");
+
+            if data.is_null() {
+                return true;
+            }
+
+            let fps = match core_ctx.av_info.timing.fps.read() {
+                Ok(fps) => fps.abs(),
+                Err(_) => return false,
+            };
+
+            match core_ctx.speed.get_throttle_state(fps) {
+                Ok(state) => {
+                    *(data as *mut retro_throttle_state) = state;
+                    true
+                }
+                Err(_) => false,
+            }
+        }
+        _ => false,
+    }
+}
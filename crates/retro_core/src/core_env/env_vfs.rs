@@ -0,0 +1,384 @@
+use super::environment::get_core_ctx;
+use crate::{
+    vfs::{RetroVfs, RetroVfsFile, VfsDirEntry, VfsOpenMode},
+    RetroCore,
+};
+use libretro_sys::binding_libretro::{
+    retro_vfs_dir_handle, retro_vfs_file_handle, retro_vfs_interface, retro_vfs_interface_info,
+    RETRO_ENVIRONMENT_GET_VFS_INTERFACE, RETRO_VFS_FILE_ACCESS_READ,
+    RETRO_VFS_FILE_ACCESS_UPDATE_EXISTING, RETRO_VFS_FILE_ACCESS_WRITE,
+    RETRO_VFS_SEEK_POSITION_CURRENT, RETRO_VFS_SEEK_POSITION_END, RETRO_VFS_SEEK_POSITION_START,
+    RETRO_VFS_STAT_IS_DIRECTORY, RETRO_VFS_STAT_IS_VALID,
+};
+use std::{
+    ffi::{c_char, c_int, c_uint, CStr, CString},
+    io::{ErrorKind, Read, Seek, SeekFrom, Write},
+    os::raw::c_void,
+    path::PathBuf,
+    ptr::{null, null_mut, slice_from_raw_parts, slice_from_raw_parts_mut},
+    sync::Arc,
+};
+
+const VFS_INTERFACE_VERSION: u32 = 3;
+
+static VFS_INTERFACE: retro_vfs_interface = retro_vfs_interface {
+    get_path: Some(vfs_get_path),
+    open: Some(vfs_open),
+    close: Some(vfs_close),
+    size: Some(vfs_size),
+    tell: Some(vfs_tell),
+    seek: Some(vfs_seek),
+    read: Some(vfs_read),
+    write: Some(vfs_write),
+    flush: Some(vfs_flush),
+    remove: Some(vfs_remove),
+    rename: Some(vfs_rename),
+    truncate: Some(vfs_truncate),
+    stat: Some(vfs_stat),
+    mkdir: Some(vfs_mkdir),
+    opendir: Some(vfs_opendir),
+    readdir: Some(vfs_readdir),
+    dirent_get_name: Some(vfs_dirent_get_name),
+    dirent_is_dir: Some(vfs_dirent_is_dir),
+    closedir: Some(vfs_closedir),
+};
+
+//o core so enxerga esses dois tipos como ponteiros opacos
+struct VfsFileHandle {
+    path: CString,
+    file: Box<dyn RetroVfsFile>,
+}
+
+struct VfsDirHandle {
+    entries: Vec<VfsDirEntry>,
+    next: usize,
+    current: Option<(CString, bool)>,
+}
+
+pub unsafe fn env_cb_vfs(core_ctx: &RetroCore, cmd: c_uint, data: *mut c_void) -> bool {
+    match cmd {
+        RETRO_ENVIRONMENT_GET_VFS_INTERFACE => {
+            #[cfg(feature = "core_ev_logs")]
+            println!("RETRO_ENVIRONMENT_GET_VFS_INTERFACE -> ok");
+
+            //sem um backend o core deve usar as suas próprias funções de arquivo
+            if data.is_null() || core_ctx.callbacks.vfs.is_none() {
+                return false;
+            }
+
+            let info = &mut *(data as *mut retro_vfs_interface_info);
+
+            if info.required_interface_version > VFS_INTERFACE_VERSION {
+                return false;
+            }
+
+            info.required_interface_version = VFS_INTERFACE_VERSION;
+            info.iface = &VFS_INTERFACE as *const retro_vfs_interface as *mut retro_vfs_interface;
+
+            true
+        }
+        _ => false,
+    }
+}
+
+fn path_from_ptr(path: *const c_char) -> Option<PathBuf> {
+    if path.is_null() {
+        return None;
+    }
+
+    let path = unsafe { CStr::from_ptr(path) };
+
+    Some(PathBuf::from(path.to_string_lossy().to_string()))
+}
+
+//o core pode acessar os arquivos de qualquer thread (como as threads que carregam a rom), então
+//o vfs e clonado para fora do contexto e a operação nao segura o core enquanto acessa o disco
+fn get_vfs() -> Option<Arc<dyn RetroVfs>> {
+    get_core_ctx().and_then(|core_ctx| core_ctx.callbacks.vfs.clone())
+}
+
+unsafe fn get_file<'a>(stream: *mut retro_vfs_file_handle) -> Option<&'a mut VfsFileHandle> {
+    (stream as *mut VfsFileHandle).as_mut()
+}
+
+unsafe fn get_dir<'a>(dir_stream: *mut retro_vfs_dir_handle) -> Option<&'a mut VfsDirHandle> {
+    (dir_stream as *mut VfsDirHandle).as_mut()
+}
+
+unsafe extern "C" fn vfs_get_path(stream: *mut retro_vfs_file_handle) -> *const c_char {
+    match get_file(stream) {
+        Some(handle) => handle.path.as_ptr(),
+        None => null(),
+    }
+}
+
+unsafe extern "C" fn vfs_open(
+    path: *const c_char,
+    mode: c_uint,
+    _hints: c_uint,
+) -> *mut retro_vfs_file_handle {
+    let (vfs, path_buf) = match (get_vfs(), path_from_ptr(path)) {
+        (Some(vfs), Some(path_buf)) => (vfs, path_buf),
+        _ => return null_mut(),
+    };
+
+    let open_mode = VfsOpenMode {
+        read: mode & RETRO_VFS_FILE_ACCESS_READ != 0,
+        write: mode & RETRO_VFS_FILE_ACCESS_WRITE != 0,
+        update_existing: mode & RETRO_VFS_FILE_ACCESS_UPDATE_EXISTING != 0,
+    };
+
+    #[cfg(feature = "core_ev_logs")]
+    println!("vfs_open -> {:?} {:?}", path_buf, open_mode);
+
+    match vfs.open(&path_buf, open_mode) {
+        Ok(file) => {
+            let handle = Box::new(VfsFileHandle {
+                path: CStr::from_ptr(path).to_owned(),
+                file,
+            });
+
+            Box::into_raw(handle) as *mut retro_vfs_file_handle
+        }
+        Err(_e) => {
+            #[cfg(feature = "core_ev_logs")]
+            println!("vfs_open -> {:?}", _e);
+
+            null_mut()
+        }
+    }
+}
+
+unsafe extern "C" fn vfs_close(stream: *mut retro_vfs_file_handle) -> c_int {
+    if stream.is_null() {
+        return -1;
+    }
+
+    let mut handle = Box::from_raw(stream as *mut VfsFileHandle);
+
+    match handle.file.flush() {
+        Ok(_) => 0,
+        Err(_) => -1,
+    }
+}
+
+unsafe extern "C" fn vfs_size(stream: *mut retro_vfs_file_handle) -> i64 {
+    match get_file(stream).map(|handle| handle.file.size()) {
+        Some(Ok(size)) => size as i64,
+        _ => -1,
+    }
+}
+
+unsafe extern "C" fn vfs_truncate(stream: *mut retro_vfs_file_handle, length: i64) -> i64 {
+    if length < 0 {
+        return -1;
+    }
+
+    match get_file(stream).map(|handle| handle.file.truncate(length as u64)) {
+        Some(Ok(_)) => 0,
+        _ => -1,
+    }
+}
+
+unsafe extern "C" fn vfs_tell(stream: *mut retro_vfs_file_handle) -> i64 {
+    match get_file(stream).map(|handle| handle.file.stream_position()) {
+        Some(Ok(pos)) => pos as i64,
+        _ => -1,
+    }
+}
+
+unsafe extern "C" fn vfs_seek(
+    stream: *mut retro_vfs_file_handle,
+    offset: i64,
+    seek_position: c_int,
+) -> i64 {
+    let seek_from = match seek_position as u32 {
+        RETRO_VFS_SEEK_POSITION_START if offset >= 0 => SeekFrom::Start(offset as u64),
+        RETRO_VFS_SEEK_POSITION_CURRENT => SeekFrom::Current(offset),
+        RETRO_VFS_SEEK_POSITION_END => SeekFrom::End(offset),
+        _ => return -1,
+    };
+
+    match get_file(stream).map(|handle| handle.file.seek(seek_from)) {
+        Some(Ok(pos)) => pos as i64,
+        _ => -1,
+    }
+}
+
+unsafe extern "C" fn vfs_read(stream: *mut retro_vfs_file_handle, s: *mut c_void, len: u64) -> i64 {
+    let handle = match get_file(stream) {
+        Some(handle) if !s.is_null() => handle,
+        _ => return -1,
+    };
+
+    let buf = &mut *slice_from_raw_parts_mut(s as *mut u8, len as usize);
+    let mut total = 0;
+
+    //assim como o fread, so para quando o buffer estiver cheio ou o arquivo acabar
+    while total < buf.len() {
+        match handle.file.read(&mut buf[total..]) {
+            Ok(0) => break,
+            Ok(read) => total += read,
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(_) => return -1,
+        }
+    }
+
+    total as i64
+}
+
+unsafe extern "C" fn vfs_write(
+    stream: *mut retro_vfs_file_handle,
+    s: *const c_void,
+    len: u64,
+) -> i64 {
+    let handle = match get_file(stream) {
+        Some(handle) if !s.is_null() => handle,
+        _ => return -1,
+    };
+
+    let buf = &*slice_from_raw_parts(s as *const u8, len as usize);
+
+    match handle.file.write_all(buf) {
+        Ok(_) => len as i64,
+        Err(_) => -1,
+    }
+}
+
+unsafe extern "C" fn vfs_flush(stream: *mut retro_vfs_file_handle) -> c_int {
+    match get_file(stream).map(|handle| handle.file.flush()) {
+        Some(Ok(_)) => 0,
+        _ => -1,
+    }
+}
+
+unsafe extern "C" fn vfs_remove(path: *const c_char) -> c_int {
+    let (vfs, path) = match (get_vfs(), path_from_ptr(path)) {
+        (Some(vfs), Some(path)) => (vfs, path),
+        _ => return -1,
+    };
+
+    match vfs.remove(&path) {
+        Ok(_) => 0,
+        _ => -1,
+    }
+}
+
+unsafe extern "C" fn vfs_rename(old_path: *const c_char, new_path: *const c_char) -> c_int {
+    let (vfs, old_path, new_path) =
+        match (get_vfs(), path_from_ptr(old_path), path_from_ptr(new_path)) {
+            (Some(vfs), Some(old_path), Some(new_path)) => (vfs, old_path, new_path),
+            _ => return -1,
+        };
+
+    match vfs.rename(&old_path, &new_path) {
+        Ok(_) => 0,
+        _ => -1,
+    }
+}
+
+unsafe extern "C" fn vfs_stat(path: *const c_char, size: *mut i32) -> c_int {
+    let (vfs, path) = match (get_vfs(), path_from_ptr(path)) {
+        (Some(vfs), Some(path)) => (vfs, path),
+        _ => return 0,
+    };
+
+    let stat = match vfs.stat(&path) {
+        Some(stat) => stat,
+        None => return 0,
+    };
+
+    if !size.is_null() {
+        *size = stat.size.min(i32::MAX as u64) as i32;
+    }
+
+    let mut flags = RETRO_VFS_STAT_IS_VALID;
+
+    if stat.is_dir {
+        flags |= RETRO_VFS_STAT_IS_DIRECTORY;
+    }
+
+    flags as c_int
+}
+
+unsafe extern "C" fn vfs_mkdir(dir: *const c_char) -> c_int {
+    let (vfs, dir) = match (get_vfs(), path_from_ptr(dir)) {
+        (Some(vfs), Some(dir)) => (vfs, dir),
+        _ => return -1,
+    };
+
+    match vfs.mkdir(&dir) {
+        Ok(_) => 0,
+        Err(e) if e.kind() == ErrorKind::AlreadyExists => -2,
+        _ => -1,
+    }
+}
+
+unsafe extern "C" fn vfs_opendir(
+    dir: *const c_char,
+    include_hidden: bool,
+) -> *mut retro_vfs_dir_handle {
+    let (vfs, dir) = match (get_vfs(), path_from_ptr(dir)) {
+        (Some(vfs), Some(dir)) => (vfs, dir),
+        _ => return null_mut(),
+    };
+
+    match vfs.read_dir(&dir, include_hidden) {
+        Ok(entries) => {
+            let handle = Box::new(VfsDirHandle {
+                entries,
+                next: 0,
+                current: None,
+            });
+
+            Box::into_raw(handle) as *mut retro_vfs_dir_handle
+        }
+        _ => null_mut(),
+    }
+}
+
+unsafe extern "C" fn vfs_readdir(dir_stream: *mut retro_vfs_dir_handle) -> bool {
+    let handle = match get_dir(dir_stream) {
+        Some(handle) => handle,
+        None => return false,
+    };
+
+    let entry = match handle.entries.get(handle.next) {
+        Some(entry) => entry,
+        None => {
+            handle.current = None;
+            return false;
+        }
+    };
+
+    handle.current = CString::new(entry.name.clone())
+        .ok()
+        .map(|name| (name, entry.is_dir));
+    handle.next += 1;
+
+    handle.current.is_some()
+}
+
+unsafe extern "C" fn vfs_dirent_get_name(dir_stream: *mut retro_vfs_dir_handle) -> *const c_char {
+    match get_dir(dir_stream).and_then(|handle| handle.current.as_ref()) {
+        Some((name, _)) => name.as_ptr(),
+        None => null(),
+    }
+}
+
+unsafe extern "C" fn vfs_dirent_is_dir(dir_stream: *mut retro_vfs_dir_handle) -> bool {
+    match get_dir(dir_stream).and_then(|handle| handle.current.as_ref()) {
+        Some((_, is_dir)) => *is_dir,
+        None => false,
+    }
+}
+
+unsafe extern "C" fn vfs_closedir(dir_stream: *mut retro_vfs_dir_handle) -> c_int {
+    if dir_stream.is_null() {
+        return -1;
+    }
+
+    drop(Box::from_raw(dir_stream as *mut VfsDirHandle));
+
+    0
+}
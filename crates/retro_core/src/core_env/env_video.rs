@@ -12,19 +12,20 @@ use crate::{
         retro_game_geometry, retro_pixel_format, RETRO_ENVIRONMENT_GET_AUDIO_VIDEO_ENABLE,
         RETRO_ENVIRONMENT_SET_GEOMETRY, RETRO_ENVIRONMENT_SET_PIXEL_FORMAT,
     },
-    RetroCoreIns,
+    RetroCore,
 };
+use std::ffi::{c_uint, c_void};
 #[cfg(feature = "hw")]
 use std::{ffi::c_char, mem};
-use std::{
-    ffi::{c_uint, c_void},
-    ptr::addr_of,
-};
 
-use super::environment::CORE_CONTEXT;
+use super::environment::get_core_ctx;
 
 pub unsafe extern "C" fn audio_sample_callback(left: i16, right: i16) {
-    if let Some(core_ctx) = &*addr_of!(CORE_CONTEXT) {
+    if let Some(core_ctx) = get_core_ctx() {
+        if !core_ctx.output.is_audio_enabled() {
+            return;
+        }
+
         if let Err(e) = core_ctx.callbacks.audio.audio_sample_callback(left, right) {
             println!("{:?}", e);
             let _ = core_ctx.de_init();
@@ -33,7 +34,12 @@ pub unsafe extern "C" fn audio_sample_callback(left: i16, right: i16) {
 }
 
 pub unsafe extern "C" fn audio_sample_batch_callback(data: *const i16, frames: usize) -> usize {
-    if let Some(core_ctx) = &*addr_of!(CORE_CONTEXT) {
+    if let Some(core_ctx) = get_core_ctx() {
+        //o áudio dos frames escondidos do run-ahead e descartado
+        if !core_ctx.output.is_audio_enabled() {
+            return frames;
+        }
+
         let res = core_ctx
             .callbacks
             .audio
@@ -58,7 +64,11 @@ pub unsafe extern "C" fn video_refresh_callback(
     height: c_uint,
     pitch: usize,
 ) {
-    if let Some(core_ctx) = &*addr_of!(CORE_CONTEXT) {
+    if let Some(core_ctx) = get_core_ctx() {
+        if !core_ctx.output.is_video_enabled() {
+            return;
+        }
+
         if let Err(e) = core_ctx
             .callbacks
             .video
@@ -73,7 +83,7 @@ pub unsafe extern "C" fn video_refresh_callback(
 #[cfg(feature = "hw")]
 unsafe extern "C" fn get_current_frame_buffer() -> usize {
     println!("get_current_frame_buffer");
-    match &*addr_of!(CORE_CONTEXT) {
+    match get_core_ctx() {
         Some(core_ctx) => core_ctx
             .av_info
             .video
@@ -92,7 +102,7 @@ unsafe extern "C" fn get_proc_address(sym: *const c_char) -> retro_proc_address_
     use crate::tools::ffi_tools::get_str_from_ptr;
 
     println!("get_proc_address");
-    match &*addr_of!(CORE_CONTEXT) {
+    match get_core_ctx() {
         Some(core_ctx) => {
             let fc_name = get_str_from_ptr(sym);
 
@@ -122,7 +132,7 @@ unsafe extern "C" fn get_proc_address(sym: *const c_char) -> retro_proc_address_
 #[cfg(feature = "hw")]
 unsafe extern "C" fn context_reset() {
     println!("context_reset");
-    if let Some(core_ctx) = &*addr_of!(CORE_CONTEXT) {
+    if let Some(core_ctx) = get_core_ctx() {
         if let Err(e) = core_ctx.callbacks.video.context_reset() {
             println!("{:?}", e);
             let _ = core_ctx.de_init();
@@ -134,7 +144,7 @@ unsafe extern "C" fn context_reset() {
 unsafe extern "C" fn context_destroy() {
     println!("context_destroy");
 
-    if let Some(core_ctx) = &*addr_of!(CORE_CONTEXT) {
+    if let Some(core_ctx) = get_core_ctx() {
         if let Err(e) = core_ctx.callbacks.video.context_destroy() {
             println!("{:?}", e);
             let _ = core_ctx.de_init();
@@ -142,7 +152,7 @@ unsafe extern "C" fn context_destroy() {
     }
 }
 
-pub unsafe fn env_cb_av(core_ctx: &RetroCoreIns, cmd: c_uint, data: *mut c_void) -> bool {
+pub unsafe fn env_cb_av(core_ctx: &RetroCore, cmd: c_uint, data: *mut c_void) -> bool {
     match cmd {
         RETRO_ENVIRONMENT_SET_GEOMETRY => {
             #[cfg(feature = "core_ev_logs")]
@@ -171,9 +181,16 @@ pub unsafe fn env_cb_av(core_ctx: &RetroCoreIns, cmd: c_uint, data: *mut c_void)
             #[cfg(feature = "core_ev_logs")]
             println!("RETRO_ENVIRONMENT_GET_AUDIO_VIDEO_ENABLE -> ok");
 
-            *(data as *mut u32) = 1 << 0 | 1 << 1;
-
-            true
+            match core_ctx.output.get_av_enable_flags() {
+                Ok(flags) => {
+                    *(data as *mut u32) = flags;
+                    true
+                }
+                Err(e) => {
+                    println!("RETRO_ENVIRONMENT_GET_AUDIO_VIDEO_ENABLE -> {:?}", e);
+                    false
+                }
+            }
         }
         #[cfg(feature = "hw")]
         RETRO_ENVIRONMENT_GET_PREFERRED_HW_RENDER => {
@@ -189,7 +206,11 @@ pub unsafe fn env_cb_av(core_ctx: &RetroCoreIns, cmd: c_uint, data: *mut c_void)
             #[cfg(feature = "core_ev_logs")]
             println!("RETRO_ENVIRONMENT_SET_HW_RENDER");
 
-            if data.is_null() {
+            //sem uma api gráfica (ex: modo headless) o core deve usar a renderização por software
+            if data.is_null()
+                || core_ctx.av_info.video.graphic_api.context_type
+                    == retro_hw_context_type::RETRO_HW_CONTEXT_NONE
+            {
                 return false;
             }
 
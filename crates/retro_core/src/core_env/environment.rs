@@ -1,40 +1,61 @@
-#[cfg(feature = "core_logs")]
-use crate::tools::ffi_tools::get_str_from_ptr;
 use crate::{
+    camera::CameraSource,
     core_env::{
-        env_directory::env_cb_directory, env_gamepads_io::env_cb_gamepad_io,
-        env_option::env_cb_option, env_video::env_cb_av,
+        env_camera::env_cb_camera, env_directory::env_cb_directory, env_disk::env_cb_disk,
+        env_gamepads_io::env_cb_gamepad_io, env_location::env_cb_location,
+        env_memory::env_cb_memory, env_message::env_cb_message, env_option::env_cb_option,
+        env_power::env_cb_power, env_sensor::env_cb_sensor, env_timing::env_cb_timing,
+        env_vfs::env_cb_vfs, env_video::env_cb_av,
     },
     libretro_sys::{
         binding_libretro::{
-            retro_language::{self, RETRO_LANGUAGE_PORTUGUESE_BRAZIL},
-            retro_log_level, retro_perf_callback, retro_rumble_effect,
-            RETRO_ENVIRONMENT_GET_LANGUAGE, RETRO_ENVIRONMENT_GET_LOG_INTERFACE,
-            RETRO_ENVIRONMENT_GET_MESSAGE_INTERFACE_VERSION, RETRO_ENVIRONMENT_GET_PERF_INTERFACE,
-            RETRO_ENVIRONMENT_GET_VARIABLE, RETRO_ENVIRONMENT_SET_INPUT_DESCRIPTORS,
+            retro_language, retro_log_level, retro_perf_callback, retro_rumble_effect,
+            retro_savestate_context, RETRO_ENVIRONMENT_GET_LANGUAGE,
+            RETRO_ENVIRONMENT_GET_LOG_INTERFACE, RETRO_ENVIRONMENT_GET_PERF_INTERFACE,
+            RETRO_ENVIRONMENT_GET_SAVESTATE_CONTEXT, RETRO_ENVIRONMENT_GET_VARIABLE,
             RETRO_ENVIRONMENT_SET_PERFORMANCE_LEVEL, RETRO_ENVIRONMENT_SET_SUPPORT_NO_GAME,
         },
         binding_log_interface::configure_log_interface,
     },
-    retro_core::RetroCore,
+    location::LocationProvider,
+    logger::{RetroLogEntry, RetroLogger, StdoutLogger},
+    message::RetroMessage,
+    power::PowerProvider,
     retro_perf::{
         core_get_perf_counter, core_perf_log, core_perf_register, core_perf_start, core_perf_stop,
         get_cpu_features, get_features_get_time_usec,
     },
-    RetroCoreIns,
+    sensor::SensorProvider,
+    tools::ffi_tools::get_str_from_ptr,
+    vfs::RetroVfs,
+    RetroCore, RetroCoreIns,
 };
 use generics::erro_handle::ErroHandle;
+use std::os::raw::c_void;
 use std::{
+    cell::RefCell,
     ffi::{c_char, c_uint},
-    rc::Rc,
-    sync::atomic::Ordering,
+    ops::Deref,
+    sync::{atomic::Ordering, Arc, Condvar, Mutex},
 };
-use std::{os::raw::c_void, ptr::addr_of};
 
 pub struct RetroEnvCallbacks {
     pub video: Box<dyn RetroVideoEnvCallbacks>,
     pub audio: Box<dyn RetroAudioEnvCallbacks>,
     pub controller: Box<dyn RetroControllerEnvCallbacks>,
+    pub message: Box<dyn RetroMessageEnvCallbacks>,
+    #[doc = "recebe as mensagens que o core envia pela interface de log"]
+    pub logger: Box<dyn RetroLogger>,
+    #[doc = "quando None o core usa as suas próprias funções para acessar os arquivos"]
+    pub vfs: Option<Arc<dyn RetroVfs>>,
+    #[doc = "quando None o core nao recebe a interface de sensores"]
+    pub sensor: Option<Box<dyn SensorProvider>>,
+    #[doc = "quando None o core nao recebe a interface de localização"]
+    pub location: Option<Box<dyn LocationProvider>>,
+    #[doc = "quando None o core nao recebe a interface da câmera"]
+    pub camera: Option<Box<dyn CameraSource>>,
+    #[doc = "quando None o core e informado que o estado da energia e desconhecido"]
+    pub power: Option<Box<dyn PowerProvider>>,
 }
 
 pub trait RetroVideoEnvCallbacks {
@@ -77,32 +98,244 @@ pub trait RetroControllerEnvCallbacks {
         effect: retro_rumble_effect,
         strength: u16,
     ) -> Result<bool, ErroHandle>;
+    #[doc = "o core pode chamar isso a cada frame mesmo que o estado do LED nao tenha mudado"]
+    fn set_led_state(&self, led: i32, state: i32) -> Result<(), ErroHandle>;
 }
 
-#[doc = "pelo amor de deus MANTENHA isso dentro desse diretório"]
-pub static mut CORE_CONTEXT: Option<Rc<RetroCore>> = None;
+pub trait RetroMessageEnvCallbacks {
+    #[doc = "chamada dentro do retro_run, então evite fazer trabalhos pesados aqui"]
+    fn message_callback(&self, message: RetroMessage) -> Result<(), ErroHandle>;
+}
+
+thread_local! {
+    #[doc = "pilha dos núcleos que estão sendo chamados nessa thread. O ultimo da lista e o núcleo
+    que esta em execução agora e e para ele que todas as callbacks serão enviadas"]
+    static CORE_CONTEXTS: RefCell<Vec<RetroCoreIns>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Enquanto essa guarda existir, todas as callbacks chamadas pelo núcleo nessa thread serão
+/// enviadas para o [crate::RetroCore] usado em [enter_core_ctx].
+///
+/// Como a libretro nao envia nenhum identificador junto das callbacks, cada chamada feita para
+/// a [libretro_sys::binding_libretro::LibretroRaw] deve acontecer dentro de uma dessas guardas.
+/// Isso permite que varios núcleos fiquem carregados ao mesmo tempo, inclusive na mesma thread.
+pub struct CoreContextGuard {
+    active: bool,
+}
+
+impl Drop for CoreContextGuard {
+    fn drop(&mut self) {
+        if !self.active {
+            return;
+        }
+
+        //o núcleo so pode ser descartado depois que o RefCell for liberado
+        let _core_ctx = CORE_CONTEXTS
+            .try_with(|ctxs| ctxs.borrow_mut().pop())
+            .ok()
+            .flatten();
+    }
+}
+
+pub fn enter_core_ctx(core_ctx: Option<RetroCoreIns>) -> CoreContextGuard {
+    match core_ctx {
+        Some(core_ctx) => {
+            let active = CORE_CONTEXTS
+                .try_with(|ctxs| ctxs.borrow_mut().push(core_ctx))
+                .is_ok();
+
+            CoreContextGuard { active }
+        }
+        None => CoreContextGuard { active: false },
+    }
+}
+
+//núcleo que ainda nao foi finalizado. *users* conta as callbacks de outras threads que estão
+//usando o núcleo agora
+struct LoadedCore {
+    core: *const RetroCore,
+    users: usize,
+    leaving: bool,
+}
+
+//núcleos que ainda nao foram finalizados, na ordem em que foram criados
+struct LoadedCores {
+    cores: Vec<LoadedCore>,
+}
+
+//os ponteiros so sao lidos pelas threads criadas pelos próprios núcleos
+unsafe impl Send for LoadedCores {}
+
+impl LoadedCores {
+    const fn new() -> Self {
+        Self { cores: Vec::new() }
+    }
+
+    fn register(&mut self, core: *const RetroCore) {
+        if !self.cores.iter().any(|loaded| loaded.core == core) {
+            self.cores.push(LoadedCore {
+                core,
+                users: 0,
+                leaving: false,
+            });
+        }
+    }
+
+    fn unregister(&mut self, core: *const RetroCore) {
+        self.cores.retain(|loaded| loaded.core != core);
+    }
 
-//noinspection RsPlaceExpression
-pub fn configure(core_ctx: RetroCoreIns) {
-    unsafe {
-        CORE_CONTEXT = Some(core_ctx);
+    //depois disso nenhuma callback nova recebe o núcleo, mas as que ja receberam continuam usando ele
+    fn leave(&mut self, core: *const RetroCore) {
+        for loaded in self.cores.iter_mut().filter(|loaded| loaded.core == core) {
+            loaded.leaving = true;
+        }
+    }
+
+    fn in_use(&self, core: *const RetroCore) -> bool {
+        self.cores
+            .iter()
+            .any(|loaded| loaded.core == core && loaded.users > 0)
+    }
+
+    //a libretro nao diz de qual núcleo veio a callback, então quando existe mais de um núcleo
+    //carregado nao tem como saber para qual deles a thread deve ser enviada
+    fn fallback(&self) -> Option<*const RetroCore> {
+        match self.cores.as_slice() {
+            [loaded] if !loaded.leaving => Some(loaded.core),
+            _ => None,
+        }
+    }
+
+    fn acquire(&mut self) -> Option<*const RetroCore> {
+        let core = self.fallback()?;
+
+        for loaded in self.cores.iter_mut().filter(|loaded| loaded.core == core) {
+            loaded.users += 1;
+        }
+
+        Some(core)
+    }
+
+    fn release(&mut self, core: *const RetroCore) {
+        for loaded in self.cores.iter_mut().filter(|loaded| loaded.core == core) {
+            loaded.users = loaded.users.saturating_sub(1);
+        }
     }
 }
 
-//noinspection RsPlaceExpression
-pub fn delete_local_core_ctx() {
-    unsafe {
-        CORE_CONTEXT = None;
+static LOADED_CORES: Mutex<LoadedCores> = Mutex::new(LoadedCores::new());
+
+//avisa o unregister_loaded_core quando uma callback de outra thread termina de usar o núcleo
+static CORE_RELEASED: Condvar = Condvar::new();
+
+#[doc = "permite que as threads criadas pelo núcleo encontrem o [RetroCore]. Deve ser desfeito com
+[unregister_loaded_core] antes do núcleo ser descartado"]
+pub fn register_loaded_core(core: &RetroCore) {
+    if let Ok(mut loaded) = LOADED_CORES.lock() {
+        loaded.register(core as *const RetroCore);
     }
 }
 
-unsafe extern "C" fn core_log(_level: retro_log_level, _log: *const c_char) {
-    #[cfg(feature = "core_logs")]
-    println!("[{:?}]: {:?}", _level, get_str_from_ptr(_log));
+#[doc = "espera as callbacks das threads do núcleo que ainda estão usando ele terminarem"]
+pub fn unregister_loaded_core(core: &RetroCore) {
+    let core = core as *const RetroCore;
+
+    let mut loaded = match LOADED_CORES.lock() {
+        Ok(loaded) => loaded,
+        Err(_) => return,
+    };
+
+    loaded.leave(core);
+
+    while loaded.in_use(core) {
+        loaded = match CORE_RELEASED.wait(loaded) {
+            Ok(loaded) => loaded,
+            Err(_) => return,
+        };
+    }
+
+    loaded.unregister(core);
+}
+
+/// Núcleo usado por uma callback vinda de uma thread criada pelo próprio núcleo.
+///
+/// Enquanto ele existir o [unregister_loaded_core] nao retorna, então o núcleo nao pode ser
+/// descartado no meio da callback.
+pub struct SharedCore {
+    core: *const RetroCore,
+}
+
+impl Drop for SharedCore {
+    fn drop(&mut self) {
+        if let Ok(mut loaded) = LOADED_CORES.lock() {
+            loaded.release(self.core);
+        }
+
+        CORE_RELEASED.notify_all();
+    }
+}
+
+/// Núcleo que deve receber uma callback.
+///
+/// *Local* vem de [enter_core_ctx]. *Shared* e usado quando a callback vem de uma thread criada pelo
+/// próprio núcleo (renderização, carregamento, áudio...), que nunca passa por [enter_core_ctx].
+pub enum CoreCtx {
+    Local(RetroCoreIns),
+    Shared(SharedCore),
+}
+
+impl Deref for CoreCtx {
+    type Target = RetroCore;
+
+    fn deref(&self) -> &RetroCore {
+        match self {
+            CoreCtx::Local(core) => core,
+            //o SharedCore impede que o núcleo seja descartado enquanto a callback usa ele
+            CoreCtx::Shared(shared) => unsafe { &*shared.core },
+        }
+    }
+}
+
+#[doc = "retorna o núcleo que esta em execução nessa thread. Threads sem nenhum núcleo so recebem um
+núcleo quando existe apenas um carregado, ja que nao tem como saber de qual núcleo a thread veio"]
+pub fn get_core_ctx() -> Option<CoreCtx> {
+    let local = CORE_CONTEXTS
+        .try_with(|ctxs| ctxs.borrow().last().cloned())
+        .ok()
+        .flatten();
+
+    match local {
+        Some(core) => Some(CoreCtx::Local(core)),
+        None => LOADED_CORES
+            .lock()
+            .ok()
+            .and_then(|mut loaded| loaded.acquire())
+            .map(|core| CoreCtx::Shared(SharedCore { core })),
+    }
+}
+
+//as threads do núcleo usam o logger da sessão através de get_core_ctx. Se nenhum núcleo for
+//encontrado (ex: uma thread que continua rodando depois do retro_deinit) a mensagem vai para o terminal
+unsafe extern "C" fn core_log(level: retro_log_level, log: *const c_char) {
+    let message = get_str_from_ptr(log);
+
+    let result = match get_core_ctx() {
+        Some(core_ctx) => core_ctx.callbacks.logger.log(&RetroLogEntry::new(
+            level,
+            core_ctx.system.info.library_name.to_string(),
+            &message,
+        )),
+        None => StdoutLogger.log(&RetroLogEntry::new(level, "core".to_string(), &message)),
+    };
+
+    if let Err(e) = result {
+        println!("core_log -> {:?}", e);
+    }
 }
 
 pub unsafe extern "C" fn core_environment(cmd: c_uint, data: *mut c_void) -> bool {
-    match &*addr_of!(CORE_CONTEXT) {
+    match get_core_ctx() {
         Some(core_ctx) => match cmd {
             RETRO_ENVIRONMENT_SET_SUPPORT_NO_GAME => {
                 #[cfg(feature = "core_ev_logs")]
@@ -118,7 +351,7 @@ pub unsafe extern "C" fn core_environment(cmd: c_uint, data: *mut c_void) -> boo
                 #[cfg(feature = "core_ev_logs")]
                 println!("RETRO_ENVIRONMENT_GET_LANGUAGE -> ok");
 
-                *(data as *mut retro_language) = RETRO_LANGUAGE_PORTUGUESE_BRAZIL;
+                *(data as *mut retro_language) = core_ctx.language;
 
                 true
             }
@@ -130,14 +363,6 @@ pub unsafe extern "C" fn core_environment(cmd: c_uint, data: *mut c_void) -> boo
 
                 true
             }
-            RETRO_ENVIRONMENT_GET_MESSAGE_INTERFACE_VERSION => {
-                #[cfg(feature = "core_ev_logs")]
-                println!("RETRO_ENVIRONMENT_SET_SUBSYSTEM_INFO -> OK");
-
-                *(data as *mut usize) = 1;
-
-                true
-            }
             RETRO_ENVIRONMENT_SET_PERFORMANCE_LEVEL => {
                 #[cfg(feature = "core_ev_logs")]
                 println!("RETRO_ENVIRONMENT_SET_PERFORMANCE_LEVEL -> OK");
@@ -155,7 +380,8 @@ pub unsafe extern "C" fn core_environment(cmd: c_uint, data: *mut c_void) -> boo
                 #[cfg(feature = "core_ev_logs")]
                 println!("RETRO_ENVIRONMENT_GET_PERF_INTERFACE -> ok");
 
-                let mut perf = *(data as *mut retro_perf_callback);
+                //a struct pertence ao core, então ela deve ser preenchida no próprio lugar
+                let perf = &mut *(data as *mut retro_perf_callback);
 
                 perf.get_time_usec = Some(get_features_get_time_usec);
                 perf.get_cpu_features = Some(get_cpu_features);
@@ -167,18 +393,44 @@ pub unsafe extern "C" fn core_environment(cmd: c_uint, data: *mut c_void) -> boo
 
                 true
             }
+            RETRO_ENVIRONMENT_GET_SAVESTATE_CONTEXT => {
+                #[cfg(feature = "core_ev_logs")]
+                println!("RETRO_ENVIRONMENT_GET_SAVESTATE_CONTEXT -> ok");
+
+                if data.is_null() {
+                    return true;
+                }
+
+                match core_ctx.output.get_savestate_context() {
+                    Ok(context) => {
+                        *(data as *mut retro_savestate_context) = context;
+                        true
+                    }
+                    Err(e) => {
+                        println!("RETRO_ENVIRONMENT_GET_SAVESTATE_CONTEXT -> {:?}", e);
+                        false
+                    }
+                }
+            }
             _ => {
-                if env_cb_av(core_ctx, cmd, data)
-                    || env_cb_gamepad_io(core_ctx, cmd, data)
-                    || env_cb_option(core_ctx, cmd, data)
-                    || env_cb_directory(core_ctx, cmd, data)
+                if env_cb_av(&core_ctx, cmd, data)
+                    || env_cb_gamepad_io(&core_ctx, cmd, data)
+                    || env_cb_option(&core_ctx, cmd, data)
+                    || env_cb_directory(&core_ctx, cmd, data)
+                    || env_cb_memory(&core_ctx, cmd, data)
+                    || env_cb_disk(&core_ctx, cmd, data)
+                    || env_cb_vfs(&core_ctx, cmd, data)
+                    || env_cb_message(&core_ctx, cmd, data)
+                    || env_cb_timing(&core_ctx, cmd, data)
+                    || env_cb_sensor(&core_ctx, cmd, data)
+                    || env_cb_location(&core_ctx, cmd, data)
+                    || env_cb_camera(&core_ctx, cmd, data)
+                    || env_cb_power(&core_ctx, cmd, data)
                 {
                     return true;
                 }
 
-                if cmd != RETRO_ENVIRONMENT_GET_VARIABLE
-                    && cmd != RETRO_ENVIRONMENT_SET_INPUT_DESCRIPTORS
-                {
+                if cmd != RETRO_ENVIRONMENT_GET_VARIABLE {
                     println!("new core cmd -> {:?}", cmd);
                 }
 
@@ -192,23 +444,27 @@ pub unsafe extern "C" fn core_environment(cmd: c_uint, data: *mut c_void) -> boo
 //TODO: novos teste para "fn core_environment"
 #[cfg(test)]
 mod test_environment {
-    use crate::{core_env::environment::CORE_CONTEXT, test_tools};
+    use crate::{test_tools, RetroCoreIns};
     use generics::erro_handle::ErroHandle;
     use libretro_sys::binding_libretro::{
         retro_pixel_format, RETRO_ENVIRONMENT_GET_INPUT_BITMASKS,
         RETRO_ENVIRONMENT_SET_PIXEL_FORMAT,
     };
-    use std::{ffi::c_void, ptr::addr_of};
+    use std::{ffi::c_void, ptr};
 
-    use super::{configure, core_environment};
+    use super::{core_environment, enter_core_ctx, get_core_ctx, CoreContextGuard, LoadedCores};
 
-    fn cfg_test() {
+    fn cfg_test() -> (RetroCoreIns, CoreContextGuard) {
         let core_ctx = test_tools::core::get_core_wrapper();
-        configure(core_ctx);
+        let guard = enter_core_ctx(Some(core_ctx.clone()));
+
+        (core_ctx, guard)
     }
 
     #[test]
     fn input_bitmasks() {
+        let _ctx = cfg_test();
+
         let mut my_bool = true;
         let data = &mut my_bool as *mut bool as *mut c_void;
 
@@ -219,9 +475,56 @@ mod test_environment {
         assert_eq!(result, true);
     }
 
+    //os outros testes rodam em paralelo e também carregam núcleos, então o registro global nao
+    //pode ser usado aqui
+    #[test]
+    fn without_core_ctx() {
+        let first = test_tools::core::get_core_wrapper();
+        let second = test_tools::core::get_core_wrapper();
+        let mut loaded = LoadedCores::new();
+
+        assert!(loaded.fallback().is_none());
+
+        loaded.register(&*first);
+        loaded.register(&*first);
+        assert!(ptr::eq(loaded.fallback().unwrap(), &*first));
+
+        //com dois núcleos carregados nao tem como saber de qual deles a thread veio
+        loaded.register(&*second);
+        assert!(loaded.fallback().is_none());
+
+        loaded.unregister(&*first);
+        assert!(ptr::eq(loaded.acquire().unwrap(), &*second));
+
+        //o núcleo continua registrado até a callback que esta usando ele terminar
+        loaded.leave(&*second);
+        assert!(loaded.fallback().is_none());
+        assert!(loaded.in_use(&*second));
+
+        loaded.release(&*second);
+        assert!(!loaded.in_use(&*second));
+
+        loaded.unregister(&*second);
+        assert!(loaded.fallback().is_none());
+    }
+
+    #[test]
+    fn nested_core_ctx() {
+        let (first, _first_guard) = cfg_test();
+
+        {
+            let second = test_tools::core::get_core_wrapper();
+            let _second_guard = enter_core_ctx(Some(second.clone()));
+
+            assert!(ptr::eq(&*get_core_ctx().unwrap(), &*second));
+        }
+
+        assert!(ptr::eq(&*get_core_ctx().unwrap(), &*first));
+    }
+
     #[test]
     fn pixel_format() -> Result<(), ErroHandle> {
-        cfg_test();
+        let (core_ctx, _guard) = cfg_test();
         let pixel = retro_pixel_format::RETRO_PIXEL_FORMAT_RGB565;
         let data = &pixel as *const retro_pixel_format as *mut c_void;
 
@@ -233,18 +536,13 @@ mod test_environment {
             result,
         );
 
-        unsafe {
-            match &*addr_of!(CORE_CONTEXT) {
-                Some(core_ctx) => assert_eq!(
-                    *core_ctx.av_info.video.pixel_format.read()?,
-                    pixel,
-                    "returno inesperado: valor desejado -> {:?}; valor recebido -> {:?}",
-                    pixel,
-                    *core_ctx.av_info.video.pixel_format.read()?
-                ),
-                _ => panic!("CORE_CONTEXT nao foi encontrado"),
-            }
-        }
+        assert_eq!(
+            *core_ctx.av_info.video.pixel_format.read()?,
+            pixel,
+            "returno inesperado: valor desejado -> {:?}; valor recebido -> {:?}",
+            pixel,
+            *core_ctx.av_info.video.pixel_format.read()?
+        );
 
         Ok(())
     }
@@ -1,6 +1,15 @@
+mod env_camera;
 mod env_directory;
+mod env_disk;
 mod env_gamepads_io;
+mod env_location;
+mod env_memory;
+mod env_message;
 mod env_option;
+mod env_power;
+mod env_sensor;
+mod env_timing;
+mod env_vfs;
 mod env_video;
 mod environment;
 
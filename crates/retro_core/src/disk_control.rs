@@ -0,0 +1,178 @@
+use crate::tools::game_tools::GameInfoData;
+use generics::erro_handle::ErroHandle;
+use libretro_sys::binding_libretro::{
+    retro_disk_control_callback, retro_disk_control_ext_callback,
+};
+use std::{ffi::c_char, sync::RwLock};
+
+/// Callbacks enviadas pelo core em RETRO_ENVIRONMENT_SET_DISK_CONTROL_INTERFACE ou
+/// RETRO_ENVIRONMENT_SET_DISK_CONTROL_EXT_INTERFACE.
+///
+/// Os índices dos discos começam em 0. Para trocar de disco a bandeja precisa estar aberta, veja
+/// [crate::RetroCore::eject_disk].
+#[derive(Debug, Default)]
+pub struct DiskControl {
+    callbacks: RwLock<Option<retro_disk_control_ext_callback>>,
+}
+
+impl DiskControl {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_callbacks(&self, raw_cb: &retro_disk_control_callback) -> Result<(), ErroHandle> {
+        self.callbacks
+            .write()?
+            .replace(retro_disk_control_ext_callback {
+                set_eject_state: raw_cb.set_eject_state,
+                get_eject_state: raw_cb.get_eject_state,
+                get_image_index: raw_cb.get_image_index,
+                set_image_index: raw_cb.set_image_index,
+                get_num_images: raw_cb.get_num_images,
+                replace_image_index: raw_cb.replace_image_index,
+                add_image_index: raw_cb.add_image_index,
+                set_initial_image: None,
+                get_image_path: None,
+                get_image_label: None,
+            });
+
+        Ok(())
+    }
+
+    pub fn set_ext_callbacks(
+        &self,
+        raw_cb: &retro_disk_control_ext_callback,
+    ) -> Result<(), ErroHandle> {
+        self.callbacks.write()?.replace(*raw_cb);
+
+        Ok(())
+    }
+
+    pub fn is_supported(&self) -> bool {
+        self.callbacks.read().is_ok_and(|cb| cb.is_some())
+    }
+
+    fn get_callbacks(&self) -> Result<retro_disk_control_ext_callback, ErroHandle> {
+        self.callbacks
+            .read()?
+            .ok_or(ErroHandle::new("O núcleo nao suporta a troca de discos"))
+    }
+
+    pub fn set_eject_state(&self, ejected: bool) -> Result<(), ErroHandle> {
+        let set_eject_state = self
+            .get_callbacks()?
+            .set_eject_state
+            .ok_or(ErroHandle::new("O núcleo nao suporta a troca de discos"))?;
+
+        if unsafe { set_eject_state(ejected) } {
+            Ok(())
+        } else {
+            Err(ErroHandle::new(
+                "O núcleo nao conseguiu abrir ou fechar a bandeja",
+            ))
+        }
+    }
+
+    pub fn get_eject_state(&self) -> Result<bool, ErroHandle> {
+        let get_eject_state = self
+            .get_callbacks()?
+            .get_eject_state
+            .ok_or(ErroHandle::new("O núcleo nao suporta a troca de discos"))?;
+
+        Ok(unsafe { get_eject_state() })
+    }
+
+    pub fn get_image_index(&self) -> Result<u32, ErroHandle> {
+        let get_image_index = self
+            .get_callbacks()?
+            .get_image_index
+            .ok_or(ErroHandle::new("O núcleo nao suporta a troca de discos"))?;
+
+        Ok(unsafe { get_image_index() })
+    }
+
+    #[doc = "a bandeja precisa estar aberta para que o disco seja trocado"]
+    pub fn set_image_index(&self, index: u32) -> Result<(), ErroHandle> {
+        let set_image_index = self
+            .get_callbacks()?
+            .set_image_index
+            .ok_or(ErroHandle::new("O núcleo nao suporta a troca de discos"))?;
+
+        if index >= self.get_num_images()? {
+            return Err(ErroHandle {
+                message: format!("O disco {} nao existe", index),
+            });
+        }
+
+        if unsafe { set_image_index(index) } {
+            Ok(())
+        } else {
+            Err(ErroHandle::new("O núcleo nao conseguiu trocar o disco"))
+        }
+    }
+
+    pub fn get_num_images(&self) -> Result<u32, ErroHandle> {
+        let get_num_images = self
+            .get_callbacks()?
+            .get_num_images
+            .ok_or(ErroHandle::new("O núcleo nao suporta a troca de discos"))?;
+
+        Ok(unsafe { get_num_images() })
+    }
+
+    #[doc = "retorna o nome do disco, se o core informar algum"]
+    pub fn get_image_label(&self, index: u32) -> Result<Option<String>, ErroHandle> {
+        let get_image_label = match self.get_callbacks()?.get_image_label {
+            Some(get_image_label) => get_image_label,
+            None => return Ok(None),
+        };
+
+        let mut label = [0 as c_char; 256];
+
+        if !unsafe { get_image_label(index, label.as_mut_ptr(), label.len()) } {
+            return Ok(None);
+        }
+
+        let label = unsafe { std::ffi::CStr::from_ptr(label.as_ptr()) };
+
+        Ok(Some(label.to_string_lossy().to_string()))
+    }
+
+    #[doc = "adiciona os discos no final da lista do core sem trocar o disco que esta em uso"]
+    pub fn append_images(&self, images: &[GameInfoData]) -> Result<(), ErroHandle> {
+        let callbacks = self.get_callbacks()?;
+        let add_image_index = callbacks
+            .add_image_index
+            .ok_or(ErroHandle::new("O núcleo nao suporta adicionar discos"))?;
+        let replace_image_index = callbacks
+            .replace_image_index
+            .ok_or(ErroHandle::new("O núcleo nao suporta adicionar discos"))?;
+
+        let was_ejected = self.get_eject_state()?;
+        let current_index = self.get_image_index()?;
+
+        if !was_ejected {
+            self.set_eject_state(true)?;
+        }
+
+        for image in images {
+            let index = self.get_num_images()?;
+
+            let added = unsafe { add_image_index() && replace_image_index(index, &image.as_raw()) };
+
+            if !added {
+                return Err(ErroHandle {
+                    message: format!("O núcleo nao aceitou o disco {}", image.get_path()),
+                });
+            }
+        }
+
+        self.set_image_index(current_index)?;
+
+        if !was_ejected {
+            self.set_eject_state(false)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,115 @@
+use generics::erro_handle::ErroHandle;
+use libretro_sys::binding_libretro::{retro_frame_time_callback, retro_usec_t};
+use std::{
+    sync::{Mutex, RwLock},
+    time::Instant,
+};
+
+/// Callback enviada pelo core em RETRO_ENVIRONMENT_SET_FRAME_TIME_CALLBACK. Informa ao core quanto
+/// tempo passou desde a ultima chamada do retro_run.
+///
+/// [FrameTime::notify] chama o core diretamente, então deve ser usada através do [crate::RetroCore].
+#[derive(Debug, Default)]
+pub struct FrameTime {
+    callback: RwLock<Option<retro_frame_time_callback>>,
+    last_run: Mutex<Option<Instant>>,
+}
+
+impl FrameTime {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_callback(&self, raw_cb: &retro_frame_time_callback) -> Result<(), ErroHandle> {
+        self.callback.write()?.replace(*raw_cb);
+        *self.last_run.lock()? = None;
+
+        Ok(())
+    }
+
+    pub fn is_supported(&self) -> bool {
+        self.callback
+            .read()
+            .is_ok_and(|cb| cb.is_some_and(|cb| cb.callback.is_some()))
+    }
+
+    #[doc = "faz a proxima chamada usar o tempo de referencia do core. deve ser usada quando o jogo
+    for pausado, para que o tempo da pausa nao seja enviado ao core"]
+    pub fn reset(&self) -> Result<(), ErroHandle> {
+        *self.last_run.lock()? = None;
+
+        Ok(())
+    }
+
+    #[doc = "retorna o tempo em microssegundos desde a ultima chamada. na primeira vez o valor de
+    referencia enviado pelo core e usado"]
+    fn next_delta(&self, now: Instant) -> Result<Option<retro_usec_t>, ErroHandle> {
+        let reference = match &*self.callback.read()? {
+            Some(cb) => cb.reference,
+            None => return Ok(None),
+        };
+
+        let mut last_run = self.last_run.lock()?;
+
+        let delta = match *last_run {
+            Some(last_run) => now.duration_since(last_run).as_micros() as retro_usec_t,
+            None => reference,
+        };
+
+        last_run.replace(now);
+
+        Ok(Some(delta))
+    }
+
+    pub fn notify(&self) -> Result<(), ErroHandle> {
+        let callback = match &*self.callback.read()? {
+            Some(cb) => cb.callback,
+            None => return Ok(()),
+        };
+
+        if let (Some(callback), Some(delta)) = (callback, self.next_delta(Instant::now())?) {
+            unsafe { callback(delta) }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_frame_time {
+    use super::FrameTime;
+    use libretro_sys::binding_libretro::retro_frame_time_callback;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn delta() {
+        let frame_time = FrameTime::new();
+        let now = Instant::now();
+
+        assert_eq!(frame_time.next_delta(now).unwrap(), None);
+
+        frame_time
+            .set_callback(&retro_frame_time_callback {
+                callback: None,
+                reference: 16_666,
+            })
+            .unwrap();
+
+        assert_eq!(frame_time.next_delta(now).unwrap(), Some(16_666));
+        assert_eq!(
+            frame_time
+                .next_delta(now + Duration::from_millis(20))
+                .unwrap(),
+            Some(20_000)
+        );
+
+        //depois de uma pausa o core recebe o tempo de referencia novamente
+        frame_time.reset().unwrap();
+        assert_eq!(
+            frame_time
+                .next_delta(now + Duration::from_secs(10))
+                .unwrap(),
+            Some(16_666)
+        );
+    }
+}
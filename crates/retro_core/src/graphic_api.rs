@@ -34,6 +34,9 @@ pub struct GraphicApi {
 
     #[doc = " Creates a debug context."]
     pub debug_context: AtomicBool,
+
+    #[doc = "true quando o core pediu para renderizar usando a GPU"]
+    pub hw_render: AtomicBool,
 }
 
 impl Default for GraphicApi {
@@ -48,6 +51,7 @@ impl Default for GraphicApi {
             minor: AtomicU32::new(0),
             cache_context: AtomicBool::new(false),
             debug_context: AtomicBool::new(false),
+            hw_render: AtomicBool::new(false),
         }
     }
 }
@@ -78,6 +82,7 @@ impl GraphicApi {
         self.major.store(hw.version_major, Ordering::SeqCst);
         self.cache_context.store(hw.cache_context, Ordering::SeqCst);
         self.debug_context.store(hw.debug_context, Ordering::SeqCst);
+        self.hw_render.store(true, Ordering::SeqCst);
 
         true
     }
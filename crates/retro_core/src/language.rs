@@ -0,0 +1,118 @@
+use libretro_sys::binding_libretro::retro_language::{self, *};
+use std::env;
+
+//variáveis lidas em ordem de prioridade, igual ao setlocale do POSIX
+const LOCALE_ENV_VARS: [&str; 3] = ["LC_ALL", "LC_MESSAGES", "LANG"];
+
+#[doc = "converte um locale como \"pt_BR.UTF-8\" ou \"ca_ES@valencia\" para o idioma do libretro"]
+pub fn language_from_locale(locale: &str) -> Option<retro_language> {
+    let (locale, modifier) = match locale.split_once('@') {
+        Some((locale, modifier)) => (locale, Some(modifier)),
+        None => (locale, None),
+    };
+
+    let locale = locale.split('.').next().unwrap_or_default();
+    let (lang, region) = match locale.split_once(|c: char| c == '_' || c == '-') {
+        Some((lang, region)) => (lang.to_lowercase(), region.to_uppercase()),
+        None => (locale.to_lowercase(), String::new()),
+    };
+
+    let language = match (lang.as_str(), region.as_str()) {
+        ("en", "GB") => RETRO_LANGUAGE_BRITISH_ENGLISH,
+        ("en", _) => RETRO_LANGUAGE_ENGLISH,
+        ("ja", _) => RETRO_LANGUAGE_JAPANESE,
+        ("fr", _) => RETRO_LANGUAGE_FRENCH,
+        ("es", _) => RETRO_LANGUAGE_SPANISH,
+        ("de", _) => RETRO_LANGUAGE_GERMAN,
+        ("it", _) => RETRO_LANGUAGE_ITALIAN,
+        ("nl", _) => RETRO_LANGUAGE_DUTCH,
+        ("pt", "PT") => RETRO_LANGUAGE_PORTUGUESE_PORTUGAL,
+        ("pt", _) => RETRO_LANGUAGE_PORTUGUESE_BRAZIL,
+        ("ru", _) => RETRO_LANGUAGE_RUSSIAN,
+        ("ko", _) => RETRO_LANGUAGE_KOREAN,
+        ("zh", "TW" | "HK" | "MO") => RETRO_LANGUAGE_CHINESE_TRADITIONAL,
+        ("zh", _) => RETRO_LANGUAGE_CHINESE_SIMPLIFIED,
+        ("eo", _) => RETRO_LANGUAGE_ESPERANTO,
+        ("pl", _) => RETRO_LANGUAGE_POLISH,
+        ("vi", _) => RETRO_LANGUAGE_VIETNAMESE,
+        ("ar", _) => RETRO_LANGUAGE_ARABIC,
+        ("el", _) => RETRO_LANGUAGE_GREEK,
+        ("tr", _) => RETRO_LANGUAGE_TURKISH,
+        ("sk", _) => RETRO_LANGUAGE_SLOVAK,
+        ("fa", _) => RETRO_LANGUAGE_PERSIAN,
+        ("he", _) => RETRO_LANGUAGE_HEBREW,
+        ("ast", _) => RETRO_LANGUAGE_ASTURIAN,
+        ("fi", _) => RETRO_LANGUAGE_FINNISH,
+        ("id", _) => RETRO_LANGUAGE_INDONESIAN,
+        ("sv", _) => RETRO_LANGUAGE_SWEDISH,
+        ("uk", _) => RETRO_LANGUAGE_UKRAINIAN,
+        ("cs", _) => RETRO_LANGUAGE_CZECH,
+        ("ca", _) if modifier == Some("valencia") => RETRO_LANGUAGE_CATALAN_VALENCIA,
+        ("ca", _) => RETRO_LANGUAGE_CATALAN,
+        ("hu", _) => RETRO_LANGUAGE_HUNGARIAN,
+        ("be", _) => RETRO_LANGUAGE_BELARUSIAN,
+        _ => return None,
+    };
+
+    Some(language)
+}
+
+#[doc = "idioma do sistema, lido das variáveis LC_ALL, LC_MESSAGES e LANG. quando nenhuma delas
+for reconhecida o inglês e usado"]
+pub fn get_system_language() -> retro_language {
+    LOCALE_ENV_VARS
+        .iter()
+        .filter_map(|var| env::var(var).ok())
+        .find(|locale| !locale.is_empty())
+        .and_then(|locale| language_from_locale(&locale))
+        .unwrap_or(RETRO_LANGUAGE_ENGLISH)
+}
+
+#[doc = "as opções do core sempre possuem os textos em inglês (*us*), as traduções (*local*) so
+devem ser usadas nos outros idiomas"]
+pub fn use_local_labels(language: retro_language) -> bool {
+    !matches!(
+        language,
+        RETRO_LANGUAGE_ENGLISH | RETRO_LANGUAGE_BRITISH_ENGLISH
+    )
+}
+
+#[cfg(test)]
+mod test_language {
+    use super::{language_from_locale, use_local_labels};
+    use libretro_sys::binding_libretro::retro_language::*;
+
+    #[test]
+    fn parse_locale() {
+        assert_eq!(
+            language_from_locale("pt_BR.UTF-8"),
+            Some(RETRO_LANGUAGE_PORTUGUESE_BRAZIL)
+        );
+        assert_eq!(
+            language_from_locale("pt_PT"),
+            Some(RETRO_LANGUAGE_PORTUGUESE_PORTUGAL)
+        );
+        assert_eq!(
+            language_from_locale("en_GB.UTF-8"),
+            Some(RETRO_LANGUAGE_BRITISH_ENGLISH)
+        );
+        assert_eq!(
+            language_from_locale("zh-TW"),
+            Some(RETRO_LANGUAGE_CHINESE_TRADITIONAL)
+        );
+        assert_eq!(
+            language_from_locale("ca_ES.UTF-8@valencia"),
+            Some(RETRO_LANGUAGE_CATALAN_VALENCIA)
+        );
+        assert_eq!(language_from_locale("ja"), Some(RETRO_LANGUAGE_JAPANESE));
+        assert_eq!(language_from_locale("C"), None);
+        assert_eq!(language_from_locale(""), None);
+    }
+
+    #[test]
+    fn local_labels() {
+        assert!(!use_local_labels(RETRO_LANGUAGE_ENGLISH));
+        assert!(!use_local_labels(RETRO_LANGUAGE_BRITISH_ENGLISH));
+        assert!(use_local_labels(RETRO_LANGUAGE_PORTUGUESE_BRAZIL));
+    }
+}
@@ -7,15 +7,34 @@ mod managers;
 mod retro_perf;
 mod tools;
 
+pub mod audio_callback;
 pub mod av_info;
+pub mod camera;
+pub mod disk_control;
+pub mod frame_time;
 pub mod graphic_api;
+pub mod language;
+pub mod location;
+pub mod logger;
+pub mod message;
+pub mod power;
 mod retro_core;
+pub mod run_ahead;
+pub mod sensor;
+pub mod speed;
 pub mod system;
 pub mod test_tools;
+pub mod vfs;
 
 pub use core_env::{
-    RetroAudioEnvCallbacks, RetroControllerEnvCallbacks, RetroEnvCallbacks, RetroVideoEnvCallbacks,
+    RetroAudioEnvCallbacks, RetroControllerEnvCallbacks, RetroEnvCallbacks,
+    RetroMessageEnvCallbacks, RetroVideoEnvCallbacks,
 };
 pub use managers::args_manager;
+pub use managers::cheat_manager;
+pub use managers::memory_manager;
+pub use managers::movie_manager;
 pub use managers::option_manager;
+pub use managers::perf_manager;
+pub use managers::rewind_manager;
 pub use retro_core::{RetroCore, RetroCoreIns};
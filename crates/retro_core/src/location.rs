@@ -0,0 +1,132 @@
+use generics::erro_handle::ErroHandle;
+use libretro_sys::binding_libretro::{retro_location_callback, retro_location_lifetime_status_t};
+use std::{
+    fmt::Debug,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, RwLock,
+    },
+};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct RetroLocation {
+    pub latitude: f64,
+    pub longitude: f64,
+    #[doc = "precisão em metros. 0 quando desconhecida"]
+    pub horizontal_accuracy: f64,
+    pub vertical_accuracy: f64,
+}
+
+/// Posição do jogador usada pelos cores que pedem RETRO_ENVIRONMENT_GET_LOCATION_INTERFACE.
+pub trait LocationProvider: Debug {
+    fn start(&self) -> Result<bool, ErroHandle>;
+
+    fn stop(&self) -> Result<(), ErroHandle>;
+
+    #[doc = "None quando a localização nao estiver disponível"]
+    fn get_position(&self) -> Result<Option<RetroLocation>, ErroHandle>;
+
+    #[doc = "intervalo mínimo entre as atualizações em milissegundos e em metros"]
+    fn set_interval(&self, interval_ms: u32, interval_distance: u32) -> Result<(), ErroHandle>;
+}
+
+/// Sempre informa a mesma posição enquanto estiver iniciado. Pode ser clonado para mover o
+/// jogador durante os testes.
+#[derive(Clone, Debug, Default)]
+pub struct FixedLocationProvider {
+    location: Arc<RwLock<RetroLocation>>,
+    started: Arc<AtomicBool>,
+}
+
+impl FixedLocationProvider {
+    pub fn new(location: RetroLocation) -> Self {
+        Self {
+            location: Arc::new(RwLock::new(location)),
+            started: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn set_location(&self, location: RetroLocation) -> Result<(), ErroHandle> {
+        *self.location.write()? = location;
+
+        Ok(())
+    }
+
+    pub fn is_started(&self) -> bool {
+        self.started.load(Ordering::SeqCst)
+    }
+}
+
+impl LocationProvider for FixedLocationProvider {
+    fn start(&self) -> Result<bool, ErroHandle> {
+        self.started.store(true, Ordering::SeqCst);
+        Ok(true)
+    }
+
+    fn stop(&self) -> Result<(), ErroHandle> {
+        self.started.store(false, Ordering::SeqCst);
+        Ok(())
+    }
+
+    fn get_position(&self) -> Result<Option<RetroLocation>, ErroHandle> {
+        if !self.is_started() {
+            return Ok(None);
+        }
+
+        Ok(Some(*self.location.read()?))
+    }
+
+    fn set_interval(&self, _interval_ms: u32, _interval_distance: u32) -> Result<(), ErroHandle> {
+        Ok(())
+    }
+}
+
+/// Callbacks que o core envia junto da interface de localização para saber quando ela pode
+/// ser usada.
+///
+/// O core so e avisado que a interface pode ser usada depois que a rom e carregada, e que ela
+/// deixou de funcionar antes da rom ser descarregada.
+#[derive(Debug, Default)]
+pub struct LocationLifetime {
+    initialized: RwLock<retro_location_lifetime_status_t>,
+    deinitialized: RwLock<retro_location_lifetime_status_t>,
+    active: AtomicBool,
+}
+
+impl LocationLifetime {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_callbacks(&self, raw_cb: &retro_location_callback) -> Result<(), ErroHandle> {
+        *self.initialized.write()? = raw_cb.initialized;
+        *self.deinitialized.write()? = raw_cb.deinitialized;
+
+        Ok(())
+    }
+
+    #[doc = "avisa o core que a localização ja pode ser usada. so chama o core uma vez"]
+    pub fn notify_initialized(&self) -> Result<(), ErroHandle> {
+        if self.active.swap(true, Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        if let Some(initialized) = *self.initialized.read()? {
+            unsafe { initialized() }
+        }
+
+        Ok(())
+    }
+
+    pub fn notify_deinitialized(&self) -> Result<(), ErroHandle> {
+        if !self.active.swap(false, Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        if let Some(deinitialized) = *self.deinitialized.read()? {
+            unsafe { deinitialized() }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,168 @@
+use super::{RetroLogEntry, RetroLogger};
+use generics::{
+    constants::{LOG_DIR_NAME, LOG_EXTENSION_FILE, MAX_LOG_FILES, MAX_LOG_FILE_SIZE},
+    erro_handle::ErroHandle,
+    retro_paths::RetroPaths,
+};
+use std::{
+    fs::{self, File},
+    io::Write,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+struct LogFile {
+    //None apenas enquanto os arquivos estão sendo rotacionados
+    file: Option<File>,
+    size: u64,
+}
+
+/// Salva as mensagens do core em um arquivo. Cada sessão começa um arquivo novo e os anteriores
+/// sao renomeados para "rom.1.log", "rom.2.log"... ate o limite de [MAX_LOG_FILES].
+pub struct FileLogger {
+    path: PathBuf,
+    max_size: u64,
+    log_file: Mutex<LogFile>,
+}
+
+impl FileLogger {
+    pub fn new(path: PathBuf, max_size: u64) -> Result<Self, ErroHandle> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        rotate(&path)?;
+
+        Ok(Self {
+            log_file: Mutex::new(LogFile {
+                file: Some(File::create(&path)?),
+                size: 0,
+            }),
+            path,
+            max_size,
+        })
+    }
+
+    #[doc = "cria o arquivo em \"temps/logs/{core_name}/{rom_name}.log\" dentro de RetroPaths"]
+    pub fn for_session(
+        paths: &RetroPaths,
+        core_name: &str,
+        rom_name: &str,
+    ) -> Result<Self, ErroHandle> {
+        //o nome da rom pode ter pontos, então set_extension nao pode ser usado aqui
+        let path = PathBuf::from(&*paths.temps)
+            .join(LOG_DIR_NAME)
+            .join(core_name)
+            .join(format!("{}.{}", rom_name, LOG_EXTENSION_FILE));
+
+        Self::new(path, MAX_LOG_FILE_SIZE)
+    }
+
+    pub fn get_path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl RetroLogger for FileLogger {
+    fn log(&self, entry: &RetroLogEntry) -> Result<(), ErroHandle> {
+        let mut log_file = self.log_file.lock()?;
+        let line = entry.format() + "\n";
+
+        if log_file.size > 0 && log_file.size + line.len() as u64 > self.max_size {
+            //no windows um arquivo aberto nao pode ser renomeado
+            log_file.file.take();
+            rotate(&self.path)?;
+
+            log_file.file = Some(File::create(&self.path)?);
+            log_file.size = 0;
+        }
+
+        if let Some(file) = &mut log_file.file {
+            file.write_all(line.as_bytes())?;
+            log_file.size += line.len() as u64;
+        }
+
+        Ok(())
+    }
+}
+
+#[doc = "\"rom.log\" vira \"rom.1.log\", \"rom.1.log\" vira \"rom.2.log\" e o mais antigo e apagado"]
+fn rotate(path: &Path) -> Result<(), ErroHandle> {
+    if !path.exists() {
+        return Ok(());
+    }
+
+    let oldest = get_rotated_path(path, MAX_LOG_FILES - 1);
+    if oldest.exists() {
+        fs::remove_file(&oldest)?;
+    }
+
+    for index in (1..MAX_LOG_FILES - 1).rev() {
+        let from = get_rotated_path(path, index);
+
+        if from.exists() {
+            fs::rename(&from, get_rotated_path(path, index + 1))?;
+        }
+    }
+
+    fs::rename(path, get_rotated_path(path, 1))?;
+
+    Ok(())
+}
+
+fn get_rotated_path(path: &Path, index: usize) -> PathBuf {
+    let mut rotated = path.to_path_buf();
+    rotated.set_extension(format!("{}.{}", index, LOG_EXTENSION_FILE));
+
+    rotated
+}
+
+#[cfg(test)]
+mod test_file_logger {
+    use super::{get_rotated_path, FileLogger};
+    use crate::logger::{RetroLogEntry, RetroLogger};
+    use crate::test_tools::paths::get_paths;
+    use libretro_sys::binding_libretro::retro_log_level;
+    use std::{fs, path::PathBuf};
+
+    #[test]
+    fn rotate_files() {
+        let paths = get_paths().unwrap();
+        let dir = PathBuf::from(&*paths.temps).join("file_logger_test");
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join("rom.log");
+
+        let entry = RetroLogEntry::new(
+            retro_log_level::RETRO_LOG_INFO,
+            "core".to_string(),
+            "mensagem",
+        );
+        let line_len = entry.format().len() as u64 + 1;
+
+        //cada sessão começa um arquivo novo
+        FileLogger::new(path.clone(), 1024)
+            .unwrap()
+            .log(&entry)
+            .unwrap();
+        let logger = FileLogger::new(path.clone(), line_len * 2).unwrap();
+        assert!(get_rotated_path(&path, 1).exists());
+
+        //o terceiro registro nao cabe no arquivo atual
+        for _ in 0..3 {
+            logger.log(&entry).unwrap();
+        }
+
+        assert_eq!(fs::read_to_string(&path).unwrap().lines().count(), 1);
+        assert_eq!(
+            fs::read_to_string(get_rotated_path(&path, 1))
+                .unwrap()
+                .lines()
+                .count(),
+            2
+        );
+        assert!(get_rotated_path(&path, 2).exists());
+        assert!(!get_rotated_path(&path, 3).exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
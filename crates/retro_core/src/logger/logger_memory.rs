@@ -0,0 +1,84 @@
+use super::{RetroLogEntry, RetroLogger};
+use generics::erro_handle::ErroHandle;
+use std::{
+    collections::VecDeque,
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+/// Guarda as ultimas mensagens do core na memória para que a interface possa exibi-las.
+///
+/// Pode ser clonado: todos os clones compartilham as mesmas mensagens.
+#[derive(Clone, Debug)]
+pub struct MemoryLogger {
+    entries: Arc<Mutex<VecDeque<RetroLogEntry>>>,
+    capacity: usize,
+}
+
+impl MemoryLogger {
+    #[doc = "quando *capacity* e atingido as mensagens mais antigas sao descartadas"]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+            capacity,
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, VecDeque<RetroLogEntry>> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    #[doc = "retorna as mensagens da mais antiga para a mais nova"]
+    pub fn get_entries(&self) -> Vec<RetroLogEntry> {
+        self.lock().iter().cloned().collect()
+    }
+
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+}
+
+impl RetroLogger for MemoryLogger {
+    fn log(&self, entry: &RetroLogEntry) -> Result<(), ErroHandle> {
+        if self.capacity == 0 {
+            return Ok(());
+        }
+
+        let mut entries = self.lock();
+
+        if entries.len() >= self.capacity {
+            entries.pop_front();
+        }
+
+        entries.push_back(entry.clone());
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_memory_logger {
+    use super::MemoryLogger;
+    use crate::logger::{RetroLogEntry, RetroLogger};
+    use libretro_sys::binding_libretro::retro_log_level;
+
+    #[test]
+    fn ring_buffer() {
+        let logger = MemoryLogger::new(2);
+
+        for message in ["a", "b", "c"] {
+            let entry =
+                RetroLogEntry::new(retro_log_level::RETRO_LOG_INFO, "core".to_string(), message);
+            logger.log(&entry).unwrap();
+        }
+
+        let messages: Vec<String> = logger
+            .get_entries()
+            .into_iter()
+            .map(|entry| entry.message)
+            .collect();
+        assert_eq!(messages, vec!["b", "c"]);
+
+        logger.clear();
+        assert!(logger.get_entries().is_empty());
+    }
+}
@@ -0,0 +1,20 @@
+use super::{RetroLogEntry, RetroLogger};
+use generics::erro_handle::ErroHandle;
+use libretro_sys::binding_libretro::retro_log_level;
+
+/// Exibe as mensagens do core no terminal. Avisos e erros vão para o stderr.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StdoutLogger;
+
+impl RetroLogger for StdoutLogger {
+    fn log(&self, entry: &RetroLogEntry) -> Result<(), ErroHandle> {
+        match entry.level {
+            retro_log_level::RETRO_LOG_DEBUG | retro_log_level::RETRO_LOG_INFO => {
+                println!("{}", entry.format())
+            }
+            _ => eprintln!("{}", entry.format()),
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,218 @@
+mod logger_file;
+mod logger_memory;
+mod logger_stdout;
+
+pub use logger_file::FileLogger;
+pub use logger_memory::MemoryLogger;
+pub use logger_stdout::StdoutLogger;
+
+use generics::erro_handle::ErroHandle;
+use libretro_sys::binding_libretro::retro_log_level;
+use std::{
+    sync::{
+        atomic::{AtomicU32, Ordering},
+        Arc,
+    },
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RetroLogEntry {
+    pub level: retro_log_level,
+    pub core_name: String,
+    pub timestamp: SystemTime,
+    pub message: String,
+}
+
+impl RetroLogEntry {
+    pub fn new(level: retro_log_level, core_name: String, message: &str) -> Self {
+        Self {
+            level,
+            core_name,
+            timestamp: SystemTime::now(),
+            //quase todos os cores terminam as mensagens com uma quebra de linha
+            message: message.trim_end().to_string(),
+        }
+    }
+
+    #[doc = "formato usado pelo stdout e pelos arquivos de log: \"2024-05-01 13:45:10.250 [INFO] [core] mensagem\""]
+    pub fn format(&self) -> String {
+        format!(
+            "{} [{}] [{}] {}",
+            format_timestamp(self.timestamp),
+            level_name(self.level),
+            self.core_name,
+            self.message
+        )
+    }
+}
+
+/// Recebe todas as mensagens que o core envia pela interface de log da libretro.
+///
+/// Implementado por [StdoutLogger], [FileLogger] e [MemoryLogger]. Use [MultiLogger] para
+/// enviar as mensagens para mais de um deles e para filtrar pelo nível mínimo.
+pub trait RetroLogger {
+    #[doc = "pode ser chamada dentro do retro_run, então evite fazer trabalhos pesados aqui"]
+    fn log(&self, entry: &RetroLogEntry) -> Result<(), ErroHandle>;
+}
+
+/// Nível mínimo que uma mensagem precisa ter para ser registrada. Pode ser clonado e alterado
+/// de qualquer thread enquanto o core esta em execução.
+#[derive(Clone, Debug)]
+pub struct LogLevelFilter {
+    min_level: Arc<AtomicU32>,
+}
+
+impl Default for LogLevelFilter {
+    fn default() -> Self {
+        Self::new(retro_log_level::RETRO_LOG_INFO)
+    }
+}
+
+impl LogLevelFilter {
+    pub fn new(min_level: retro_log_level) -> Self {
+        Self {
+            min_level: Arc::new(AtomicU32::new(min_level as u32)),
+        }
+    }
+
+    pub fn set(&self, min_level: retro_log_level) {
+        self.min_level.store(min_level as u32, Ordering::SeqCst);
+    }
+
+    pub fn get(&self) -> retro_log_level {
+        match self.min_level.load(Ordering::SeqCst) {
+            0 => retro_log_level::RETRO_LOG_DEBUG,
+            1 => retro_log_level::RETRO_LOG_INFO,
+            2 => retro_log_level::RETRO_LOG_WARN,
+            _ => retro_log_level::RETRO_LOG_ERROR,
+        }
+    }
+
+    pub fn allows(&self, level: retro_log_level) -> bool {
+        level as u32 >= self.min_level.load(Ordering::SeqCst)
+    }
+}
+
+/// Envia cada mensagem para todos os loggers, ignorando as que estão abaixo do nível mínimo.
+pub struct MultiLogger {
+    loggers: Vec<Box<dyn RetroLogger>>,
+    filter: LogLevelFilter,
+}
+
+impl MultiLogger {
+    pub fn new(filter: LogLevelFilter) -> Self {
+        Self {
+            loggers: Vec::new(),
+            filter,
+        }
+    }
+
+    pub fn add(mut self, logger: Box<dyn RetroLogger>) -> Self {
+        self.loggers.push(logger);
+        self
+    }
+}
+
+impl RetroLogger for MultiLogger {
+    fn log(&self, entry: &RetroLogEntry) -> Result<(), ErroHandle> {
+        if !self.filter.allows(entry.level) {
+            return Ok(());
+        }
+
+        //um logger com problema nao deve impedir que os outros recebam a mensagem
+        let mut result = Ok(());
+
+        for logger in &self.loggers {
+            if let Err(e) = logger.log(entry) {
+                result = Err(e);
+            }
+        }
+
+        result
+    }
+}
+
+pub fn level_name(level: retro_log_level) -> &'static str {
+    match level {
+        retro_log_level::RETRO_LOG_DEBUG => "DEBUG",
+        retro_log_level::RETRO_LOG_INFO => "INFO",
+        retro_log_level::RETRO_LOG_WARN => "WARN",
+        _ => "ERROR",
+    }
+}
+
+#[doc = "converte para \"AAAA-MM-DD HH:MM:SS.mmm\" em UTC"]
+pub fn format_timestamp(timestamp: SystemTime) -> String {
+    let duration = timestamp.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = duration.as_secs();
+    let (year, month, day) = civil_from_days((secs / 86_400) as i64);
+    let secs_of_day = secs % 86_400;
+
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}.{:03}",
+        year,
+        month,
+        day,
+        secs_of_day / 3600,
+        secs_of_day % 3600 / 60,
+        secs_of_day % 60,
+        duration.subsec_millis()
+    )
+}
+
+//algoritmo de Howard Hinnant para converter os dias desde 1970-01-01 em uma data
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod test_logger {
+    use super::{format_timestamp, LogLevelFilter, MemoryLogger, MultiLogger, RetroLogEntry};
+    use crate::logger::RetroLogger;
+    use libretro_sys::binding_libretro::retro_log_level;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    #[test]
+    fn timestamp() {
+        let time = UNIX_EPOCH + Duration::from_millis(1_714_571_110_250);
+        assert_eq!(format_timestamp(time), "2024-05-01 13:45:10.250");
+        assert_eq!(format_timestamp(UNIX_EPOCH), "1970-01-01 00:00:00.000");
+    }
+
+    #[test]
+    fn filter_level() {
+        let filter = LogLevelFilter::new(retro_log_level::RETRO_LOG_WARN);
+        let memory = MemoryLogger::new(10);
+        let logger = MultiLogger::new(filter.clone()).add(Box::new(memory.clone()));
+
+        let entry = |level| RetroLogEntry::new(level, "core".to_string(), "mensagem\n");
+
+        logger.log(&entry(retro_log_level::RETRO_LOG_INFO)).unwrap();
+        logger
+            .log(&entry(retro_log_level::RETRO_LOG_ERROR))
+            .unwrap();
+        assert_eq!(memory.get_entries().len(), 1);
+
+        //o nível pode mudar enquanto o core esta em execução
+        filter.set(retro_log_level::RETRO_LOG_DEBUG);
+        logger
+            .log(&entry(retro_log_level::RETRO_LOG_DEBUG))
+            .unwrap();
+
+        let entries = memory.get_entries();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[1].level, retro_log_level::RETRO_LOG_DEBUG);
+        assert_eq!(entries[1].message, "mensagem");
+    }
+}
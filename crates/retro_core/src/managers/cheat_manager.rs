@@ -0,0 +1,232 @@
+use generics::constants::CHEAT_EXTENSION_FILE;
+use generics::erro_handle::ErroHandle;
+use std::{fs, path::PathBuf, sync::RwLock};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Cheat {
+    pub index: usize,
+    pub desc: String,
+    #[doc = "pode conter varios códigos separados por '+'. O core e quem decide como usa-los"]
+    pub code: String,
+    pub enabled: bool,
+}
+
+/// Lê e salva os cheats no formato usado pelo RetroArch (.cht):
+///
+/// ```text
+/// cheats = 1
+///
+/// cheat0_desc = "Vidas infinitas"
+/// cheat0_code = "00C1-5F-00"
+/// cheat0_enable = false
+/// ```
+#[derive(Debug, Default)]
+pub struct CheatManager {
+    file_path: RwLock<Option<PathBuf>>,
+    #[doc = "todas as chaves do arquivo na ordem original, para que nada seja perdido ao salvar"]
+    entries: RwLock<Vec<(String, String)>>,
+    cheats: RwLock<Vec<Cheat>>,
+}
+
+impl CheatManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[doc = "carrega os cheats de *cheats_dir*/*rom_name*.cht. Se o arquivo nao existir a lista fica vazia"]
+    pub fn load(&self, cheats_dir: &str, rom_name: &str) -> Result<(), ErroHandle> {
+        let file_path =
+            PathBuf::from(cheats_dir).join(format!("{}.{}", rom_name, CHEAT_EXTENSION_FILE));
+
+        let (entries, cheats) = if file_path.exists() {
+            parse_cht(&fs::read_to_string(&file_path)?)
+        } else {
+            (Vec::new(), Vec::new())
+        };
+
+        *self.file_path.write()? = Some(file_path);
+        *self.entries.write()? = entries;
+        *self.cheats.write()? = cheats;
+
+        Ok(())
+    }
+
+    pub fn get_cheats(&self) -> Result<Vec<Cheat>, ErroHandle> {
+        Ok(self.cheats.read()?.clone())
+    }
+
+    pub fn get_enabled(&self) -> Result<Vec<Cheat>, ErroHandle> {
+        Ok(self
+            .cheats
+            .read()?
+            .iter()
+            .filter(|cheat| cheat.enabled)
+            .cloned()
+            .collect())
+    }
+
+    pub fn set_enabled(&self, index: usize, enabled: bool) -> Result<(), ErroHandle> {
+        match self.cheats.write()?.get_mut(index) {
+            Some(cheat) => cheat.enabled = enabled,
+            None => {
+                return Err(ErroHandle {
+                    message: format!("O cheat {} nao existe", index),
+                })
+            }
+        }
+
+        self.set_entry(&format!("cheat{}_enable", index), enabled.to_string())?;
+        self.write_in_file()
+    }
+
+    #[doc = "desativa todos os cheats"]
+    pub fn reset(&self) -> Result<(), ErroHandle> {
+        let count = {
+            let mut cheats = self.cheats.write()?;
+
+            for cheat in cheats.iter_mut() {
+                cheat.enabled = false;
+            }
+
+            cheats.len()
+        };
+
+        for index in 0..count {
+            self.set_entry(&format!("cheat{}_enable", index), "false".to_string())?;
+        }
+
+        self.write_in_file()
+    }
+
+    fn set_entry(&self, key: &str, value: String) -> Result<(), ErroHandle> {
+        let mut entries = self.entries.write()?;
+
+        match entries.iter_mut().find(|(k, _)| k == key) {
+            Some((_, v)) => *v = value,
+            None => entries.push((key.to_string(), value)),
+        }
+
+        Ok(())
+    }
+
+    fn write_in_file(&self) -> Result<(), ErroHandle> {
+        let file_path = match &*self.file_path.read()? {
+            Some(file_path) => file_path.clone(),
+            None => return Ok(()),
+        };
+
+        let mut content = String::new();
+
+        for (key, value) in &*self.entries.read()? {
+            content.push_str(&format!("{} = {}\n", key, value));
+        }
+
+        fs::write(file_path, content)?;
+
+        Ok(())
+    }
+}
+
+fn unquote(value: &str) -> String {
+    value
+        .strip_prefix('"')
+        .and_then(|v| v.strip_suffix('"'))
+        .unwrap_or(value)
+        .to_string()
+}
+
+fn parse_cht(content: &str) -> (Vec<(String, String)>, Vec<Cheat>) {
+    let mut entries: Vec<(String, String)> = Vec::new();
+
+    for line in content.lines() {
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        if let Some((key, value)) = line.split_once('=') {
+            entries.push((key.trim().to_string(), value.trim().to_string()));
+        }
+    }
+
+    let get = |key: &str| {
+        entries
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| unquote(v))
+    };
+
+    let count = get("cheats")
+        .and_then(|count| count.parse::<usize>().ok())
+        .unwrap_or(0);
+
+    let mut cheats = Vec::with_capacity(count);
+
+    for index in 0..count {
+        cheats.push(Cheat {
+            index,
+            desc: get(&format!("cheat{}_desc", index)).unwrap_or_default(),
+            code: get(&format!("cheat{}_code", index)).unwrap_or_default(),
+            enabled: get(&format!("cheat{}_enable", index)).is_some_and(|v| v == "true"),
+        });
+    }
+
+    (entries, cheats)
+}
+
+#[cfg(test)]
+mod test_cheat_manager {
+    use super::{parse_cht, CheatManager};
+    use std::fs;
+
+    const CHT: &str = r#"
+cheats = 2
+
+cheat0_desc = "Vidas infinitas"
+cheat0_code = "00C1-5F-00+00C2-5F-00"
+cheat0_enable = false
+
+cheat1_desc = "Tempo infinito"
+cheat1_code = "7E0F4C63"
+cheat1_enable = true
+cheat1_handler = 0
+"#;
+
+    #[test]
+    fn parse() {
+        let (_, cheats) = parse_cht(CHT);
+
+        assert_eq!(cheats.len(), 2);
+        assert_eq!(cheats[0].desc, "Vidas infinitas");
+        assert_eq!(cheats[0].code, "00C1-5F-00+00C2-5F-00");
+        assert!(!cheats[0].enabled);
+        assert_eq!(cheats[1].index, 1);
+        assert!(cheats[1].enabled);
+    }
+
+    #[test]
+    fn save_enabled_state() {
+        let dir = "retro_out_test/cheats_test";
+        fs::create_dir_all(dir).unwrap();
+        fs::write(format!("{}/rom.cht", dir), CHT).unwrap();
+
+        let manager = CheatManager::new();
+        manager.load(dir, "rom").unwrap();
+        manager.set_enabled(0, true).unwrap();
+
+        let other = CheatManager::new();
+        other.load(dir, "rom").unwrap();
+
+        let cheats = other.get_cheats().unwrap();
+        assert!(cheats[0].enabled);
+        assert!(cheats[1].enabled);
+
+        //as chaves que nao sao usadas pelo tinic devem continuar no arquivo
+        let content = fs::read_to_string(format!("{}/rom.cht", dir)).unwrap();
+        assert!(content.contains("cheat1_handler = 0"));
+
+        other.reset().unwrap();
+        assert!(other.get_enabled().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,429 @@
+use crate::tools::ffi_tools::get_str_from_ptr;
+use generics::erro_handle::ErroHandle;
+use libretro_sys::binding_libretro::{
+    retro_memory_descriptor, retro_memory_map, RETRO_MEMDESC_CONST, RETRO_MEMORY_RTC,
+    RETRO_MEMORY_SAVE_RAM, RETRO_MEMORY_SYSTEM_RAM, RETRO_MEMORY_VIDEO_RAM,
+};
+use std::{ptr::slice_from_raw_parts, sync::RwLock};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MemoryRegion {
+    SaveRam,
+    Rtc,
+    SystemRam,
+    VideoRam,
+}
+
+impl MemoryRegion {
+    #[doc = "id usado por retro_get_memory_data e retro_get_memory_size"]
+    pub fn id(&self) -> u32 {
+        match self {
+            MemoryRegion::SaveRam => RETRO_MEMORY_SAVE_RAM,
+            MemoryRegion::Rtc => RETRO_MEMORY_RTC,
+            MemoryRegion::SystemRam => RETRO_MEMORY_SYSTEM_RAM,
+            MemoryRegion::VideoRam => RETRO_MEMORY_VIDEO_RAM,
+        }
+    }
+
+    pub fn from_id(id: u32) -> Option<Self> {
+        match id {
+            RETRO_MEMORY_SAVE_RAM => Some(MemoryRegion::SaveRam),
+            RETRO_MEMORY_RTC => Some(MemoryRegion::Rtc),
+            RETRO_MEMORY_SYSTEM_RAM => Some(MemoryRegion::SystemRam),
+            RETRO_MEMORY_VIDEO_RAM => Some(MemoryRegion::VideoRam),
+            _ => None,
+        }
+    }
+}
+
+/// Uma parte do mapa de memoria enviado pelo core em RETRO_ENVIRONMENT_SET_MEMORY_MAPS.
+///
+/// Os campos *select*, *disconnect* e *len* ja estão normalizados, ou seja, nunca sao 0.
+#[derive(Clone, Debug, Default)]
+pub struct MemoryDescriptor {
+    pub flags: u64,
+    #[doc = "endereço do buffer do core. 0 indica uma area que nao pode ser lida"]
+    pub ptr: usize,
+    pub offset: usize,
+    pub start: usize,
+    pub select: usize,
+    pub disconnect: usize,
+    pub len: usize,
+    pub addr_space: String,
+    disconnect_mask: usize,
+}
+
+impl MemoryDescriptor {
+    pub fn is_read_only(&self) -> bool {
+        self.flags & RETRO_MEMDESC_CONST as u64 != 0
+    }
+
+    fn contains(&self, address: usize) -> bool {
+        (self.start ^ address) & self.select == 0
+    }
+
+    fn to_offset(&self, address: usize) -> usize {
+        reduce(
+            (address - self.start) & self.disconnect_mask,
+            self.disconnect,
+        )
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct MemoryManager {
+    descriptors: RwLock<Vec<MemoryDescriptor>>,
+}
+
+impl MemoryManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub unsafe fn set_memory_maps(
+        &self,
+        raw_map: *const retro_memory_map,
+    ) -> Result<(), ErroHandle> {
+        if raw_map.is_null() {
+            return Err(ErroHandle::new(
+                "O mapa de memoria enviado pelo core e invalido",
+            ));
+        }
+
+        let map = *raw_map;
+        let raw_descriptors: &[retro_memory_descriptor] =
+            if map.descriptors.is_null() || map.num_descriptors == 0 {
+                &[]
+            } else {
+                &*slice_from_raw_parts(map.descriptors, map.num_descriptors as usize)
+            };
+
+        let descriptors = raw_descriptors
+            .iter()
+            .map(|desc| MemoryDescriptor {
+                flags: desc.flags,
+                ptr: desc.ptr as usize,
+                offset: desc.offset,
+                start: desc.start,
+                select: desc.select,
+                disconnect: desc.disconnect,
+                len: desc.len,
+                addr_space: if desc.addrspace.is_null() {
+                    String::new()
+                } else {
+                    get_str_from_ptr(desc.addrspace)
+                },
+                disconnect_mask: 0,
+            })
+            .collect();
+
+        self.set_descriptors(descriptors)
+    }
+
+    pub fn set_descriptors(
+        &self,
+        mut descriptors: Vec<MemoryDescriptor>,
+    ) -> Result<(), ErroHandle> {
+        let mut current = self.descriptors.write()?;
+        current.clear();
+
+        preprocess_descriptors(&mut descriptors)?;
+        *current = descriptors;
+
+        Ok(())
+    }
+
+    pub fn get_descriptors(&self) -> Result<Vec<MemoryDescriptor>, ErroHandle> {
+        Ok(self.descriptors.read()?.clone())
+    }
+
+    pub fn clear(&self) -> Result<(), ErroHandle> {
+        self.descriptors.write()?.clear();
+
+        Ok(())
+    }
+
+    #[doc = "lê *len* bytes a partir de *address*, usando os endereços do próprio sistema emulado"]
+    pub fn read(&self, address: usize, len: usize) -> Result<Vec<u8>, ErroHandle> {
+        let descriptors = self.descriptors.read()?;
+        let end = get_range_end(&descriptors, address, len)?;
+
+        let mut data = Vec::with_capacity(len);
+
+        for byte_address in address..end {
+            let (ptr, _) = find_byte(&descriptors, byte_address)?;
+            data.push(unsafe { *ptr });
+        }
+
+        Ok(data)
+    }
+
+    #[doc = "escreve *data* a partir de *address*, usando os endereços do próprio sistema emulado"]
+    pub fn write(&self, address: usize, data: &[u8]) -> Result<(), ErroHandle> {
+        let descriptors = self.descriptors.read()?;
+        let end = get_range_end(&descriptors, address, data.len())?;
+
+        //nada e escrito se algum dos endereços for invalido
+        let mut targets = Vec::with_capacity(data.len());
+
+        for byte_address in address..end {
+            let (ptr, read_only) = find_byte(&descriptors, byte_address)?;
+
+            if read_only {
+                return Err(ErroHandle {
+                    message: format!("O endereço {:#x} e somente leitura", byte_address),
+                });
+            }
+
+            targets.push(ptr);
+        }
+
+        for (ptr, value) in targets.into_iter().zip(data) {
+            unsafe { *ptr = *value };
+        }
+
+        Ok(())
+    }
+}
+
+//com os espelhos um intervalo enorme poderia ser valido, então o acesso também e limitado ao
+//tamanho de toda a memoria mapeada
+fn get_range_end(
+    descriptors: &[MemoryDescriptor],
+    address: usize,
+    len: usize,
+) -> Result<usize, ErroHandle> {
+    let mapped = descriptors
+        .iter()
+        .fold(0usize, |total, desc| total.saturating_add(desc.len));
+
+    if len > mapped {
+        return Err(ErroHandle {
+            message: format!(
+                "O acesso de {} bytes e maior que toda a memoria mapeada ({} bytes)",
+                len, mapped
+            ),
+        });
+    }
+
+    address.checked_add(len).ok_or(ErroHandle {
+        message: format!(
+            "O acesso de {} bytes a partir de {:#x} ultrapassa o ultimo endereço",
+            len, address
+        ),
+    })
+}
+
+fn find_byte(
+    descriptors: &[MemoryDescriptor],
+    address: usize,
+) -> Result<(*mut u8, bool), ErroHandle> {
+    for desc in descriptors {
+        if !desc.contains(address) {
+            continue;
+        }
+
+        if desc.ptr == 0 {
+            return Err(ErroHandle {
+                message: format!("O endereço {:#x} nao pode ser acessado", address),
+            });
+        }
+
+        let offset = desc.to_offset(address);
+
+        if offset >= desc.len {
+            continue;
+        }
+
+        let ptr = (desc.ptr + desc.offset + offset) as *mut u8;
+
+        return Ok((ptr, desc.is_read_only()));
+    }
+
+    Err(ErroHandle {
+        message: format!("O endereço {:#x} nao esta mapeado", address),
+    })
+}
+
+// As funções abaixo seguem as regras descritas em libretro.h para "retro_memory_descriptor"
+// e sao as mesmas usadas pelo RetroArch.
+
+fn add_bits_down(mut n: usize) -> usize {
+    n |= n >> 1;
+    n |= n >> 2;
+    n |= n >> 4;
+    n |= n >> 8;
+    n |= n >> 16;
+    #[cfg(target_pointer_width = "64")]
+    {
+        n |= n >> 32;
+    }
+
+    n
+}
+
+fn highest_bit(n: usize) -> usize {
+    let n = add_bits_down(n);
+    n ^ (n >> 1)
+}
+
+#[doc = "insere um bit 0 em cada posição que esta ligada em *mask*"]
+fn inflate(mut addr: usize, mut mask: usize) -> usize {
+    while mask != 0 {
+        let tmp = (mask - 1) & !mask;
+        addr = ((addr & !tmp) << 1) | (addr & tmp);
+        mask &= mask - 1;
+    }
+
+    addr
+}
+
+#[doc = "remove os bits de *addr* que estão ligados em *mask*"]
+fn reduce(mut addr: usize, mut mask: usize) -> usize {
+    while mask != 0 {
+        let tmp = (mask - 1) & !mask;
+        addr = (addr & tmp) | ((addr >> 1) & !tmp);
+        mask = (mask & (mask - 1)) >> 1;
+    }
+
+    addr
+}
+
+fn preprocess_descriptors(descriptors: &mut [MemoryDescriptor]) -> Result<(), ErroHandle> {
+    let mut top_addr = 1;
+
+    for desc in descriptors.iter() {
+        if desc.select != 0 {
+            top_addr |= desc.select;
+        } else {
+            top_addr |= desc.start + desc.len.saturating_sub(1);
+        }
+    }
+
+    let top_addr = add_bits_down(top_addr);
+
+    for desc in descriptors.iter_mut() {
+        if desc.select == 0 {
+            if desc.len == 0 || !desc.len.is_power_of_two() {
+                return Err(ErroHandle::new(
+                    "O mapa de memoria enviado pelo core tem um descritor sem select e com um tamanho invalido",
+                ));
+            }
+
+            desc.select = top_addr & !inflate(add_bits_down(desc.len - 1), desc.disconnect);
+        }
+
+        if desc.len == 0 {
+            desc.len = add_bits_down(reduce(top_addr & !desc.select, desc.disconnect)) + 1;
+        }
+
+        if desc.start & !desc.select != 0 {
+            return Err(ErroHandle::new(
+                "O mapa de memoria enviado pelo core tem um descritor com o start fora do select",
+            ));
+        }
+
+        let highest_reachable = inflate(desc.len - 1, desc.disconnect);
+
+        //bits que nunca chegam ao buffer do core apenas espelham a memoria
+        loop {
+            let unused = highest_bit(top_addr & !desc.select & !desc.disconnect);
+
+            if unused <= highest_bit(highest_reachable) {
+                break;
+            }
+
+            desc.disconnect |= unused;
+        }
+
+        desc.disconnect_mask = add_bits_down(desc.len - 1);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test_memory_manager {
+    use super::{MemoryDescriptor, MemoryManager, RETRO_MEMDESC_CONST};
+
+    fn descriptor(buffer: &mut [u8], start: usize, select: usize) -> MemoryDescriptor {
+        MemoryDescriptor {
+            ptr: buffer.as_mut_ptr() as usize,
+            start,
+            select,
+            len: buffer.len(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn read_and_write_by_address() {
+        let mut wram = vec![0u8; 0x2000];
+        let mut sram = vec![0u8; 0x800];
+
+        let manager = MemoryManager::new();
+        manager
+            .set_descriptors(vec![
+                descriptor(&mut wram, 0xC000, 0),
+                descriptor(&mut sram, 0xA000, 0),
+            ])
+            .unwrap();
+
+        manager.write(0xC010, &[1, 2, 3]).unwrap();
+        manager.write(0xA7FF, &[9]).unwrap();
+
+        assert_eq!(wram[0x10..0x13], [1, 2, 3]);
+        assert_eq!(sram[0x7FF], 9);
+        assert_eq!(manager.read(0xC010, 3).unwrap(), vec![1, 2, 3]);
+
+        //endereços fora de qualquer descritor
+        assert!(manager.read(0x8000, 1).is_err());
+    }
+
+    #[test]
+    fn mirrored_memory() {
+        //2KB de ram espelhados em 0x0000..0x2000, como no NES
+        let mut ram = vec![0u8; 0x800];
+
+        let manager = MemoryManager::new();
+        manager
+            .set_descriptors(vec![descriptor(&mut ram, 0x0000, 0xE000)])
+            .unwrap();
+
+        manager.write(0x0805, &[42]).unwrap();
+
+        assert_eq!(ram[0x5], 42);
+        assert_eq!(manager.read(0x1805, 1).unwrap(), vec![42]);
+    }
+
+    #[test]
+    fn read_only_memory() {
+        let mut rom = vec![7u8; 0x100];
+        let mut desc = descriptor(&mut rom, 0x100, 0);
+        desc.flags = RETRO_MEMDESC_CONST as u64;
+
+        let manager = MemoryManager::new();
+        manager.set_descriptors(vec![desc]).unwrap();
+
+        assert_eq!(manager.read(0x1FF, 1).unwrap(), vec![7]);
+        assert!(manager.write(0x100, &[0]).is_err());
+    }
+
+    #[test]
+    fn invalid_range() {
+        let mut ram = vec![0u8; 0x100];
+
+        let manager = MemoryManager::new();
+        manager
+            .set_descriptors(vec![descriptor(&mut ram, 0x0000, 0)])
+            .unwrap();
+
+        //o tamanho nao pode fazer o endereço final passar de usize::MAX
+        assert!(manager.read(0x10, usize::MAX).is_err());
+        assert!(manager.write(usize::MAX, &[1, 2]).is_err());
+
+        //os espelhos cobrem todos os endereços, mas o acesso nao pode passar do tamanho da memoria
+        assert!(manager.read(0xF0, 0x101).is_err());
+        assert_eq!(manager.read(0xF0, 0x100).unwrap().len(), 0x100);
+    }
+}
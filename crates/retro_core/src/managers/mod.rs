@@ -1,2 +1,7 @@
 pub mod args_manager;
+pub mod cheat_manager;
+pub mod memory_manager;
+pub mod movie_manager;
 pub mod option_manager;
+pub mod perf_manager;
+pub mod rewind_manager;
@@ -0,0 +1,820 @@
+use crate::managers::memory_manager::MemoryRegion;
+use generics::erro_handle::ErroHandle;
+use std::{
+    fs,
+    path::Path,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Mutex,
+    },
+};
+
+const MOVIE_MAGIC: &[u8; 4] = b"TMOV";
+#[doc = "versão do formato dos filmes. deve ser incrementada sempre que o formato mudar"]
+pub const MOVIE_VERSION: u16 = 1;
+
+const START_POWER_ON: u8 = 0;
+const START_SAVE_STATE: u8 = 1;
+
+const HASH_SAVE_STATE: u8 = 0;
+const HASH_MEMORY: u8 = 1;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InputSample {
+    pub port: i16,
+    pub device: i16,
+    pub index: i16,
+    pub id: i16,
+    #[doc = "valor retornado para o core pela input_state_callback"]
+    pub value: i16,
+}
+
+impl InputSample {
+    fn is_same_input(&self, port: i16, device: i16, index: i16, id: i16) -> bool {
+        self.port == port && self.device == device && self.index == index && self.id == id
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum MovieStart {
+    #[doc = "o jogo foi reiniciado (retro_reset) antes do primeiro frame. *save_ram* e a SRAM do jogo
+    no inicio da gravação, vazia se o core nao tiver SRAM"]
+    PowerOn { save_ram: Vec<u8> },
+    #[doc = "estado do core (retro_serialize) no inicio da gravação"]
+    SaveState(Vec<u8>),
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MovieOrigin {
+    #[default]
+    PowerOn,
+    CurrentState,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StateHashSource {
+    #[doc = "o state inteiro retornado pelo retro_serialize"]
+    SaveState,
+    #[doc = "apenas as regiões escolhidas. E mais rápido, mas diferenças fora delas nao sao encontradas"]
+    Memory(Vec<MemoryRegion>),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StateHashConfig {
+    #[doc = "um hash e guardado a cada *interval* frames"]
+    pub interval: u32,
+    pub source: StateHashSource,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct StateHash {
+    #[doc = "frame do filme, começando em 0, depois do qual o hash foi calculado"]
+    pub frame: u32,
+    pub hash: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MovieDesync {
+    pub frame: u32,
+    pub expected: u64,
+    pub found: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MovieVerification {
+    #[doc = "quantidade de frames do filme"]
+    pub frames: usize,
+    #[doc = "quantidade de hashes comparados antes de terminar o filme ou encontrar a primeira diferença"]
+    pub checked_hashes: usize,
+    #[doc = "primeiro frame em que o jogo ficou diferente da gravação. None se o filme esta sincronizado"]
+    pub desync: Option<MovieDesync>,
+}
+
+#[doc = "FNV-1a de 64 bits. Nao e criptográfico, mas e suficiente para comparar dois states"]
+#[derive(Clone, Copy, Debug)]
+pub struct StateHasher {
+    hash: u64,
+}
+
+impl Default for StateHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StateHasher {
+    pub fn new() -> Self {
+        Self {
+            hash: FNV_OFFSET_BASIS,
+        }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        for byte in data {
+            self.hash ^= *byte as u64;
+            self.hash = self.hash.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    pub fn finish(&self) -> u64 {
+        self.hash
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MovieMode {
+    Idle,
+    Recording,
+    Playing,
+}
+
+/// Inputs lidos pelo core em cada frame desde o inicio da gravação.
+///
+/// O arquivo começa com "TMOV" e a versão do formato. Todos os números sao little endian.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Movie {
+    pub core_name: String,
+    pub core_version: String,
+    pub rom_name: String,
+    pub start: MovieStart,
+    #[doc = "cada frame guarda as chamadas da input_state_callback na ordem em que foram feitas"]
+    pub frames: Vec<Vec<InputSample>>,
+    #[doc = "None quando o filme nao guarda hashes do estado do jogo"]
+    pub hash_config: Option<StateHashConfig>,
+    #[doc = "hashes em ordem crescente de frame"]
+    pub hashes: Vec<StateHash>,
+}
+
+impl Movie {
+    pub fn new(
+        core_name: String,
+        core_version: String,
+        rom_name: String,
+        start: MovieStart,
+    ) -> Self {
+        Self {
+            core_name,
+            core_version,
+            rom_name,
+            start,
+            frames: Vec::new(),
+            hash_config: None,
+            hashes: Vec::new(),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), ErroHandle> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        fs::write(path, self.to_bytes())?;
+
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> Result<Self, ErroHandle> {
+        Self::from_bytes(&fs::read(path)?)
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+
+        data.extend_from_slice(MOVIE_MAGIC);
+        data.extend_from_slice(&MOVIE_VERSION.to_le_bytes());
+
+        write_bytes(&mut data, self.core_name.as_bytes());
+        write_bytes(&mut data, self.core_version.as_bytes());
+        write_bytes(&mut data, self.rom_name.as_bytes());
+
+        match &self.start {
+            MovieStart::PowerOn { save_ram } => {
+                data.push(START_POWER_ON);
+                write_bytes(&mut data, save_ram);
+            }
+            MovieStart::SaveState(state) => {
+                data.push(START_SAVE_STATE);
+                write_bytes(&mut data, state);
+            }
+        }
+
+        match &self.hash_config {
+            None => data.push(0),
+            Some(config) => {
+                data.push(1);
+                data.extend_from_slice(&config.interval.to_le_bytes());
+
+                match &config.source {
+                    StateHashSource::SaveState => data.push(HASH_SAVE_STATE),
+                    StateHashSource::Memory(regions) => {
+                        data.push(HASH_MEMORY);
+                        data.extend_from_slice(&(regions.len() as u32).to_le_bytes());
+
+                        for region in regions {
+                            data.extend_from_slice(&region.id().to_le_bytes());
+                        }
+                    }
+                }
+            }
+        }
+
+        data.extend_from_slice(&(self.frames.len() as u32).to_le_bytes());
+
+        for frame in &self.frames {
+            data.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+
+            for sample in frame {
+                for value in [
+                    sample.port,
+                    sample.device,
+                    sample.index,
+                    sample.id,
+                    sample.value,
+                ] {
+                    data.extend_from_slice(&value.to_le_bytes());
+                }
+            }
+        }
+
+        data.extend_from_slice(&(self.hashes.len() as u32).to_le_bytes());
+
+        for state_hash in &self.hashes {
+            data.extend_from_slice(&state_hash.frame.to_le_bytes());
+            data.extend_from_slice(&state_hash.hash.to_le_bytes());
+        }
+
+        data
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Result<Self, ErroHandle> {
+        let mut reader = MovieReader { data, pos: 0 };
+
+        if reader.read(MOVIE_MAGIC.len())? != MOVIE_MAGIC {
+            return Err(ErroHandle::new("O arquivo nao e um filme do tinic"));
+        }
+
+        let version = reader.read_u16()?;
+        if version != MOVIE_VERSION {
+            return Err(ErroHandle {
+                message: format!("A versão {} do filme nao e suportada", version),
+            });
+        }
+
+        let core_name = reader.read_string()?;
+        let core_version = reader.read_string()?;
+        let rom_name = reader.read_string()?;
+
+        let start = match reader.read(1)?[0] {
+            START_POWER_ON => MovieStart::PowerOn {
+                save_ram: reader.read_bytes()?.to_vec(),
+            },
+            START_SAVE_STATE => MovieStart::SaveState(reader.read_bytes()?.to_vec()),
+            kind => {
+                return Err(ErroHandle {
+                    message: format!("Inicio do filme desconhecido: {}", kind),
+                })
+            }
+        };
+
+        let hash_config = if reader.read(1)?[0] != 0 {
+            let interval = reader.read_u32()?;
+
+            let source = match reader.read(1)?[0] {
+                HASH_SAVE_STATE => StateHashSource::SaveState,
+                HASH_MEMORY => {
+                    let mut regions = Vec::new();
+
+                    for _ in 0..reader.read_u32()? {
+                        let id = reader.read_u32()?;
+                        regions.push(MemoryRegion::from_id(id).ok_or(ErroHandle {
+                            message: format!("Região de memoria desconhecida: {}", id),
+                        })?);
+                    }
+
+                    StateHashSource::Memory(regions)
+                }
+                kind => {
+                    return Err(ErroHandle {
+                        message: format!("Origem do hash desconhecida: {}", kind),
+                    })
+                }
+            };
+
+            Some(StateHashConfig { interval, source })
+        } else {
+            None
+        };
+
+        let frame_count = reader.read_u32()?;
+        let mut frames = Vec::new();
+
+        for _ in 0..frame_count {
+            let sample_count = reader.read_u32()?;
+            let mut frame = Vec::new();
+
+            for _ in 0..sample_count {
+                frame.push(InputSample {
+                    port: reader.read_i16()?,
+                    device: reader.read_i16()?,
+                    index: reader.read_i16()?,
+                    id: reader.read_i16()?,
+                    value: reader.read_i16()?,
+                });
+            }
+
+            frames.push(frame);
+        }
+
+        let mut hashes = Vec::new();
+
+        for _ in 0..reader.read_u32()? {
+            hashes.push(StateHash {
+                frame: reader.read_u32()?,
+                hash: reader.read_u64()?,
+            });
+        }
+
+        Ok(Self {
+            core_name,
+            core_version,
+            rom_name,
+            start,
+            frames,
+            hash_config,
+            hashes,
+        })
+    }
+}
+
+fn write_bytes(data: &mut Vec<u8>, bytes: &[u8]) {
+    data.extend_from_slice(&(bytes.len() as u64).to_le_bytes());
+    data.extend_from_slice(bytes);
+}
+
+struct MovieReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> MovieReader<'a> {
+    fn read(&mut self, len: usize) -> Result<&'a [u8], ErroHandle> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|end| *end <= self.data.len())
+            .ok_or(ErroHandle::new("O arquivo do filme esta incompleto"))?;
+
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+
+        Ok(bytes)
+    }
+
+    fn read_u16(&mut self) -> Result<u16, ErroHandle> {
+        let mut bytes = [0; 2];
+        bytes.copy_from_slice(self.read(2)?);
+
+        Ok(u16::from_le_bytes(bytes))
+    }
+
+    fn read_i16(&mut self) -> Result<i16, ErroHandle> {
+        Ok(self.read_u16()? as i16)
+    }
+
+    fn read_u32(&mut self) -> Result<u32, ErroHandle> {
+        let mut bytes = [0; 4];
+        bytes.copy_from_slice(self.read(4)?);
+
+        Ok(u32::from_le_bytes(bytes))
+    }
+
+    fn read_u64(&mut self) -> Result<u64, ErroHandle> {
+        let mut bytes = [0; 8];
+        bytes.copy_from_slice(self.read(8)?);
+
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    fn read_bytes(&mut self) -> Result<&'a [u8], ErroHandle> {
+        let len = usize::try_from(self.read_u64()?)
+            .map_err(|_| ErroHandle::new("O arquivo do filme esta corrompido"))?;
+
+        self.read(len)
+    }
+
+    fn read_string(&mut self) -> Result<String, ErroHandle> {
+        Ok(String::from_utf8_lossy(self.read_bytes()?).to_string())
+    }
+}
+
+#[derive(Debug)]
+enum MovieState {
+    Idle,
+    Recording(Movie),
+    Playing {
+        movie: Movie,
+        next_frame: usize,
+        //posição da próxima chamada esperada dentro do frame atual
+        cursor: usize,
+    },
+}
+
+/// Grava ou reproduz os valores retornados pela input_state_callback.
+///
+/// Um frame começa a cada retro_run. Os frames escondidos do run-ahead nao sao gravados e,
+/// durante a reprodução, recebem os mesmos inputs do frame atual.
+///
+/// Se o filme tiver [StateHashConfig], os hashes do estado do jogo sao guardados durante a gravação
+/// e comparados durante a reprodução para encontrar o primeiro frame dessincronizado.
+#[derive(Debug)]
+pub struct MovieManager {
+    state: Mutex<MovieState>,
+    hidden_frame: AtomicBool,
+    desync: Mutex<Option<MovieDesync>>,
+    checked_hashes: AtomicUsize,
+}
+
+impl Default for MovieManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MovieManager {
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(MovieState::Idle),
+            hidden_frame: AtomicBool::new(false),
+            desync: Mutex::new(None),
+            checked_hashes: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn get_mode(&self) -> Result<MovieMode, ErroHandle> {
+        Ok(match &*self.state.lock()? {
+            MovieState::Idle => MovieMode::Idle,
+            MovieState::Recording(_) => MovieMode::Recording,
+            MovieState::Playing { .. } => MovieMode::Playing,
+        })
+    }
+
+    pub fn start_recording(&self, movie: Movie) -> Result<(), ErroHandle> {
+        *self.state.lock()? = MovieState::Recording(movie);
+        Ok(())
+    }
+
+    pub fn start_playback(&self, movie: Movie) -> Result<(), ErroHandle> {
+        *self.desync.lock()? = None;
+        self.checked_hashes.store(0, Ordering::SeqCst);
+
+        *self.state.lock()? = MovieState::Playing {
+            movie,
+            next_frame: 0,
+            cursor: 0,
+        };
+        Ok(())
+    }
+
+    #[doc = "encerra a gravação ou a reprodução. retorna o filme se ele estava sendo gravado"]
+    pub fn stop(&self) -> Result<Option<Movie>, ErroHandle> {
+        let state = std::mem::replace(&mut *self.state.lock()?, MovieState::Idle);
+
+        match state {
+            MovieState::Recording(movie) => Ok(Some(movie)),
+            _ => Ok(None),
+        }
+    }
+
+    #[doc = "deve ser chamada antes de cada retro_run"]
+    pub fn begin_frame(&self, hidden: bool) -> Result<(), ErroHandle> {
+        self.hidden_frame.store(hidden, Ordering::SeqCst);
+
+        if hidden {
+            return Ok(());
+        }
+
+        let mut state = self.state.lock()?;
+
+        match &mut *state {
+            MovieState::Idle => {}
+            MovieState::Recording(movie) => movie.frames.push(Vec::new()),
+            MovieState::Playing {
+                movie,
+                next_frame,
+                cursor,
+            } => {
+                if *next_frame >= movie.frames.len() {
+                    //depois do ultimo frame o jogador volta a controlar o jogo
+                    *state = MovieState::Idle;
+                } else {
+                    *next_frame += 1;
+                    *cursor = 0;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    #[doc = "retorna a origem do hash se o estado do jogo deve ser comparado ou guardado depois do frame atual"]
+    pub fn get_hash_request(&self) -> Result<Option<StateHashSource>, ErroHandle> {
+        if self.hidden_frame.load(Ordering::SeqCst) {
+            return Ok(None);
+        }
+
+        Ok(match &*self.state.lock()? {
+            MovieState::Idle => None,
+            MovieState::Recording(movie) => movie.hash_config.as_ref().and_then(|config| {
+                let frames = movie.frames.len() as u32;
+                let interval = config.interval.max(1);
+
+                (frames > 0 && frames % interval == 0).then(|| config.source.clone())
+            }),
+            MovieState::Playing {
+                movie, next_frame, ..
+            } => {
+                let frame = next_frame.checked_sub(1).map(|frame| frame as u32);
+
+                match (&movie.hash_config, frame) {
+                    (Some(config), Some(frame)) => {
+                        find_hash(&movie.hashes, frame).map(|_| config.source.clone())
+                    }
+                    _ => None,
+                }
+            }
+        })
+    }
+
+    #[doc = "guarda ou compara o hash pedido por [MovieManager::get_hash_request]. Retorna a primeira
+    diferença encontrada na reprodução, apenas no frame em que ela acontece"]
+    pub fn push_hash(&self, hash: u64) -> Result<Option<MovieDesync>, ErroHandle> {
+        match &mut *self.state.lock()? {
+            MovieState::Idle => Ok(None),
+            MovieState::Recording(movie) => {
+                if let Some(frame) = movie.frames.len().checked_sub(1) {
+                    movie.hashes.push(StateHash {
+                        frame: frame as u32,
+                        hash,
+                    });
+                }
+
+                Ok(None)
+            }
+            MovieState::Playing {
+                movie, next_frame, ..
+            } => {
+                let frame = match next_frame.checked_sub(1) {
+                    Some(frame) => frame as u32,
+                    None => return Ok(None),
+                };
+
+                let expected = match find_hash(&movie.hashes, frame) {
+                    Some(expected) => expected,
+                    None => return Ok(None),
+                };
+
+                let mut desync = self.desync.lock()?;
+
+                if desync.is_some() {
+                    return Ok(None);
+                }
+
+                self.checked_hashes.fetch_add(1, Ordering::SeqCst);
+
+                if expected == hash {
+                    return Ok(None);
+                }
+
+                desync.replace(MovieDesync {
+                    frame,
+                    expected,
+                    found: hash,
+                });
+
+                Ok(*desync)
+            }
+        }
+    }
+
+    #[doc = "primeira diferença encontrada desde o inicio da ultima reprodução"]
+    pub fn get_desync(&self) -> Result<Option<MovieDesync>, ErroHandle> {
+        Ok(*self.desync.lock()?)
+    }
+
+    #[doc = "hashes comparados desde o inicio da ultima reprodução"]
+    pub fn get_checked_hashes(&self) -> usize {
+        self.checked_hashes.load(Ordering::SeqCst)
+    }
+
+    #[doc = "durante a reprodução retorna o valor gravado, senão chama *live* e grava o valor se necessário"]
+    pub fn input_state(
+        &self,
+        port: i16,
+        device: i16,
+        index: i16,
+        id: i16,
+        live: impl FnOnce() -> Result<i16, ErroHandle>,
+    ) -> Result<i16, ErroHandle> {
+        let hidden = self.hidden_frame.load(Ordering::SeqCst);
+
+        if let MovieState::Playing {
+            movie,
+            next_frame,
+            cursor,
+        } = &mut *self.state.lock()?
+        {
+            let frame = match next_frame.checked_sub(1).and_then(|i| movie.frames.get(i)) {
+                Some(frame) => frame,
+                None => return Ok(0),
+            };
+
+            //o core costuma ler os inputs sempre na mesma ordem, mas se a ordem mudar o valor
+            //e procurado no frame inteiro. inputs que nao foram gravados valem 0
+            let start = if hidden { 0 } else { *cursor };
+            let found = frame[start..]
+                .iter()
+                .position(|sample| sample.is_same_input(port, device, index, id))
+                .map(|i| start + i)
+                .or_else(|| {
+                    frame[..start]
+                        .iter()
+                        .position(|sample| sample.is_same_input(port, device, index, id))
+                });
+
+            return match found {
+                Some(i) => {
+                    if !hidden {
+                        *cursor = i + 1;
+                    }
+                    Ok(frame[i].value)
+                }
+                None => Ok(0),
+            };
+        }
+
+        //a callback do controle e chamada sem segurar o mutex
+        let value = live()?;
+
+        if !hidden {
+            if let MovieState::Recording(movie) = &mut *self.state.lock()? {
+                if let Some(frame) = movie.frames.last_mut() {
+                    frame.push(InputSample {
+                        port,
+                        device,
+                        index,
+                        id,
+                        value,
+                    });
+                }
+            }
+        }
+
+        Ok(value)
+    }
+}
+
+fn find_hash(hashes: &[StateHash], frame: u32) -> Option<u64> {
+    hashes
+        .binary_search_by_key(&frame, |state_hash| state_hash.frame)
+        .ok()
+        .map(|i| hashes[i].hash)
+}
+
+#[cfg(test)]
+mod test_movie {
+    use super::{
+        InputSample, Movie, MovieDesync, MovieManager, MovieMode, MovieStart, StateHash,
+        StateHashConfig, StateHashSource, MOVIE_VERSION,
+    };
+    use crate::managers::memory_manager::MemoryRegion;
+
+    fn create_movie() -> Movie {
+        Movie::new(
+            "Test Core".to_string(),
+            "1.0".to_string(),
+            "test_rom".to_string(),
+            MovieStart::SaveState(vec![1, 2, 3, 4]),
+        )
+    }
+
+    #[test]
+    fn movie_file_round_trip() {
+        let mut movie = create_movie();
+        movie.frames.push(Vec::new());
+        movie.frames.push(vec![InputSample {
+            port: 1,
+            device: 1,
+            index: 0,
+            id: 8,
+            value: -1,
+        }]);
+        movie.hash_config = Some(StateHashConfig {
+            interval: 2,
+            source: StateHashSource::Memory(vec![MemoryRegion::SystemRam, MemoryRegion::VideoRam]),
+        });
+        movie.hashes.push(StateHash {
+            frame: 1,
+            hash: u64::MAX,
+        });
+
+        let data = movie.to_bytes();
+        assert_eq!(Movie::from_bytes(&data).unwrap(), movie);
+
+        movie.start = MovieStart::PowerOn {
+            save_ram: vec![0xff; 8],
+        };
+        assert_eq!(Movie::from_bytes(&movie.to_bytes()).unwrap(), movie);
+
+        //a versão fica logo depois do magic
+        let mut other_version = data.clone();
+        other_version[4..6].copy_from_slice(&(MOVIE_VERSION + 1).to_le_bytes());
+        assert!(Movie::from_bytes(&other_version).is_err());
+
+        assert!(Movie::from_bytes(&data[..data.len() - 1]).is_err());
+        assert!(Movie::from_bytes(b"XMOV").is_err());
+    }
+
+    #[test]
+    fn record_and_play() {
+        let manager = MovieManager::new();
+        manager.start_recording(create_movie()).unwrap();
+
+        for value in [1, 0, 1] {
+            manager.begin_frame(false).unwrap();
+            manager.input_state(0, 1, 0, 0, || Ok(value)).unwrap();
+            manager.input_state(0, 1, 0, 1, || Ok(value * 2)).unwrap();
+
+            //os frames escondidos do run-ahead nao sao gravados
+            manager.begin_frame(true).unwrap();
+            manager.input_state(0, 1, 0, 0, || Ok(5)).unwrap();
+        }
+
+        let movie = manager.stop().unwrap().unwrap();
+        assert_eq!(movie.frames.len(), 3);
+        assert_eq!(manager.get_mode().unwrap(), MovieMode::Idle);
+
+        manager.start_playback(movie).unwrap();
+
+        for value in [1, 0, 1] {
+            manager.begin_frame(false).unwrap();
+            //a ordem das chamadas pode mudar
+            assert_eq!(
+                manager.input_state(0, 1, 0, 1, || Ok(9)).unwrap(),
+                value * 2
+            );
+            assert_eq!(manager.input_state(0, 1, 0, 0, || Ok(9)).unwrap(), value);
+            assert_eq!(manager.input_state(0, 1, 0, 2, || Ok(9)).unwrap(), 0);
+        }
+
+        //depois do ultimo frame os inputs voltam a ser do jogador
+        manager.begin_frame(false).unwrap();
+        assert_eq!(manager.get_mode().unwrap(), MovieMode::Idle);
+        assert_eq!(manager.input_state(0, 1, 0, 0, || Ok(9)).unwrap(), 9);
+    }
+
+    #[test]
+    fn find_desync() {
+        let manager = MovieManager::new();
+        let mut movie = create_movie();
+        movie.hash_config = Some(StateHashConfig {
+            interval: 2,
+            source: StateHashSource::SaveState,
+        });
+        manager.start_recording(movie).unwrap();
+
+        for frame in 0..6 {
+            manager.begin_frame(false).unwrap();
+
+            if manager.get_hash_request().unwrap().is_some() {
+                manager.push_hash(frame).unwrap();
+            }
+        }
+
+        let movie = manager.stop().unwrap().unwrap();
+        let frames: Vec<u32> = movie.hashes.iter().map(|h| h.frame).collect();
+        assert_eq!(frames, vec![1, 3, 5]);
+
+        manager.start_playback(movie).unwrap();
+
+        for frame in 0..6 {
+            manager.begin_frame(false).unwrap();
+
+            if manager.get_hash_request().unwrap().is_some() {
+                //a partir do frame 3 o jogo fica diferente da gravação
+                let hash = if frame < 3 { frame } else { frame + 10 };
+                manager.push_hash(hash).unwrap();
+            }
+        }
+
+        let desync = MovieDesync {
+            frame: 3,
+            expected: 3,
+            found: 13,
+        };
+        assert_eq!(manager.get_desync().unwrap(), Some(desync));
+        assert_eq!(manager.get_checked_hashes(), 2);
+    }
+}
@@ -1,17 +1,21 @@
 use crate::tools::ffi_tools::get_arc_string_from_ptr;
 use crate::tools::mutex_tools::get_string_mutex_from_ptr;
 use crate::{
+    language::use_local_labels,
     libretro_sys::binding_libretro::{
-        retro_core_option_v2_category, retro_core_option_v2_definition, retro_core_options_v2,
-        retro_core_options_v2_intl,
+        retro_core_option_definition, retro_core_option_v2_category,
+        retro_core_option_v2_definition, retro_core_option_value, retro_core_options_intl,
+        retro_core_options_update_display_callback_t, retro_core_options_v2,
+        retro_core_options_v2_intl, retro_language, retro_variable,
     },
-    tools::mutex_tools::get_string_rwlock_from_ptr,
+    tools::{ffi_tools::get_str_from_ptr, mutex_tools::get_string_rwlock_from_ptr},
 };
-use generics::constants::{CORE_OPTION_EXTENSION_FILE, MAX_CORE_OPTIONS};
+use generics::constants::CORE_OPTION_EXTENSION_FILE;
 use generics::erro_handle::ErroHandle;
 use std::sync::atomic::{AtomicBool, AtomicU16, Ordering};
 use std::sync::Arc;
 use std::{
+    fmt::Debug,
     fs::File,
     io::{Read, Write},
     path::PathBuf,
@@ -46,16 +50,26 @@ pub struct Categories {
     pub desc: Arc<String>,
 }
 
+#[doc = "avisa quando o core esconde ou mostra uma opção, permitindo atualizar a tela de configurações"]
+pub trait OptionVisibilityListener: Debug + Send + Sync {
+    fn visibility_changed(&self, key: &str, visible: bool);
+}
+
 #[derive(Default, Debug)]
 pub struct OptionManager {
     pub file_path: RwLock<PathBuf>,
     pub categories: RwLock<Vec<Categories>>,
     pub updated_count: AtomicU16,
     pub opts: Mutex<Vec<CoreOpt>>,
+    display_callback: RwLock<retro_core_options_update_display_callback_t>,
+    need_display_update: AtomicBool,
+    visibility_listener: RwLock<Option<Box<dyn OptionVisibilityListener>>>,
+    #[doc = "quando false, as traduções enviadas pelo core sao ignoradas e os textos em inglês usados"]
+    use_local_labels: bool,
 }
 
 impl OptionManager {
-    pub fn new(opt_path: &str, library_name: String) -> OptionManager {
+    pub fn new(opt_path: &str, library_name: String, language: retro_language) -> OptionManager {
         let mut file_path = PathBuf::from(opt_path).join(library_name);
         file_path.set_extension(CORE_OPTION_EXTENSION_FILE);
 
@@ -64,6 +78,10 @@ impl OptionManager {
             categories: RwLock::new(Vec::new()),
             file_path: RwLock::new(file_path),
             opts: Mutex::new(Vec::new()),
+            display_callback: RwLock::new(None),
+            need_display_update: AtomicBool::new(false),
+            visibility_listener: RwLock::new(None),
+            use_local_labels: use_local_labels(language),
         }
     }
 
@@ -71,6 +89,41 @@ impl OptionManager {
         self.change_value_selected(opt_key, new_value_selected)?;
         self.write_all_options_in_file()?;
 
+        //o core precisa ser chamado na thread do jogo, então aqui apenas marcamos que ele deve
+        //verificar a visibilidade das opções antes do próximo frame
+        if self.display_callback.read()?.is_some() {
+            self.need_display_update.store(true, Ordering::SeqCst);
+        }
+
+        Ok(())
+    }
+
+    pub fn set_display_callback(
+        &self,
+        callback: retro_core_options_update_display_callback_t,
+    ) -> Result<(), ErroHandle> {
+        *self.display_callback.write()? = callback;
+
+        Ok(())
+    }
+
+    #[doc = "retorna a callback do core se alguma opção foi alterada desde a ultima chamada"]
+    pub fn take_display_update(
+        &self,
+    ) -> Result<retro_core_options_update_display_callback_t, ErroHandle> {
+        if !self.need_display_update.swap(false, Ordering::SeqCst) {
+            return Ok(None);
+        }
+
+        Ok(*self.display_callback.read()?)
+    }
+
+    pub fn set_visibility_listener(
+        &self,
+        listener: Box<dyn OptionVisibilityListener>,
+    ) -> Result<(), ErroHandle> {
+        self.visibility_listener.write()?.replace(listener);
+
         Ok(())
     }
 
@@ -99,12 +152,16 @@ impl OptionManager {
     }
 
     pub fn change_visibility(&self, key: &String, visibility: bool) -> Result<(), ErroHandle> {
+        let mut changed = false;
+
         for core_opt in &mut *self.opts.lock()? {
             if !core_opt.key.to_string().eq(key) {
                 continue;
             }
 
-            core_opt.visibility.store(visibility, Ordering::SeqCst);
+            if core_opt.visibility.swap(visibility, Ordering::SeqCst) != visibility {
+                changed = true;
+            }
 
             if !visibility && core_opt.need_update.load(Ordering::SeqCst) {
                 core_opt.need_update.store(false, Ordering::SeqCst);
@@ -115,6 +172,13 @@ impl OptionManager {
             }
         }
 
+        //o listener e chamado depois de liberar *opts* para que ele possa ler as opções
+        if changed {
+            if let Some(listener) = &*self.visibility_listener.read()? {
+                listener.visibility_changed(key, visibility);
+            }
+        }
+
         Ok(())
     }
 
@@ -163,6 +227,36 @@ impl OptionManager {
         Ok(())
     }
 
+    #[doc = "copia os valores selecionados em *other* para as opções com a mesma chave. Usado para que
+    a segunda instancia do run-ahead veja as mesmas opções do core principal"]
+    pub fn sync_selected_from(&self, other: &OptionManager) -> Result<(), ErroHandle> {
+        //os valores sao copiados antes para que os dois mutex nao fiquem travados ao mesmo tempo
+        let mut selected = Vec::new();
+
+        for core_opt in &*other.opts.lock()? {
+            selected.push((core_opt.key.clone(), core_opt.selected.read()?.clone()));
+        }
+
+        for core_opt in &*self.opts.lock()? {
+            let new_value = match selected.iter().find(|(key, _)| *key == core_opt.key) {
+                Some((_, value)) => value,
+                None => continue,
+            };
+
+            if *core_opt.selected.read()? == *new_value {
+                continue;
+            }
+
+            *core_opt.selected.write()? = new_value.clone();
+
+            if !core_opt.need_update.swap(true, Ordering::SeqCst) {
+                self.updated_count.fetch_add(1, Ordering::SeqCst);
+            }
+        }
+
+        Ok(())
+    }
+
     fn load_all_option_in_file(&self) -> Result<(), ErroHandle> {
         let file_path = self.file_path.read()?.clone();
 
@@ -208,78 +302,96 @@ impl OptionManager {
         }
     }
 
+    #[doc = "o core pode enviar as opções mais de uma vez, então as antigas precisam ser removidas"]
+    fn clear_opts(&self) -> Result<(), ErroHandle> {
+        self.opts.lock()?.clear();
+        self.categories.write()?.clear();
+        self.updated_count.store(0, Ordering::SeqCst);
+
+        Ok(())
+    }
+
     //===============================================
     //=================v2_intl=======================
     //===============================================
 
+    #[doc = "a lista enviada pelo core termina com um item que tem *key* nulo. a libretro permite que
+    *categories* seja nulo quando o core nao usa categorias"]
     fn get_v2_intl_category(
         &self,
-        categories: *mut retro_core_option_v2_category,
+        categories: *const retro_core_option_v2_category,
     ) -> Result<(), ErroHandle> {
-        let categories =
-            unsafe { *(categories as *mut [retro_core_option_v2_category; MAX_CORE_OPTIONS]) };
-
-        for category in categories {
-            if !category.key.is_null() {
-                let key = get_arc_string_from_ptr(category.key);
-                let info = get_arc_string_from_ptr(category.info);
-                let desc = get_arc_string_from_ptr(category.desc);
-
-                self.categories
-                    .write()?
-                    .push(Categories { key, desc, info });
-            } else {
+        if categories.is_null() {
+            return Ok(());
+        }
+
+        let mut index = 0;
+
+        loop {
+            let category = unsafe { &*categories.add(index) };
+
+            if category.key.is_null() {
                 break;
             }
+
+            let key = get_arc_string_from_ptr(category.key);
+            let info = get_arc_string_from_ptr(category.info);
+            let desc = get_arc_string_from_ptr(category.desc);
+
+            self.categories
+                .write()?
+                .push(Categories { key, desc, info });
+
+            index += 1;
         }
 
         Ok(())
     }
 
+    #[doc = "a lista enviada pelo core termina com um item que tem *key* nulo"]
     fn get_v2_intl_definitions(
         &self,
-        definitions: *mut retro_core_option_v2_definition,
+        definitions: *const retro_core_option_v2_definition,
     ) -> Result<(), ErroHandle> {
-        let definitions = unsafe { *(definitions as *mut [retro_core_option_v2_definition; 90]) };
-
-        for definition in definitions {
-            if !definition.key.is_null() {
-                let key = get_arc_string_from_ptr(definition.key);
-                let selected = get_string_rwlock_from_ptr(definition.default_value);
-                let default_value = get_arc_string_from_ptr(definition.default_value);
-                let info = get_arc_string_from_ptr(definition.info);
-                let desc = get_arc_string_from_ptr(definition.desc);
-                let desc_categorized = get_arc_string_from_ptr(definition.desc_categorized);
-                let category_key = get_arc_string_from_ptr(definition.category_key);
-                let info_categorized = get_arc_string_from_ptr(definition.info_categorized);
-                let values = Mutex::new(Vec::new());
-                let need_update = AtomicBool::new(false);
-
-                for retro_value in definition.values {
-                    if !retro_value.label.is_null() {
-                        let value = get_string_mutex_from_ptr(retro_value.value);
-                        let label = get_arc_string_from_ptr(retro_value.label);
-
-                        values.lock()?.push(CoreValue { label, value });
-                    }
-                }
+        if definitions.is_null() {
+            return Ok(());
+        }
 
-                self.opts.lock()?.push(CoreOpt {
-                    key,
-                    selected,
-                    visibility: AtomicBool::new(true),
-                    default_value,
-                    info,
-                    desc,
-                    category_key,
-                    desc_categorized,
-                    info_categorized,
-                    values,
-                    need_update,
-                })
-            } else {
+        let mut index = 0;
+
+        loop {
+            let definition = unsafe { &*definitions.add(index) };
+
+            if definition.key.is_null() {
                 break;
             }
+
+            let key = get_arc_string_from_ptr(definition.key);
+            let selected = get_string_rwlock_from_ptr(definition.default_value);
+            let default_value = get_arc_string_from_ptr(definition.default_value);
+            let info = get_arc_string_from_ptr(definition.info);
+            let desc = get_arc_string_from_ptr(definition.desc);
+            let desc_categorized = get_arc_string_from_ptr(definition.desc_categorized);
+            let category_key = get_arc_string_from_ptr(definition.category_key);
+            let info_categorized = get_arc_string_from_ptr(definition.info_categorized);
+            let values = Mutex::new(get_values(&definition.values));
+            let need_update = AtomicBool::new(false);
+
+            self.opts.lock()?.push(CoreOpt {
+                key,
+                selected,
+                visibility: AtomicBool::new(true),
+                default_value,
+                info,
+                desc,
+                category_key,
+                desc_categorized,
+                info_categorized,
+                values,
+                need_update,
+            });
+
+            index += 1;
         }
 
         Ok(())
@@ -289,19 +401,551 @@ impl OptionManager {
         &self,
         option_intl_v2: retro_core_options_v2_intl,
     ) -> Result<(), ErroHandle> {
+        self.clear_opts()?;
+
+        if option_intl_v2.us.is_null() {
+            return Err(ErroHandle::new(
+                "O núcleo enviou as opções sem a versão em inglês",
+            ));
+        }
+
         unsafe {
-            if option_intl_v2.local.is_null() {
-                let us: retro_core_options_v2 = *(option_intl_v2.us);
-                self.get_v2_intl_definitions(us.definitions)?;
-                self.get_v2_intl_category(us.categories)?;
-            } else {
-                let local: retro_core_options_v2 = *(option_intl_v2.local);
-                self.get_v2_intl_definitions(local.definitions)?;
-                self.get_v2_intl_category(local.categories)?;
+            let us: retro_core_options_v2 = *(option_intl_v2.us);
+            self.get_v2_intl_definitions(us.definitions)?;
+            self.get_v2_intl_category(us.categories)?;
+
+            if !self.use_local_labels || option_intl_v2.local.is_null() {
+                return Ok(());
             }
+
+            //a tradução pode estar incompleta, então ela so substitui os textos que existirem nela
+            let local: retro_core_options_v2 = *(option_intl_v2.local);
+            let local_manager = OptionManager::default();
+            local_manager.get_v2_intl_definitions(local.definitions)?;
+            local_manager.get_v2_intl_category(local.categories)?;
+
+            self.apply_local_labels(local_manager)
         }
+    }
+
+    //===============================================
+    //=====================v2========================
+    //===============================================
+
+    pub fn convert_option_v2(&self, option_v2: retro_core_options_v2) -> Result<(), ErroHandle> {
+        self.clear_opts()?;
+        self.get_v2_intl_definitions(option_v2.definitions)?;
+        self.get_v2_intl_category(option_v2.categories)?;
 
         Ok(())
     }
+
+    //===============================================
+    //=====================v1========================
     //===============================================
+
+    #[doc = "a lista enviada pelo core termina com um item que tem *key* nulo"]
+    pub fn convert_option_v1(
+        &self,
+        definitions: *const retro_core_option_definition,
+    ) -> Result<(), ErroHandle> {
+        self.clear_opts()?;
+
+        if definitions.is_null() {
+            return Ok(());
+        }
+
+        let mut index = 0;
+
+        loop {
+            let definition = unsafe { &*definitions.add(index) };
+
+            if definition.key.is_null() {
+                break;
+            }
+
+            self.push_legacy_opt(
+                get_str_from_ptr(definition.key),
+                get_str_from_ptr(definition.desc),
+                get_str_from_ptr(definition.info),
+                get_values(&definition.values),
+                get_str_from_ptr(definition.default_value),
+            )?;
+
+            index += 1;
+        }
+
+        Ok(())
+    }
+
+    pub fn convert_option_v1_intl(
+        &self,
+        option_intl: retro_core_options_intl,
+    ) -> Result<(), ErroHandle> {
+        self.convert_option_v1(option_intl.us)?;
+
+        if !self.use_local_labels || option_intl.local.is_null() {
+            return Ok(());
+        }
+
+        let local_manager = OptionManager::default();
+        local_manager.convert_option_v1(option_intl.local)?;
+
+        self.apply_local_labels(local_manager)
+    }
+
+    #[doc = "troca os textos em inglês pelos textos traduzidos de *local*. os valores e as chaves nunca mudam"]
+    fn apply_local_labels(&self, local: OptionManager) -> Result<(), ErroHandle> {
+        let local_opts = local.opts.into_inner().unwrap_or_else(|e| e.into_inner());
+        let local_categories = local
+            .categories
+            .into_inner()
+            .unwrap_or_else(|e| e.into_inner());
+
+        for opt in &mut *self.opts.lock()? {
+            let local_opt = match local_opts.iter().find(|local_opt| local_opt.key == opt.key) {
+                Some(local_opt) => local_opt,
+                None => continue,
+            };
+
+            replace_label(&mut opt.desc, &local_opt.desc);
+            replace_label(&mut opt.info, &local_opt.info);
+            replace_label(&mut opt.desc_categorized, &local_opt.desc_categorized);
+            replace_label(&mut opt.info_categorized, &local_opt.info_categorized);
+
+            let local_values = local_opt.values.lock()?;
+
+            for value in &mut *opt.values.lock()? {
+                let raw_value = value.value.lock()?.clone();
+
+                //sem um label o próprio valor e usado, então ele nao e uma tradução
+                for local_value in &*local_values {
+                    if *local_value.value.lock()? == raw_value && *local_value.label != raw_value {
+                        replace_label(&mut value.label, &local_value.label);
+                    }
+                }
+            }
+        }
+
+        for category in &mut *self.categories.write()? {
+            if let Some(local_category) = local_categories.iter().find(|c| c.key == category.key) {
+                replace_label(&mut category.desc, &local_category.desc);
+                replace_label(&mut category.info, &local_category.info);
+            }
+        }
+
+        Ok(())
+    }
+
+    //===============================================
+    //=====================v0========================
+    //===============================================
+
+    #[doc = "a lista enviada pelo core termina com um item que tem *key* nulo"]
+    pub fn convert_variables(&self, variables: *const retro_variable) -> Result<(), ErroHandle> {
+        self.clear_opts()?;
+
+        if variables.is_null() {
+            return Ok(());
+        }
+
+        let mut index = 0;
+
+        loop {
+            let variable = unsafe { &*variables.add(index) };
+
+            if variable.key.is_null() {
+                break;
+            }
+
+            if let Some((desc, values)) = parse_variable_value(&get_str_from_ptr(variable.value)) {
+                let values = values
+                    .into_iter()
+                    .map(|value| CoreValue {
+                        label: Arc::new(value.clone()),
+                        value: Mutex::new(value),
+                    })
+                    .collect();
+
+                //no formato v0 o primeiro valor e sempre o padrão
+                self.push_legacy_opt(
+                    get_str_from_ptr(variable.key),
+                    desc,
+                    String::new(),
+                    values,
+                    String::new(),
+                )?;
+            }
+
+            index += 1;
+        }
+
+        Ok(())
+    }
+
+    #[doc = "usado pelos formatos v0 e v1, que nao possuem categorias"]
+    fn push_legacy_opt(
+        &self,
+        key: String,
+        desc: String,
+        info: String,
+        values: Vec<CoreValue>,
+        default_value: String,
+    ) -> Result<(), ErroHandle> {
+        let default_value = if default_value.is_empty() {
+            match values.first() {
+                Some(value) => value.value.lock()?.clone(),
+                None => return Ok(()),
+            }
+        } else {
+            default_value
+        };
+
+        self.opts.lock()?.push(CoreOpt {
+            key: Arc::new(key),
+            visibility: AtomicBool::new(true),
+            selected: RwLock::new(default_value.clone()),
+            desc: Arc::new(desc),
+            info: Arc::new(info),
+            values: Mutex::new(values),
+            default_value: Arc::new(default_value),
+            ..Default::default()
+        });
+
+        Ok(())
+    }
+    //===============================================
+}
+
+fn replace_label(label: &mut Arc<String>, local_label: &Arc<String>) {
+    if !local_label.is_empty() {
+        *label = local_label.clone();
+    }
+}
+
+#[doc = "quando o core nao informa um *label* o próprio valor e usado no lugar"]
+fn get_values(raw_values: &[retro_core_option_value]) -> Vec<CoreValue> {
+    let mut values = Vec::new();
+
+    for retro_value in raw_values {
+        if retro_value.value.is_null() {
+            break;
+        }
+
+        let label = if retro_value.label.is_null() {
+            get_arc_string_from_ptr(retro_value.value)
+        } else {
+            get_arc_string_from_ptr(retro_value.label)
+        };
+
+        values.push(CoreValue {
+            label,
+            value: get_string_mutex_from_ptr(retro_value.value),
+        });
+    }
+
+    values
+}
+
+#[doc = "separa a descrição e os valores de uma variável v0, que tem o formato \"Descrição; a|b|c\""]
+pub fn parse_variable_value(raw_value: &str) -> Option<(String, Vec<String>)> {
+    let (desc, values) = raw_value.split_once(';')?;
+
+    let values: Vec<String> = values
+        .trim_start()
+        .split('|')
+        .filter(|value| !value.is_empty())
+        .map(|value| value.to_string())
+        .collect();
+
+    if values.is_empty() {
+        return None;
+    }
+
+    Some((desc.trim().to_string(), values))
+}
+
+#[cfg(test)]
+mod test_option_manager {
+    use super::{
+        parse_variable_value, CoreOpt, CoreValue, OptionManager, OptionVisibilityListener,
+    };
+    use crate::test_tools::paths::get_paths;
+    use libretro_sys::binding_libretro::{
+        retro_core_option_definition, retro_core_option_v2_category,
+        retro_core_option_v2_definition, retro_core_option_value, retro_core_options_intl,
+        retro_core_options_v2, retro_language,
+    };
+    use std::{
+        ffi::CString,
+        ptr::{null, null_mut},
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc, Mutex, RwLock,
+        },
+    };
+
+    #[derive(Debug, Default)]
+    struct Listener {
+        changes: Arc<Mutex<Vec<(String, bool)>>>,
+    }
+
+    impl OptionVisibilityListener for Listener {
+        fn visibility_changed(&self, key: &str, visible: bool) {
+            self.changes
+                .lock()
+                .unwrap()
+                .push((key.to_string(), visible));
+        }
+    }
+
+    unsafe extern "C" fn update_display() -> bool {
+        true
+    }
+
+    #[test]
+    fn display_update() {
+        let paths = get_paths().unwrap();
+        let manager = OptionManager::new(
+            &paths.opt,
+            "display_update_test".to_string(),
+            retro_language::RETRO_LANGUAGE_ENGLISH,
+        );
+
+        manager.opts.lock().unwrap().push(CoreOpt {
+            key: Arc::new("modo".to_string()),
+            visibility: AtomicBool::new(true),
+            values: Mutex::new(vec![CoreValue {
+                value: Mutex::new("a".to_string()),
+                label: Arc::new("a".to_string()),
+            }]),
+            ..Default::default()
+        });
+
+        //sem uma callback do core nada precisa ser feito
+        manager.update_opt("modo", "a").unwrap();
+        assert!(manager.take_display_update().unwrap().is_none());
+
+        manager.set_display_callback(Some(update_display)).unwrap();
+        manager.update_opt("modo", "a").unwrap();
+        assert!(manager.take_display_update().unwrap().is_some());
+        assert!(manager.take_display_update().unwrap().is_none());
+
+        let changes = Arc::new(Mutex::new(Vec::new()));
+        manager
+            .set_visibility_listener(Box::new(Listener {
+                changes: changes.clone(),
+            }))
+            .unwrap();
+
+        manager
+            .change_visibility(&"modo".to_string(), false)
+            .unwrap();
+        manager
+            .change_visibility(&"modo".to_string(), false)
+            .unwrap();
+
+        assert_eq!(*changes.lock().unwrap(), vec![("modo".to_string(), false)]);
+        assert!(!manager.opts.lock().unwrap()[0]
+            .visibility
+            .load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn sync_selected() {
+        let paths = get_paths().unwrap();
+        let language = retro_language::RETRO_LANGUAGE_ENGLISH;
+        let main = OptionManager::new(&paths.opt, "sync_main_test".to_string(), language);
+        let secondary = OptionManager::new(&paths.opt, "sync_secondary_test".to_string(), language);
+
+        for manager in [&main, &secondary] {
+            manager.opts.lock().unwrap().push(CoreOpt {
+                key: Arc::new("modo".to_string()),
+                selected: RwLock::new("a".to_string()),
+                values: Mutex::new(vec![
+                    CoreValue {
+                        value: Mutex::new("a".to_string()),
+                        label: Arc::new("a".to_string()),
+                    },
+                    CoreValue {
+                        value: Mutex::new("b".to_string()),
+                        label: Arc::new("b".to_string()),
+                    },
+                ]),
+                ..Default::default()
+            });
+        }
+
+        //nada mudou, então o core nao precisa ser avisado
+        secondary.sync_selected_from(&main).unwrap();
+        assert_eq!(secondary.get_opt_value("modo").unwrap(), None);
+
+        main.update_opt("modo", "b").unwrap();
+        secondary.sync_selected_from(&main).unwrap();
+
+        assert_eq!(
+            secondary.get_opt_value("modo").unwrap(),
+            Some("b".to_string())
+        );
+    }
+
+    #[test]
+    fn local_labels() {
+        let strings: Vec<CString> = [
+            "modo",
+            "Mode",
+            "a",
+            "Auto",
+            "velocidade",
+            "Speed",
+            "Modo",
+            "Automático",
+        ]
+        .iter()
+        .map(|s| CString::new(*s).unwrap())
+        .collect();
+
+        let definition = |key: usize, desc: usize, label: usize| {
+            let mut values = [retro_core_option_value {
+                value: null(),
+                label: null(),
+            }; 128];
+            values[0] = retro_core_option_value {
+                value: strings[2].as_ptr(),
+                label: strings[label].as_ptr(),
+            };
+
+            retro_core_option_definition {
+                key: strings[key].as_ptr(),
+                desc: strings[desc].as_ptr(),
+                info: null(),
+                values,
+                default_value: null(),
+            }
+        };
+        let end = retro_core_option_definition {
+            key: null(),
+            ..definition(0, 1, 3)
+        };
+
+        let us = [definition(0, 1, 3), definition(4, 5, 3), end];
+        //a tradução nao possui a opção "velocidade"
+        let local = [definition(0, 6, 7), end];
+        let intl = retro_core_options_intl {
+            us: us.as_ptr() as *mut _,
+            local: local.as_ptr() as *mut _,
+        };
+
+        let paths = get_paths().unwrap();
+        let english = OptionManager::new(
+            &paths.opt,
+            "local_labels_test".to_string(),
+            retro_language::RETRO_LANGUAGE_ENGLISH,
+        );
+        english.convert_option_v1_intl(intl).unwrap();
+        assert_eq!(*english.opts.lock().unwrap()[0].desc, "Mode");
+
+        let portuguese = OptionManager::new(
+            &paths.opt,
+            "local_labels_test".to_string(),
+            retro_language::RETRO_LANGUAGE_PORTUGUESE_BRAZIL,
+        );
+        portuguese.convert_option_v1_intl(intl).unwrap();
+
+        let opts = portuguese.opts.lock().unwrap();
+        assert_eq!(*opts[0].desc, "Modo");
+        assert_eq!(*opts[0].values.lock().unwrap()[0].label, "Automático");
+        assert_eq!(
+            *opts[0].values.lock().unwrap()[0].value.lock().unwrap(),
+            "a"
+        );
+        assert_eq!(*opts[1].desc, "Speed");
+    }
+
+    #[test]
+    fn v2_without_categories() {
+        let strings: Vec<CString> = ["modo", "Modo", "a", "video", "Vídeo"]
+            .iter()
+            .map(|s| CString::new(*s).unwrap())
+            .collect();
+
+        let mut values = [retro_core_option_value {
+            value: null(),
+            label: null(),
+        }; 128];
+        values[0] = retro_core_option_value {
+            value: strings[2].as_ptr(),
+            label: null(),
+        };
+
+        let definition = retro_core_option_v2_definition {
+            key: strings[0].as_ptr(),
+            desc: strings[1].as_ptr(),
+            desc_categorized: null(),
+            info: null(),
+            info_categorized: null(),
+            category_key: null(),
+            values,
+            default_value: strings[2].as_ptr(),
+        };
+        let definitions = [
+            definition,
+            retro_core_option_v2_definition {
+                key: null(),
+                ..definition
+            },
+        ];
+
+        let manager = OptionManager::default();
+
+        //a libretro permite que o core envie as categorias como null
+        manager
+            .convert_option_v2(retro_core_options_v2 {
+                categories: null_mut(),
+                definitions: definitions.as_ptr() as *mut _,
+            })
+            .unwrap();
+
+        assert_eq!(manager.opts.lock().unwrap().len(), 1);
+        assert!(manager.categories.read().unwrap().is_empty());
+
+        //a leitura para no item com key nulo, sem passar do fim da lista do core
+        let category = retro_core_option_v2_category {
+            key: strings[3].as_ptr(),
+            desc: strings[4].as_ptr(),
+            info: null(),
+        };
+        let categories = [
+            category,
+            retro_core_option_v2_category {
+                key: null(),
+                ..category
+            },
+        ];
+
+        manager
+            .convert_option_v2(retro_core_options_v2 {
+                categories: categories.as_ptr() as *mut _,
+                definitions: definitions.as_ptr() as *mut _,
+            })
+            .unwrap();
+
+        assert_eq!(manager.opts.lock().unwrap().len(), 1);
+        assert_eq!(manager.categories.read().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn parse_v0_variable() {
+        let (desc, values) = parse_variable_value("Frameskip; disabled|1|2").unwrap();
+
+        assert_eq!(desc, "Frameskip");
+        assert_eq!(values, vec!["disabled", "1", "2"]);
+
+        //alguns cores usam valores com espaços, como "Game Boy Color"
+        let (_, values) = parse_variable_value("Modelo;  Auto|Game Boy Color").unwrap();
+        assert_eq!(values, vec!["Auto", "Game Boy Color"]);
+
+        assert!(parse_variable_value("Sem valores;").is_none());
+        assert!(parse_variable_value("Sem separador").is_none());
+    }
 }
@@ -0,0 +1,166 @@
+use crate::tools::ffi_tools::get_str_from_ptr;
+use generics::erro_handle::ErroHandle;
+use libretro_sys::binding_libretro::{retro_perf_counter, retro_perf_tick_t};
+use std::sync::RwLock;
+
+/// Resultado de um contador de desempenho registrado pelo core.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PerfCounterReport {
+    pub ident: String,
+    pub call_count: u64,
+    pub total_ticks: u64,
+    pub average_ticks: f64,
+}
+
+impl PerfCounterReport {
+    pub fn from_raw(raw: &retro_perf_counter) -> Self {
+        let average_ticks = if raw.call_cnt == 0 {
+            0.0
+        } else {
+            raw.total as f64 / raw.call_cnt as f64
+        };
+
+        Self {
+            ident: get_str_from_ptr(raw.ident),
+            call_count: raw.call_cnt,
+            total_ticks: raw.total,
+            average_ticks,
+        }
+    }
+}
+
+/// Guarda os contadores que o core registrou com a interface RETRO_ENVIRONMENT_GET_PERF_INTERFACE.
+///
+/// Os contadores pertencem ao core, então aqui so ficam os ponteiros. Eles sao atualizados no
+/// próprio lugar por start e stop, ja que o core também pode ler os valores.
+#[derive(Debug, Default)]
+pub struct PerfManager {
+    counters: RwLock<Vec<*mut retro_perf_counter>>,
+}
+
+impl PerfManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[doc = "o core pode registrar o mesmo contador mais de uma vez"]
+    pub fn register(&self, counter: *mut retro_perf_counter) -> Result<(), ErroHandle> {
+        if counter.is_null() {
+            return Ok(());
+        }
+
+        unsafe { (*counter).registered = true }
+
+        let mut counters = self.counters.write()?;
+
+        if !counters.contains(&counter) {
+            counters.push(counter);
+        }
+
+        Ok(())
+    }
+
+    pub fn start(counter: *mut retro_perf_counter, now: retro_perf_tick_t) {
+        if let Some(counter) = unsafe { counter.as_mut() } {
+            if counter.registered {
+                counter.call_cnt += 1;
+                counter.start = now;
+            }
+        }
+    }
+
+    pub fn stop(counter: *mut retro_perf_counter, now: retro_perf_tick_t) {
+        if let Some(counter) = unsafe { counter.as_mut() } {
+            if counter.registered {
+                counter.total += now.saturating_sub(counter.start);
+            }
+        }
+    }
+
+    #[doc = "retorna os contadores ordenados do que consumiu mais tempo para o que consumiu menos"]
+    pub fn get_report(&self) -> Result<Vec<PerfCounterReport>, ErroHandle> {
+        let mut report: Vec<PerfCounterReport> = self
+            .counters
+            .read()?
+            .iter()
+            .map(|counter| PerfCounterReport::from_raw(unsafe { &**counter }))
+            .collect();
+
+        report.sort_by(|a, b| b.total_ticks.cmp(&a.total_ticks));
+
+        Ok(report)
+    }
+
+    #[doc = "os contadores deixam de existir quando o core e descarregado"]
+    pub fn clear(&self) -> Result<(), ErroHandle> {
+        self.counters.write()?.clear();
+
+        Ok(())
+    }
+}
+
+#[doc = "gera uma tabela com um contador por linha, pronta para ser exibida no terminal ou no log"]
+pub fn format_perf_report(report: &[PerfCounterReport]) -> String {
+    let ident_width = report
+        .iter()
+        .map(|counter| counter.ident.len())
+        .max()
+        .unwrap_or(0)
+        .max("contador".len());
+
+    let mut out = format!(
+        "{:<ident_width$} {:>12} {:>16} {:>14}\n",
+        "contador", "chamadas", "total (ticks)", "media (ticks)"
+    );
+
+    for counter in report {
+        out += &format!(
+            "{:<ident_width$} {:>12} {:>16} {:>14.1}\n",
+            counter.ident, counter.call_count, counter.total_ticks, counter.average_ticks
+        );
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod test_perf_manager {
+    use super::{format_perf_report, PerfManager};
+    use libretro_sys::binding_libretro::retro_perf_counter;
+    use std::ffi::CString;
+
+    #[test]
+    fn counters_in_place() {
+        let ident = CString::new("gpu").unwrap();
+        let mut counter = retro_perf_counter {
+            ident: ident.as_ptr(),
+            start: 0,
+            total: 0,
+            call_cnt: 0,
+            registered: false,
+        };
+        let counter_ptr: *mut retro_perf_counter = &mut counter;
+
+        let manager = PerfManager::new();
+        manager.register(counter_ptr).unwrap();
+        manager.register(counter_ptr).unwrap();
+
+        PerfManager::start(counter_ptr, 100);
+        PerfManager::stop(counter_ptr, 130);
+        PerfManager::start(counter_ptr, 200);
+        PerfManager::stop(counter_ptr, 210);
+
+        let report = manager.get_report().unwrap();
+        assert_eq!(report.len(), 1);
+        assert_eq!(report[0].ident, "gpu");
+        assert_eq!(report[0].call_count, 2);
+        assert_eq!(report[0].total_ticks, 40);
+        assert_eq!(report[0].average_ticks, 20.0);
+
+        //o core também enxerga os valores atualizados
+        assert!(counter.registered);
+        assert_eq!(counter.total, 40);
+
+        assert!(format_perf_report(&report).contains("gpu"));
+    }
+}
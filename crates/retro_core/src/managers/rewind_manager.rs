@@ -0,0 +1,272 @@
+use generics::constants::{DEFAULT_REWIND_BUFFER_SIZE, DEFAULT_REWIND_GRANULARITY};
+use generics::erro_handle::ErroHandle;
+use std::collections::VecDeque;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RewindConfig {
+    pub enabled: bool,
+    #[doc = "memoria máxima em bytes usada pelos states guardados"]
+    pub buffer_size: usize,
+    #[doc = "um state e guardado a cada *granularity* frames. valores maiores gastam menos memoria e CPU,
+    mas o jogo volta mais rápido"]
+    pub granularity: u32,
+}
+
+impl Default for RewindConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            buffer_size: DEFAULT_REWIND_BUFFER_SIZE,
+            granularity: DEFAULT_REWIND_GRANULARITY,
+        }
+    }
+}
+
+/// Guarda os states gerados pelo retro_serialize para que o jogo possa voltar no tempo.
+///
+/// So o state mais novo fica inteiro na memoria. Os anteriores sao guardados como a diferença
+/// (XOR) para o state seguinte, compactada com RLE, já que entre dois frames quase toda a memoria
+/// do console continua igual. Quando o limite de memoria e atingido os states mais antigos
+/// sao descartados.
+#[derive(Debug)]
+pub struct RewindManager {
+    config: RewindConfig,
+    current: Vec<u8>,
+    patches: VecDeque<Vec<u8>>,
+    patches_size: usize,
+    frame_count: u32,
+}
+
+impl RewindManager {
+    pub fn new(config: RewindConfig) -> Self {
+        Self {
+            config,
+            current: Vec::new(),
+            patches: VecDeque::new(),
+            patches_size: 0,
+            frame_count: 0,
+        }
+    }
+
+    pub fn get_config(&self) -> &RewindConfig {
+        &self.config
+    }
+
+    pub fn set_config(&mut self, config: RewindConfig) {
+        if !config.enabled {
+            self.clear();
+        }
+
+        self.config = config;
+        self.shrink();
+    }
+
+    #[doc = "deve ser chamada a cada frame. retorna true quando um novo state deve ser guardado"]
+    pub fn should_capture(&mut self) -> bool {
+        if !self.config.enabled {
+            return false;
+        }
+
+        self.frame_count += 1;
+
+        if self.frame_count >= self.config.granularity.max(1) {
+            self.frame_count = 0;
+            return true;
+        }
+
+        false
+    }
+
+    pub fn push(&mut self, state: Vec<u8>) {
+        //o core mudou o tamanho do state, então os anteriores nao podem mais ser usados
+        if self.current.len() != state.len() {
+            self.clear();
+        }
+
+        if !self.current.is_empty() {
+            let patch = encode_patch(&self.current, &state);
+
+            self.patches_size += patch.len();
+            self.patches.push_back(patch);
+        }
+
+        self.current = state;
+        self.shrink();
+    }
+
+    #[doc = "retorna o state anterior ao ultimo que foi guardado. None quando nao ha mais states"]
+    pub fn pop(&mut self) -> Result<Option<&[u8]>, ErroHandle> {
+        let patch = match self.patches.pop_back() {
+            Some(patch) => patch,
+            None => return Ok(None),
+        };
+
+        self.patches_size -= patch.len();
+        apply_patch(&mut self.current, &patch)?;
+
+        //evita que o state seja guardado logo depois do jogo voltar a rodar
+        self.frame_count = 0;
+
+        Ok(Some(&self.current))
+    }
+
+    #[doc = "quantidade de states que podem ser restaurados"]
+    pub fn len(&self) -> usize {
+        self.patches.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.patches.is_empty()
+    }
+
+    #[doc = "memoria em bytes usada pelos states guardados"]
+    pub fn memory_usage(&self) -> usize {
+        self.current.len() + self.patches_size
+    }
+
+    pub fn clear(&mut self) {
+        self.current.clear();
+        self.patches.clear();
+        self.patches_size = 0;
+        self.frame_count = 0;
+    }
+
+    fn shrink(&mut self) {
+        while self.memory_usage() > self.config.buffer_size {
+            match self.patches.pop_front() {
+                Some(patch) => self.patches_size -= patch.len(),
+                None => break,
+            }
+        }
+    }
+}
+
+//cada bloco do patch e formado por: quantidade de bytes iguais (u32), quantidade de bytes
+//diferentes (u32) e o XOR desses bytes diferentes
+fn encode_patch(old: &[u8], new: &[u8]) -> Vec<u8> {
+    let mut patch = Vec::new();
+    let mut index = 0;
+
+    while index < new.len() {
+        let start = index;
+        while index < new.len() && old[index] == new[index] {
+            index += 1;
+        }
+        let equal = index - start;
+
+        //o resto do state e igual, então nao precisa ser guardado
+        if index == new.len() {
+            break;
+        }
+
+        let start = index;
+        while index < new.len() && old[index] != new[index] {
+            index += 1;
+        }
+
+        patch.extend_from_slice(&(equal as u32).to_le_bytes());
+        patch.extend_from_slice(&((index - start) as u32).to_le_bytes());
+        patch.extend(
+            old[start..index]
+                .iter()
+                .zip(&new[start..index])
+                .map(|(old, new)| old ^ new),
+        );
+    }
+
+    patch
+}
+
+fn apply_patch(state: &mut [u8], patch: &[u8]) -> Result<(), ErroHandle> {
+    let invalid = || ErroHandle::new("O patch do rewind esta corrompido");
+    let read_u32 = |at: usize| -> Result<usize, ErroHandle> {
+        let bytes = patch.get(at..at + 4).ok_or_else(invalid)?;
+        Ok(u32::from_le_bytes(bytes.try_into().map_err(|_| invalid())?) as usize)
+    };
+
+    let mut cursor = 0;
+    let mut index = 0;
+
+    while cursor < patch.len() {
+        let equal = read_u32(cursor)?;
+        let diff = read_u32(cursor + 4)?;
+        cursor += 8;
+
+        index += equal;
+
+        let xor = patch.get(cursor..cursor + diff).ok_or_else(invalid)?;
+        let target = state.get_mut(index..index + diff).ok_or_else(invalid)?;
+
+        for (byte, xor) in target.iter_mut().zip(xor) {
+            *byte ^= xor;
+        }
+
+        cursor += diff;
+        index += diff;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test_rewind_manager {
+    use super::{RewindConfig, RewindManager};
+
+    fn state(frame: u8) -> Vec<u8> {
+        let mut state = vec![0u8; 1024];
+        state[10] = frame;
+        state[500] = frame.wrapping_mul(3);
+        state
+    }
+
+    #[test]
+    fn rewind_states() {
+        let mut rewind = RewindManager::new(RewindConfig {
+            enabled: true,
+            buffer_size: 1024 * 1024,
+            granularity: 2,
+        });
+
+        for frame in 0..10 {
+            if rewind.should_capture() {
+                rewind.push(state(frame));
+            }
+        }
+
+        //frames 1, 3, 5, 7 e 9 foram guardados
+        assert_eq!(rewind.len(), 4);
+        assert!(rewind.memory_usage() < 1024 * 2);
+
+        assert_eq!(rewind.pop().unwrap(), Some(state(7).as_slice()));
+        assert_eq!(rewind.pop().unwrap(), Some(state(5).as_slice()));
+        assert_eq!(rewind.pop().unwrap(), Some(state(3).as_slice()));
+        assert_eq!(rewind.pop().unwrap(), Some(state(1).as_slice()));
+        assert_eq!(rewind.pop().unwrap(), None);
+    }
+
+    #[test]
+    fn memory_limit() {
+        let mut rewind = RewindManager::new(RewindConfig {
+            enabled: true,
+            buffer_size: 1024 + 40,
+            granularity: 1,
+        });
+
+        for frame in 0..10 {
+            rewind.push(state(frame));
+        }
+
+        //cada patch ocupa 18 bytes, então so os dois mais novos cabem no limite
+        assert_eq!(rewind.len(), 2);
+        assert!(rewind.memory_usage() <= 1024 + 40);
+        assert_eq!(rewind.pop().unwrap(), Some(state(8).as_slice()));
+
+        //states com outro tamanho descartam os anteriores
+        rewind.push(vec![1; 16]);
+        assert!(rewind.is_empty());
+
+        rewind.set_config(RewindConfig::default());
+        assert!(!rewind.should_capture());
+        assert_eq!(rewind.memory_usage(), 0);
+    }
+}
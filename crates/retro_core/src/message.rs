@@ -0,0 +1,86 @@
+use libretro_sys::binding_libretro::{
+    retro_log_level, retro_message, retro_message_ext, retro_message_target, retro_message_type,
+};
+
+use crate::tools::ffi_tools::get_str_from_ptr;
+
+//usado quando o core envia uma mensagem antes de informar o fps
+const DEFAULT_FPS: f64 = 60.0;
+
+/// Mensagem enviada pelo core com RETRO_ENVIRONMENT_SET_MESSAGE ou
+/// RETRO_ENVIRONMENT_SET_MESSAGE_EXT, como "Disk 2 inserted".
+#[derive(Clone, Debug, PartialEq)]
+pub struct RetroMessage {
+    pub msg: String,
+    #[doc = "tempo em milissegundos que a mensagem deve ficar na tela"]
+    pub duration: u32,
+    #[doc = "quanto maior, mais importante. mensagens com a mesma prioridade podem se substituir"]
+    pub priority: u32,
+    pub level: retro_log_level,
+    pub target: retro_message_target,
+    pub type_: retro_message_type,
+    #[doc = "de 0 a 100 para mensagens do tipo RETRO_MESSAGE_TYPE_PROGRESS. -1 quando indefinido"]
+    pub progress: i8,
+}
+
+impl RetroMessage {
+    #[doc = "a primeira versão da interface informa a duração em frames, entao o *fps* do core e necessário"]
+    pub fn from_raw(raw: &retro_message, fps: f64) -> Self {
+        let fps = if fps > 0.0 { fps } else { DEFAULT_FPS };
+
+        Self {
+            msg: get_str_from_ptr(raw.msg),
+            duration: (raw.frames as f64 / fps * 1000.0) as u32,
+            priority: 1,
+            level: retro_log_level::RETRO_LOG_INFO,
+            target: retro_message_target::RETRO_MESSAGE_TARGET_ALL,
+            type_: retro_message_type::RETRO_MESSAGE_TYPE_NOTIFICATION,
+            progress: -1,
+        }
+    }
+
+    pub fn from_raw_ext(raw: &retro_message_ext) -> Self {
+        Self {
+            msg: get_str_from_ptr(raw.msg),
+            duration: raw.duration,
+            priority: raw.priority,
+            level: raw.level,
+            target: raw.target,
+            type_: raw.type_,
+            progress: raw.progress,
+        }
+    }
+
+    #[doc = "mensagens com o alvo RETRO_MESSAGE_TARGET_LOG nao devem aparecer na tela"]
+    pub fn show_on_screen(&self) -> bool {
+        self.target != retro_message_target::RETRO_MESSAGE_TARGET_LOG
+    }
+}
+
+#[cfg(test)]
+mod test_message {
+    use super::RetroMessage;
+    use libretro_sys::binding_libretro::{retro_message, retro_message_target};
+    use std::ffi::CString;
+
+    #[test]
+    fn frames_to_duration() {
+        let msg = CString::new("Disk 2 inserted").unwrap();
+        let raw = retro_message {
+            msg: msg.as_ptr(),
+            frames: 180,
+        };
+
+        let message = RetroMessage::from_raw(&raw, 60.0);
+        assert_eq!(message.msg, "Disk 2 inserted");
+        assert_eq!(message.duration, 3000);
+        assert_eq!(
+            message.target,
+            retro_message_target::RETRO_MESSAGE_TARGET_ALL
+        );
+        assert!(message.show_on_screen());
+
+        //sem o fps do core, 60 e usado
+        assert_eq!(RetroMessage::from_raw(&raw, 0.0).duration, 3000);
+    }
+}
@@ -0,0 +1,258 @@
+use generics::erro_handle::ErroHandle;
+use libretro_sys::binding_libretro::{
+    retro_device_power, retro_power_state, RETRO_POWERSTATE_NO_ESTIMATE,
+};
+use std::{
+    fmt::Debug,
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+};
+
+#[doc = "pasta onde o kernel do Linux expõe as baterias e as fontes de energia"]
+pub const SYSFS_POWER_SUPPLY_DIR: &str = "/sys/class/power_supply";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DevicePower {
+    pub state: retro_power_state,
+    #[doc = "tempo estimado em segundos ate o aparelho desligar. so existe quando ele esta usando a bateria"]
+    pub seconds: Option<i32>,
+    #[doc = "carga da bateria de 0 a 100"]
+    pub percent: Option<i8>,
+}
+
+impl DevicePower {
+    pub fn to_raw(&self) -> retro_device_power {
+        let seconds = match self.state {
+            retro_power_state::RETRO_POWERSTATE_DISCHARGING => self.seconds,
+            _ => None,
+        };
+
+        retro_device_power {
+            state: self.state,
+            seconds: seconds.unwrap_or(RETRO_POWERSTATE_NO_ESTIMATE),
+            percent: self.percent.unwrap_or(RETRO_POWERSTATE_NO_ESTIMATE as i8),
+        }
+    }
+}
+
+/// Informa ao core como o aparelho esta sendo alimentado, usado em RETRO_ENVIRONMENT_GET_DEVICE_POWER
+/// para simular a bateria dos portáteis.
+pub trait PowerProvider: Debug {
+    #[doc = "None quando nao for possível saber o estado da energia"]
+    fn get_power(&self) -> Result<Option<DevicePower>, ErroHandle>;
+}
+
+/// Sempre informa o mesmo estado. Pode ser clonado para alterar o estado durante os testes.
+#[derive(Clone, Debug, Default)]
+pub struct MockPowerProvider {
+    power: Arc<RwLock<Option<DevicePower>>>,
+}
+
+impl MockPowerProvider {
+    pub fn new(power: Option<DevicePower>) -> Self {
+        Self {
+            power: Arc::new(RwLock::new(power)),
+        }
+    }
+
+    pub fn set_power(&self, power: Option<DevicePower>) -> Result<(), ErroHandle> {
+        *self.power.write()? = power;
+        Ok(())
+    }
+}
+
+impl PowerProvider for MockPowerProvider {
+    fn get_power(&self) -> Result<Option<DevicePower>, ErroHandle> {
+        Ok(*self.power.read()?)
+    }
+}
+
+/// Lê o estado da energia em /sys/class/power_supply. Em outros sistemas essa pasta nao existe
+/// e o core e informado que o estado e desconhecido.
+#[derive(Clone, Debug)]
+pub struct SysfsPowerProvider {
+    root: PathBuf,
+}
+
+impl Default for SysfsPowerProvider {
+    fn default() -> Self {
+        Self::with_root(Path::new(SYSFS_POWER_SUPPLY_DIR))
+    }
+}
+
+impl SysfsPowerProvider {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[doc = "usa outra pasta no lugar de /sys/class/power_supply"]
+    pub fn with_root(root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+        }
+    }
+
+    fn read_value(supply: &Path, name: &str) -> Option<String> {
+        fs::read_to_string(supply.join(name))
+            .ok()
+            .map(|value| value.trim().to_string())
+    }
+
+    fn read_number(supply: &Path, name: &str) -> Option<i64> {
+        Self::read_value(supply, name).and_then(|value| value.parse().ok())
+    }
+
+    //energy_* estão em µWh e power_now em µW. alguns drivers usam charge_* (µAh) e current_now (µA)
+    fn read_seconds(supply: &Path) -> Option<i32> {
+        let (now, rate) = match (
+            Self::read_number(supply, "energy_now"),
+            Self::read_number(supply, "power_now"),
+        ) {
+            (Some(now), Some(rate)) => (now, rate),
+            _ => (
+                Self::read_number(supply, "charge_now")?,
+                Self::read_number(supply, "current_now")?,
+            ),
+        };
+
+        let rate = rate.abs();
+
+        if rate == 0 {
+            return None;
+        }
+
+        i32::try_from(now * 3600 / rate).ok()
+    }
+
+    fn read_battery(supply: &Path) -> DevicePower {
+        let state = match Self::read_value(supply, "status").as_deref() {
+            Some("Discharging") => retro_power_state::RETRO_POWERSTATE_DISCHARGING,
+            Some("Charging") => retro_power_state::RETRO_POWERSTATE_CHARGING,
+            Some("Full") | Some("Not charging") => retro_power_state::RETRO_POWERSTATE_CHARGED,
+            _ => retro_power_state::RETRO_POWERSTATE_UNKNOWN,
+        };
+
+        let seconds = match state {
+            retro_power_state::RETRO_POWERSTATE_DISCHARGING => Self::read_seconds(supply),
+            _ => None,
+        };
+
+        DevicePower {
+            state,
+            seconds,
+            percent: Self::read_number(supply, "capacity")
+                .map(|capacity| capacity.clamp(0, 100) as i8),
+        }
+    }
+}
+
+impl PowerProvider for SysfsPowerProvider {
+    fn get_power(&self) -> Result<Option<DevicePower>, ErroHandle> {
+        let entries = match fs::read_dir(&self.root) {
+            Ok(entries) => entries,
+            Err(_) => return Ok(None),
+        };
+
+        let mut plugged_in = false;
+
+        let mut supplies: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .collect();
+        supplies.sort();
+
+        for supply in supplies {
+            match Self::read_value(&supply, "type").as_deref() {
+                //baterias dos controles e de outros periféricos nao sao do aparelho
+                Some("Battery")
+                    if Self::read_value(&supply, "scope").as_deref() != Some("Device") =>
+                {
+                    let power = Self::read_battery(&supply);
+
+                    if power.state != retro_power_state::RETRO_POWERSTATE_UNKNOWN {
+                        return Ok(Some(power));
+                    }
+                }
+                Some("Mains") | Some("USB") => {
+                    plugged_in |= Self::read_number(&supply, "online") == Some(1);
+                }
+                _ => {}
+            }
+        }
+
+        if plugged_in {
+            return Ok(Some(DevicePower {
+                state: retro_power_state::RETRO_POWERSTATE_PLUGGED_IN,
+                seconds: None,
+                percent: None,
+            }));
+        }
+
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod test_power {
+    use super::{PowerProvider, SysfsPowerProvider};
+    use libretro_sys::binding_libretro::{retro_power_state, RETRO_POWERSTATE_NO_ESTIMATE};
+    use std::{env, fs, path::Path};
+
+    fn create_supply(root: &Path, name: &str, values: &[(&str, &str)]) {
+        let supply = root.join(name);
+        fs::create_dir_all(&supply).unwrap();
+
+        for (file, value) in values {
+            fs::write(supply.join(file), format!("{value}\n")).unwrap();
+        }
+    }
+
+    #[test]
+    fn sysfs_power() {
+        let root = env::temp_dir().join(format!("tinic_power_supply_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+
+        let provider = SysfsPowerProvider::with_root(&root);
+        assert_eq!(provider.get_power().unwrap(), None);
+
+        create_supply(&root, "AC", &[("type", "Mains"), ("online", "0")]);
+        assert_eq!(provider.get_power().unwrap(), None);
+
+        //bateria de um controle nao deve ser usada
+        create_supply(
+            &root,
+            "hid-gamepad-battery",
+            &[
+                ("type", "Battery"),
+                ("scope", "Device"),
+                ("status", "Discharging"),
+            ],
+        );
+        assert_eq!(provider.get_power().unwrap(), None);
+
+        create_supply(
+            &root,
+            "BAT0",
+            &[
+                ("type", "Battery"),
+                ("status", "Discharging"),
+                ("capacity", "42"),
+                ("energy_now", "20000000"),
+                ("power_now", "10000000"),
+            ],
+        );
+
+        let power = provider.get_power().unwrap().unwrap();
+        assert_eq!(power.state, retro_power_state::RETRO_POWERSTATE_DISCHARGING);
+        assert_eq!(power.percent, Some(42));
+        assert_eq!(power.seconds, Some(7200));
+
+        create_supply(&root, "BAT0", &[("status", "Full"), ("capacity", "100")]);
+        let raw = provider.get_power().unwrap().unwrap().to_raw();
+        assert_eq!(raw.state, retro_power_state::RETRO_POWERSTATE_CHARGED);
+        assert_eq!(raw.seconds, RETRO_POWERSTATE_NO_ESTIMATE);
+        assert_eq!(raw.percent, 100);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -1,30 +1,69 @@
+use crate::audio_callback::{AudioBufferStatus, AudioCallback};
 use crate::av_info::AvInfo;
+use crate::camera::Camera;
 use crate::core_env::{self, RetroEnvCallbacks};
+use crate::disk_control::DiskControl;
+use crate::frame_time::FrameTime;
 use crate::graphic_api::GraphicApi;
+use crate::location::LocationLifetime;
+use crate::logger::{RetroLogEntry, RetroLogger};
+use crate::managers::cheat_manager::{Cheat, CheatManager};
+use crate::managers::memory_manager::{MemoryManager, MemoryRegion};
+use crate::managers::movie_manager::{
+    Movie, MovieManager, MovieMode, MovieOrigin, MovieStart, MovieVerification, StateHashConfig,
+    StateHashSource, StateHasher,
+};
+use crate::managers::perf_manager::{format_perf_report, PerfCounterReport, PerfManager};
+use crate::run_ahead::FrameOutput;
+use crate::speed::SpeedState;
+use crate::tools::ffi_tools::make_c_string;
 use crate::tools::game_tools::RomTools;
 use crate::{managers::option_manager::OptionManager, system::System};
 use generics::constants::INVALID_CONTROLLER_PORT;
 use generics::erro_handle::ErroHandle;
 use generics::retro_paths::RetroPaths;
-use libretro_sys::binding_libretro::LibretroRaw;
+use libretro_sys::binding_libretro::{
+    retro_language, retro_log_level, retro_savestate_context, LibretroRaw,
+};
 use std::path::PathBuf;
-use std::rc::Rc;
+use std::ptr::{slice_from_raw_parts, slice_from_raw_parts_mut};
+use std::rc::{Rc, Weak};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, RwLock};
 
 pub type RetroCoreIns = Rc<RetroCore>;
 
+/// Um núcleo libretro carregado e tudo que ele enviou ao frontend.
+///
+/// As interfaces que guardam funções do core, como [crate::disk_control::DiskControl] e
+/// [crate::camera::Camera], devem ser usadas pelos métodos daqui. Eles ativam o contexto dessa
+/// instancia antes de chamar o core, então as callbacks que o core chamar de volta chegam ao
+/// RetroCore certo.
 pub struct RetroCore {
     pub rom_name: RwLock<String>,
     pub initialized: AtomicBool,
     pub game_loaded: AtomicBool,
     pub support_no_game: AtomicBool,
+    #[doc = "idioma informado ao core. para trocar e necessário carregar o core novamente"]
+    pub language: retro_language,
     pub av_info: Arc<AvInfo>,
     pub system: System,
     pub paths: RetroPaths,
     pub options: Arc<OptionManager>,
+    pub memory: MemoryManager,
+    pub cheats: CheatManager,
+    pub perf: PerfManager,
+    pub disk_control: DiskControl,
+    pub frame_time: FrameTime,
+    pub audio_callback: AudioCallback,
+    pub location: LocationLifetime,
+    pub camera: Camera,
+    pub output: FrameOutput,
+    pub speed: SpeedState,
+    pub movie: MovieManager,
     pub callbacks: RetroEnvCallbacks,
     raw: Arc<LibretroRaw>,
+    weak_self: Weak<RetroCore>,
 }
 
 impl RetroCore {
@@ -33,6 +72,7 @@ impl RetroCore {
         paths: RetroPaths,
         callbacks: RetroEnvCallbacks,
         graphic_api: GraphicApi,
+        language: retro_language,
     ) -> Result<RetroCoreIns, ErroHandle> {
         let raw = unsafe { LibretroRaw::new(core_path).unwrap() };
 
@@ -41,22 +81,39 @@ impl RetroCore {
         let options = Arc::new(OptionManager::new(
             &paths.opt,
             system.info.library_name.clone().to_string(),
+            language,
         ));
 
-        let core = Rc::new(RetroCore {
+        let core = Rc::new_cyclic(|weak_self| RetroCore {
             raw: Arc::new(raw),
+            weak_self: weak_self.clone(),
             initialized: AtomicBool::new(false),
             game_loaded: AtomicBool::new(false),
             support_no_game: AtomicBool::new(false),
             av_info: Arc::new(AvInfo::new(graphic_api)),
+            language,
             rom_name: RwLock::new("".to_string()),
             system,
             paths,
             options,
+            memory: MemoryManager::new(),
+            cheats: CheatManager::new(),
+            perf: PerfManager::new(),
+            disk_control: DiskControl::new(),
+            frame_time: FrameTime::new(),
+            audio_callback: AudioCallback::new(),
+            location: LocationLifetime::new(),
+            camera: Camera::new(),
+            output: FrameOutput::new(),
+            speed: SpeedState::new(),
+            movie: MovieManager::new(),
             callbacks,
         });
 
-        core_env::configure(core.clone());
+        //as threads criadas pelo núcleo nunca passam por enter_ctx
+        core_env::register_loaded_core(&core);
+
+        let _ctx = core.enter_ctx();
 
         unsafe {
             core.raw
@@ -83,6 +140,11 @@ impl RetroCore {
         Ok(core)
     }
 
+    #[doc = "faz com que as callbacks chamadas pelo núcleo nessa thread sejam enviadas para essa instancia"]
+    fn enter_ctx(&self) -> core_env::CoreContextGuard {
+        core_env::enter_core_ctx(self.weak_self.upgrade())
+    }
+
     fn init(&self) -> Result<(), ErroHandle> {
         if self.game_loaded.load(Ordering::SeqCst) || self.initialized.load(Ordering::SeqCst) {
             return Err(ErroHandle::new(
@@ -90,6 +152,8 @@ impl RetroCore {
             ));
         }
 
+        let _ctx = self.enter_ctx();
+
         unsafe {
             self.initialized.store(true, Ordering::SeqCst);
             self.raw.retro_init();
@@ -99,6 +163,87 @@ impl RetroCore {
     }
 
     pub fn load_game(&self, path: &str) -> Result<Arc<AvInfo>, ErroHandle> {
+        self.can_load_game()?;
+
+        let _ctx = self.enter_ctx();
+        let loaded =
+            RomTools::try_load_game(&self.raw, &self.system.info, &self.disk_control, path)?;
+        self.game_loaded.store(loaded, Ordering::SeqCst);
+
+        if loaded {
+            self.on_game_loaded(RomTools::get_rom_name(&PathBuf::from(path))?)
+        } else {
+            Err(ErroHandle::new("nao foi possível carregar a rom"))
+        }
+    }
+
+    #[doc = "carrega varias roms de uma vez usando um dos subsistemas do core (ex: Super Game Boy).
+    as roms devem seguir a mesma ordem de [crate::system::SubSystemInfo::roms], use uma string vazia
+    para pular uma rom opcional"]
+    pub fn load_game_special(
+        &self,
+        subsystem_ident: &str,
+        roms: &[String],
+    ) -> Result<Arc<AvInfo>, ErroHandle> {
+        self.can_load_game()?;
+
+        let (subsystem_id, games_info) = {
+            let subsystems = self.system.subsystem.read()?;
+            let subsystem = subsystems
+                .iter()
+                .find(|subsystem| *subsystem.ident == subsystem_ident)
+                .ok_or(ErroHandle {
+                    message: format!("O núcleo nao tem o subsistema {}", subsystem_ident),
+                })?;
+
+            (
+                *subsystem.id,
+                RomTools::get_special_games_info(subsystem, roms)?,
+            )
+        };
+
+        let _ctx = self.enter_ctx();
+        let loaded = RomTools::try_load_game_special(&self.raw, subsystem_id, &games_info)?;
+        self.game_loaded.store(loaded, Ordering::SeqCst);
+
+        if !loaded {
+            return Err(ErroHandle::new("nao foi possível carregar as roms"));
+        }
+
+        //os saves ficam separados para cada combinação de roms
+        let mut names = Vec::new();
+        for rom in roms.iter().filter(|rom| !rom.is_empty()) {
+            names.push(RomTools::get_rom_name(&PathBuf::from(rom))?);
+        }
+
+        self.on_game_loaded(names.join(" + "))
+    }
+
+    #[doc = "inicia o core sem nenhuma rom, como em jogos que ja estão embutidos no core (ex: 2048).
+    os saves e as opções usam o nome do core no lugar do nome da rom"]
+    pub fn load_no_game(&self) -> Result<Arc<AvInfo>, ErroHandle> {
+        self.can_load_game()?;
+
+        if !self.support_no_game.load(Ordering::SeqCst) {
+            return Err(ErroHandle::new(
+                "Esse núcleo precisa de uma rom para ser iniciado",
+            ));
+        }
+
+        let _ctx = self.enter_ctx();
+        let loaded = RomTools::try_load_no_game(&self.raw);
+        self.game_loaded.store(loaded, Ordering::SeqCst);
+
+        if loaded {
+            self.on_game_loaded(self.system.info.library_name.to_string())
+        } else {
+            Err(ErroHandle::new(
+                "nao foi possível iniciar o núcleo sem uma rom",
+            ))
+        }
+    }
+
+    fn can_load_game(&self) -> Result<(), ErroHandle> {
         if self.game_loaded.load(Ordering::SeqCst) {
             return Err(ErroHandle::new("Ja existe uma rom carregada no momento"));
         }
@@ -109,18 +254,44 @@ impl RetroCore {
             ));
         }
 
-        let loaded = RomTools::try_load_game(&self.raw, &self.system.info, path)?;
-        self.game_loaded.store(loaded, Ordering::SeqCst);
+        Ok(())
+    }
 
-        if loaded {
-            *self.rom_name.write()? = RomTools::get_rom_name(&PathBuf::from(path))?;
+    fn on_game_loaded(&self, rom_name: String) -> Result<Arc<AvInfo>, ErroHandle> {
+        *self.rom_name.write()? = rom_name;
 
-            self.av_info.update_av_info(&self.raw)?;
+        self.av_info.update_av_info(&self.raw)?;
 
-            Ok(self.av_info.clone())
-        } else {
-            Err(ErroHandle::new("nao foi possível carregar a rom"))
+        //nesse ponto a rom ja esta carregada no core, então um save que nao pode ser lido nao deve
+        //impedir o jogo de iniciar
+        if let Err(e) = RomTools::load_battery_saves(
+            &self.raw,
+            &self.paths.save,
+            &self.system.info,
+            &*self.rom_name.read()?,
+        ) {
+            println!("load_battery_saves -> {:?}", e);
+        }
+
+        //um arquivo de cheats invalido também nao deve impedir o jogo de iniciar
+        if let Err(e) = self
+            .cheats
+            .load(&self.paths.cheats, &self.rom_name.read()?)
+            .and_then(|_| self.apply_cheats())
+        {
+            println!("load_cheats -> {:?}", e);
         }
+
+        //a libretro so permite iniciar a localização depois que a rom e carregada
+        if self.callbacks.location.is_some() {
+            self.location.notify_initialized()?;
+        }
+
+        if self.callbacks.camera.is_some() {
+            self.camera.notify_initialized()?;
+        }
+
+        Ok(self.av_info.clone())
     }
 
     pub fn reset(&self) -> Result<(), ErroHandle> {
@@ -132,6 +303,8 @@ impl RetroCore {
             return Err(ErroHandle::new("Nao ha nenhuma rum carregada no momento"));
         }
 
+        let _ctx = self.enter_ctx();
+
         unsafe {
             self.raw.retro_reset();
         }
@@ -140,6 +313,11 @@ impl RetroCore {
     }
 
     pub fn run(&self) -> Result<(), ErroHandle> {
+        self.run_frame(false)
+    }
+
+    //os frames escondidos do run-ahead nao fazem parte do filme
+    fn run_frame(&self, hidden: bool) -> Result<(), ErroHandle> {
         if !self.initialized.load(Ordering::SeqCst) {
             return Err(ErroHandle::new("O núcleo nao foi inicializado"));
         }
@@ -148,25 +326,384 @@ impl RetroCore {
             return Err(ErroHandle::new("Nao ha nenhuma rum carregada no momento"));
         }
 
+        let _ctx = self.enter_ctx();
+
+        self.update_option_display()?;
+
+        //o core recebe a imagem da câmera antes de gerar o frame
+        if let Some(source) = &self.callbacks.camera {
+            self.camera.push_frame(source.as_ref())?;
+        }
+
+        self.movie.begin_frame(hidden)?;
+
         unsafe { self.raw.retro_run() }
 
+        //um erro no hash nao deve interromper o jogo
+        if let Err(e) = self.check_movie_hash() {
+            println!("check_movie_hash -> {:?}", e);
+        }
+
+        Ok(())
+    }
+
+    fn check_movie_hash(&self) -> Result<(), ErroHandle> {
+        let source = match self.movie.get_hash_request()? {
+            Some(source) => source,
+            None => return Ok(()),
+        };
+
+        let hash = self.hash_state(&source)?;
+
+        if let Some(desync) = self.movie.push_hash(hash)? {
+            let entry = RetroLogEntry::new(
+                retro_log_level::RETRO_LOG_WARN,
+                self.system.info.library_name.to_string(),
+                &format!(
+                    "[movie] o jogo perdeu a sincronia com o filme no frame {}\n",
+                    desync.frame
+                ),
+            );
+
+            self.callbacks.logger.log(&entry)?;
+        }
+
+        Ok(())
+    }
+
+    #[doc = "hash (FNV-1a) do estado atual do jogo. regiões de memoria que o core nao expõe sao ignoradas"]
+    pub fn hash_state(&self, source: &StateHashSource) -> Result<u64, ErroHandle> {
+        let mut hasher = StateHasher::new();
+
+        match source {
+            StateHashSource::SaveState => {
+                //o run-ahead e a verificação desabilitam o áudio e o vídeo, o que permite ao core
+                //criar states diferentes. O hash deve usar sempre o state completo
+                let (video, audio) = (
+                    self.output.is_video_enabled(),
+                    self.output.is_audio_enabled(),
+                );
+
+                self.output.set_enabled(true, true);
+                let state = self.serialize();
+                self.output.set_enabled(video, audio);
+
+                hasher.update(&state?);
+            }
+            StateHashSource::Memory(regions) => {
+                for region in regions {
+                    let size = self.get_memory_size(*region)?;
+
+                    if size > 0 {
+                        hasher.update(&self.read_memory(*region, 0, size)?);
+                    }
+                }
+            }
+        }
+
+        Ok(hasher.finish())
+    }
+
+    #[doc = "gera o frame atual e mais *frames* escondidos, exibindo o ultimo. Depois o core volta ao
+    state do frame atual. Isso remove alguns frames do atraso entre o input e a resposta do jogo"]
+    pub fn run_ahead(&self, frames: u32) -> Result<(), ErroHandle> {
+        if frames == 0 {
+            return self.run();
+        }
+
+        self.run_main_frame()?;
+
+        let context = retro_savestate_context::RETRO_SAVESTATE_CONTEXT_RUNAHEAD_SAME_INSTANCE;
+        let state = self.serialize_with(context)?;
+
+        self.run_hidden_frames(frames)?;
+        self.unserialize_with(&state, context)
+    }
+
+    #[doc = "igual a [RetroCore::run_ahead], mas os frames escondidos sao gerados por *secondary*,
+    que deve ser outra copia do mesmo core com a mesma rom carregada"]
+    pub fn run_ahead_with(&self, secondary: &RetroCore, frames: u32) -> Result<(), ErroHandle> {
+        if frames == 0 {
+            return self.run();
+        }
+
+        self.run_main_frame()?;
+
+        //a segunda instancia gera o frame exibido, então precisa usar as mesmas opções
+        secondary.options.sync_selected_from(&self.options)?;
+
+        let context = retro_savestate_context::RETRO_SAVESTATE_CONTEXT_RUNAHEAD_SAME_BINARY;
+        let state = self.serialize_with(context)?;
+
+        secondary.unserialize_with(&state, context)?;
+        secondary.run_hidden_frames(frames)
+    }
+
+    //so o áudio do frame real e usado, o vídeo exibido sera o do ultimo frame escondido
+    fn run_main_frame(&self) -> Result<(), ErroHandle> {
+        self.output.set_enabled(false, true);
+        let result = self.run();
+        self.output.set_enabled(true, true);
+
+        result
+    }
+
+    fn run_hidden_frames(&self, frames: u32) -> Result<(), ErroHandle> {
+        let mut result = Ok(());
+
+        for frame in 1..=frames {
+            self.output.set_enabled(frame == frames, false);
+            result = self.run_frame(true);
+
+            if result.is_err() {
+                break;
+            }
+        }
+
+        self.output.set_enabled(true, true);
+
+        result
+    }
+
+    #[doc = "começa a gravar os inputs lidos pelo core. Com [MovieOrigin::PowerOn] o jogo e reiniciado
+    antes, senão o state atual e guardado no filme. Com *hash_config* o filme também guarda hashes do
+    estado do jogo, usados para encontrar dessincronias na reprodução"]
+    pub fn start_movie_recording(
+        &self,
+        origin: MovieOrigin,
+        hash_config: Option<StateHashConfig>,
+    ) -> Result<(), ErroHandle> {
+        if !self.game_loaded.load(Ordering::SeqCst) {
+            return Err(ErroHandle::new("Uma rom precisa ser carregada primeiro"));
+        }
+
+        if let Some(config) = &hash_config {
+            if config.source == StateHashSource::SaveState && self.get_serialize_size()? == 0 {
+                return Err(ErroHandle::new(
+                    "O núcleo nao suporta states, escolha regiões de memoria para o hash",
+                ));
+            }
+        }
+
+        let start = match origin {
+            //a SRAM e gravada junto para que o filme nao dependa do save do jogador
+            MovieOrigin::PowerOn => {
+                self.reset()?;
+                MovieStart::PowerOn {
+                    save_ram: self.read_save_ram()?,
+                }
+            }
+            MovieOrigin::CurrentState => MovieStart::SaveState(self.serialize()?),
+        };
+
+        let mut movie = Movie::new(
+            self.system.info.library_name.to_string(),
+            self.system.info.library_version.to_string(),
+            self.rom_name.read()?.clone(),
+            start,
+        );
+        movie.hash_config = hash_config;
+
+        self.movie.start_recording(movie)
+    }
+
+    #[doc = "volta ao inicio do filme e passa a usar os inputs gravados no lugar dos inputs do jogador"]
+    pub fn play_movie(&self, movie: Movie) -> Result<(), ErroHandle> {
+        if !self.game_loaded.load(Ordering::SeqCst) {
+            return Err(ErroHandle::new("Uma rom precisa ser carregada primeiro"));
+        }
+
+        if movie.core_name != *self.system.info.library_name {
+            return Err(ErroHandle {
+                message: format!("O filme foi gravado com o núcleo {}", movie.core_name),
+            });
+        }
+
+        match &movie.start {
+            MovieStart::PowerOn { save_ram } => {
+                self.reset()?;
+                self.write_save_ram(save_ram)?;
+            }
+            MovieStart::SaveState(state) => self.unserialize(state)?,
+        }
+
+        self.movie.start_playback(movie)
+    }
+
+    #[doc = "reproduz o filme inteiro o mais rápido possível, sem áudio e vídeo, e retorna o primeiro
+    frame em que o hash do jogo for diferente do gravado. Útil para testar cores depois de atualizações.
+    O jogo volta ao estado em que estava antes da verificação"]
+    pub fn verify_movie(&self, movie: Movie) -> Result<MovieVerification, ErroHandle> {
+        if movie.hash_config.is_none() || movie.hashes.is_empty() {
+            return Err(ErroHandle::new(
+                "O filme nao tem hashes para serem verificados",
+            ));
+        }
+
+        if self.get_serialize_size()? == 0 {
+            return Err(ErroHandle::new(
+                "O núcleo nao suporta states, então o jogo atual seria perdido na verificação",
+            ));
+        }
+
+        //o filme reinicia o jogo e pode trocar a SRAM, então as duas precisam ser guardadas
+        let player_state = self.serialize()?;
+        let player_save_ram = self.read_save_ram()?;
+
+        let verification = self.run_movie_verification(movie);
+
+        self.unserialize(&player_state)?;
+        self.write_save_ram(&player_save_ram)?;
+
+        verification
+    }
+
+    fn run_movie_verification(&self, movie: Movie) -> Result<MovieVerification, ErroHandle> {
+        let frames = movie.frames.len();
+        self.play_movie(movie)?;
+
+        self.output.set_enabled(false, false);
+
+        let mut result = Ok(());
+        for _ in 0..frames {
+            result = self.update_frame_time().and_then(|_| self.run());
+
+            if result.is_err() || self.movie.get_desync()?.is_some() {
+                break;
+            }
+        }
+
+        self.output.set_enabled(true, true);
+        self.movie.stop()?;
+        result?;
+
+        Ok(MovieVerification {
+            frames,
+            checked_hashes: self.movie.get_checked_hashes(),
+            desync: self.movie.get_desync()?,
+        })
+    }
+
+    //cores sem SRAM retornam um vetor vazio
+    fn read_save_ram(&self) -> Result<Vec<u8>, ErroHandle> {
+        match self.get_memory_size(MemoryRegion::SaveRam)? {
+            0 => Ok(Vec::new()),
+            size => self.read_memory(MemoryRegion::SaveRam, 0, size),
+        }
+    }
+
+    fn write_save_ram(&self, save_ram: &[u8]) -> Result<(), ErroHandle> {
+        let size = self.get_memory_size(MemoryRegion::SaveRam)?;
+
+        if save_ram.len() != size {
+            return Err(ErroHandle {
+                message: format!(
+                    "A SRAM do filme tem {} bytes, mas a do núcleo tem {}",
+                    save_ram.len(),
+                    size
+                ),
+            });
+        }
+
+        if size > 0 {
+            self.write_memory(MemoryRegion::SaveRam, 0, save_ram)?;
+        }
+
+        Ok(())
+    }
+
+    #[doc = "encerra a gravação ou a reprodução. retorna o filme se ele estava sendo gravado"]
+    pub fn stop_movie(&self) -> Result<Option<Movie>, ErroHandle> {
+        self.movie.stop()
+    }
+
+    #[doc = "uma opção alterada pelo usuário pode fazer o core esconder ou mostrar outras opções. deve
+    ser chamada mesmo com o jogo pausado, ja que as opções podem ser alteradas durante a pausa"]
+    pub fn update_option_display(&self) -> Result<(), ErroHandle> {
+        if let Some(update_display) = self.options.take_display_update()? {
+            let _ctx = self.enter_ctx();
+            unsafe { update_display() };
+        }
+
         Ok(())
     }
 
+    #[doc = "envia para o core o tempo real desde o ultimo frame. deve ser chamada antes de [RetroCore::run].
+    enquanto um filme e gravado ou reproduzido o tempo de referencia do core e usado"]
+    pub fn update_frame_time(&self) -> Result<(), ErroHandle> {
+        let _ctx = self.enter_ctx();
+
+        //o tempo real seria diferente em cada reprodução e o jogo perderia a sincronia com o filme
+        if self.movie.get_mode()? != MovieMode::Idle {
+            self.frame_time.reset()?;
+        }
+
+        self.frame_time.notify()
+    }
+
+    #[doc = "a proxima chamada de [RetroCore::update_frame_time] usara o tempo de referencia do core"]
+    pub fn reset_frame_time(&self) -> Result<(), ErroHandle> {
+        self.frame_time.reset()
+    }
+
+    #[doc = "pede ao core mais áudio, se ele usar RETRO_ENVIRONMENT_SET_AUDIO_CALLBACK"]
+    pub fn run_audio_callback(&self) -> Result<(), ErroHandle> {
+        let _ctx = self.enter_ctx();
+        self.audio_callback.run()
+    }
+
+    pub fn set_audio_state(&self, enabled: bool) -> Result<(), ErroHandle> {
+        let _ctx = self.enter_ctx();
+        self.audio_callback.set_state(enabled)
+    }
+
+    pub fn report_audio_buffer_status(&self, status: AudioBufferStatus) -> Result<(), ErroHandle> {
+        let _ctx = self.enter_ctx();
+        self.audio_callback.report_buffer_status(status)
+    }
+
+    #[doc = "tempo gasto em cada contador registrado pelo core com a interface de desempenho"]
+    pub fn get_perf_report(&self) -> Result<Vec<PerfCounterReport>, ErroHandle> {
+        self.perf.get_report()
+    }
+
+    #[doc = "envia o relatório dos contadores para o logger do core"]
+    pub fn log_perf_report(&self) -> Result<(), ErroHandle> {
+        let report = self.get_perf_report()?;
+
+        if report.is_empty() {
+            return Ok(());
+        }
+
+        let entry = RetroLogEntry::new(
+            retro_log_level::RETRO_LOG_INFO,
+            self.system.info.library_name.to_string(),
+            &format!("[perf]\n{}", format_perf_report(&report)),
+        );
+
+        self.callbacks.logger.log(&entry)
+    }
+
     pub fn de_init(&self) -> Result<(), ErroHandle> {
         //Se uma *rom* estive carrega ela deve ser descarregada primeiro
         if let Err(e) = self.unload_game() {
             self.initialized.store(false, Ordering::SeqCst);
-            core_env::delete_local_core_ctx();
 
             return Err(e);
         }
 
+        let _ctx = self.enter_ctx();
+
         unsafe {
             self.raw.retro_deinit();
         }
         self.initialized.store(false, Ordering::SeqCst);
-        core_env::delete_local_core_ctx();
+
+        //depois do retro_deinit o núcleo nao tem mais nenhuma thread rodando
+        core_env::unregister_loaded_core(self);
+
+        //os contadores ficam na memoria do core, que nao deve mais ser acessada
+        self.perf.clear()?;
 
         Ok(())
     }
@@ -179,6 +716,8 @@ impl RetroCore {
         }
 
         if port != INVALID_CONTROLLER_PORT {
+            let _ctx = self.enter_ctx();
+
             unsafe {
                 self.raw
                     .retro_set_controller_port_device(port as u32, controller);
@@ -199,12 +738,41 @@ impl RetroCore {
             ));
         }
 
+        let _ctx = self.enter_ctx();
+
+        //a rom deve ser descarregada mesmo se nao for possível salvar a memoria da bateria
+        let saved = self.save_battery();
+        let _ = self.audio_callback.set_state(false);
+        let _ = self.location.notify_deinitialized();
+        if let Some(source) = &self.callbacks.camera {
+            let _ = self.camera.notify_deinitialized(source.as_ref());
+        }
+
         unsafe {
             self.raw.retro_unload_game();
         }
         self.game_loaded.store(false, Ordering::SeqCst);
 
-        Ok(())
+        //o mapa de memoria aponta para buffers que so existem enquanto a rom esta carregada
+        self.memory.clear()?;
+
+        saved
+    }
+
+    #[doc = "salva a SRAM e o RTC da rom atual. Isso ja e feito ao descarregar a rom"]
+    pub fn save_battery(&self) -> Result<(), ErroHandle> {
+        if !self.game_loaded.load(Ordering::SeqCst) {
+            return Err(ErroHandle::new("Uma rom precisa ser carregada primeiro"));
+        }
+
+        let _ctx = self.enter_ctx();
+
+        RomTools::save_battery_saves(
+            &self.raw,
+            &self.paths.save,
+            &self.system.info,
+            &*self.rom_name.read()?,
+        )
     }
 
     pub fn save_state(&self, slot: usize) -> Result<PathBuf, ErroHandle> {
@@ -218,6 +786,8 @@ impl RetroCore {
             ));
         }
 
+        let _ctx = self.enter_ctx();
+
         RomTools::create_save_state(
             &self.raw,
             &self.paths.save,
@@ -238,6 +808,8 @@ impl RetroCore {
             ));
         }
 
+        let _ctx = self.enter_ctx();
+
         RomTools::load_save_state(
             &self.raw,
             &self.paths.save,
@@ -248,6 +820,242 @@ impl RetroCore {
 
         Ok(())
     }
+
+    #[doc = "tamanho em bytes do estado do core. cores que retornam 0 nao suportam states"]
+    pub fn get_serialize_size(&self) -> Result<usize, ErroHandle> {
+        if !self.game_loaded.load(Ordering::SeqCst) {
+            return Err(ErroHandle::new("Uma rom precisa ser carregada primeiro"));
+        }
+
+        let _ctx = self.enter_ctx();
+
+        Ok(unsafe { self.raw.retro_serialize_size() })
+    }
+
+    #[doc = "retorna o estado atual do core sem salvar em disco"]
+    pub fn serialize(&self) -> Result<Vec<u8>, ErroHandle> {
+        self.serialize_with(retro_savestate_context::RETRO_SAVESTATE_CONTEXT_NORMAL)
+    }
+
+    #[doc = "restaura um estado retornado por [RetroCore::serialize]"]
+    pub fn unserialize(&self, data: &[u8]) -> Result<(), ErroHandle> {
+        self.unserialize_with(
+            data,
+            retro_savestate_context::RETRO_SAVESTATE_CONTEXT_NORMAL,
+        )
+    }
+
+    //o core pode perguntar o contexto do state usando RETRO_ENVIRONMENT_GET_SAVESTATE_CONTEXT
+    fn serialize_with(&self, context: retro_savestate_context) -> Result<Vec<u8>, ErroHandle> {
+        if !self.game_loaded.load(Ordering::SeqCst) {
+            return Err(ErroHandle::new("Uma rom precisa ser carregada primeiro"));
+        }
+
+        let _ctx = self.enter_ctx();
+
+        self.output.set_savestate_context(context)?;
+        let state = RomTools::serialize(&self.raw);
+        self.output
+            .set_savestate_context(retro_savestate_context::RETRO_SAVESTATE_CONTEXT_NORMAL)?;
+
+        state
+    }
+
+    fn unserialize_with(
+        &self,
+        data: &[u8],
+        context: retro_savestate_context,
+    ) -> Result<(), ErroHandle> {
+        if !self.game_loaded.load(Ordering::SeqCst) {
+            return Err(ErroHandle::new("Uma rom precisa ser carregada primeiro"));
+        }
+
+        let _ctx = self.enter_ctx();
+
+        self.output.set_savestate_context(context)?;
+        let result = RomTools::unserialize(&self.raw, data);
+        self.output
+            .set_savestate_context(retro_savestate_context::RETRO_SAVESTATE_CONTEXT_NORMAL)?;
+
+        result
+    }
+
+    pub fn get_memory_size(&self, region: MemoryRegion) -> Result<usize, ErroHandle> {
+        if !self.game_loaded.load(Ordering::SeqCst) {
+            return Err(ErroHandle::new("Uma rom precisa ser carregada primeiro"));
+        }
+
+        let _ctx = self.enter_ctx();
+
+        Ok(unsafe { self.raw.retro_get_memory_size(region.id()) })
+    }
+
+    pub fn read_memory(
+        &self,
+        region: MemoryRegion,
+        offset: usize,
+        len: usize,
+    ) -> Result<Vec<u8>, ErroHandle> {
+        let memory = self.get_memory_region(region, offset, len)?;
+        let data = unsafe { &*slice_from_raw_parts(memory.add(offset), len) };
+
+        Ok(data.to_vec())
+    }
+
+    pub fn write_memory(
+        &self,
+        region: MemoryRegion,
+        offset: usize,
+        data: &[u8],
+    ) -> Result<(), ErroHandle> {
+        let memory = self.get_memory_region(region, offset, data.len())?;
+        let target = unsafe { &mut *slice_from_raw_parts_mut(memory.add(offset), data.len()) };
+
+        target.copy_from_slice(data);
+
+        Ok(())
+    }
+
+    #[doc = "lê a memoria usando os endereços do sistema emulado. So funciona se o core enviar o mapa de memoria"]
+    pub fn read_memory_address(&self, address: usize, len: usize) -> Result<Vec<u8>, ErroHandle> {
+        if !self.game_loaded.load(Ordering::SeqCst) {
+            return Err(ErroHandle::new("Uma rom precisa ser carregada primeiro"));
+        }
+
+        self.memory.read(address, len)
+    }
+
+    #[doc = "escreve na memoria usando os endereços do sistema emulado. So funciona se o core enviar o mapa de memoria"]
+    pub fn write_memory_address(&self, address: usize, data: &[u8]) -> Result<(), ErroHandle> {
+        if !self.game_loaded.load(Ordering::SeqCst) {
+            return Err(ErroHandle::new("Uma rom precisa ser carregada primeiro"));
+        }
+
+        self.memory.write(address, data)
+    }
+
+    pub fn get_cheats(&self) -> Result<Vec<Cheat>, ErroHandle> {
+        self.cheats.get_cheats()
+    }
+
+    #[doc = "ativa ou desativa um cheat. O novo estado e salvo no arquivo .cht da rom"]
+    pub fn set_cheat(&self, index: usize, enabled: bool) -> Result<(), ErroHandle> {
+        if !self.game_loaded.load(Ordering::SeqCst) {
+            return Err(ErroHandle::new("Uma rom precisa ser carregada primeiro"));
+        }
+
+        self.cheats.set_enabled(index, enabled)?;
+        self.apply_cheats()
+    }
+
+    #[doc = "desativa todos os cheats da rom atual"]
+    pub fn reset_cheats(&self) -> Result<(), ErroHandle> {
+        if !self.game_loaded.load(Ordering::SeqCst) {
+            return Err(ErroHandle::new("Uma rom precisa ser carregada primeiro"));
+        }
+
+        self.cheats.reset()?;
+        self.apply_cheats()
+    }
+
+    //a libretro nao tem uma forma de remover um único cheat, então todos sao enviados novamente
+    fn apply_cheats(&self) -> Result<(), ErroHandle> {
+        let _ctx = self.enter_ctx();
+
+        unsafe {
+            self.raw.retro_cheat_reset();
+        }
+
+        for cheat in self.cheats.get_enabled()? {
+            let code = make_c_string(&cheat.code)?;
+
+            unsafe {
+                self.raw
+                    .retro_cheat_set(cheat.index as u32, true, code.as_ptr());
+            }
+        }
+
+        Ok(())
+    }
+
+    #[doc = "abre a bandeja de discos. Deve ser chamado antes de [RetroCore::set_disk_index]"]
+    pub fn eject_disk(&self) -> Result<(), ErroHandle> {
+        if !self.game_loaded.load(Ordering::SeqCst) {
+            return Err(ErroHandle::new("Uma rom precisa ser carregada primeiro"));
+        }
+
+        let _ctx = self.enter_ctx();
+        self.disk_control.set_eject_state(true)
+    }
+
+    #[doc = "fecha a bandeja de discos"]
+    pub fn insert_disk(&self) -> Result<(), ErroHandle> {
+        if !self.game_loaded.load(Ordering::SeqCst) {
+            return Err(ErroHandle::new("Uma rom precisa ser carregada primeiro"));
+        }
+
+        let _ctx = self.enter_ctx();
+        self.disk_control.set_eject_state(false)
+    }
+
+    pub fn set_disk_index(&self, index: u32) -> Result<(), ErroHandle> {
+        if !self.game_loaded.load(Ordering::SeqCst) {
+            return Err(ErroHandle::new("Uma rom precisa ser carregada primeiro"));
+        }
+
+        let _ctx = self.enter_ctx();
+
+        if !self.disk_control.get_eject_state()? {
+            return Err(ErroHandle::new(
+                "A bandeja precisa estar aberta para trocar o disco",
+            ));
+        }
+
+        self.disk_control.set_image_index(index)
+    }
+
+    pub fn get_disk_index(&self) -> Result<u32, ErroHandle> {
+        let _ctx = self.enter_ctx();
+        self.disk_control.get_image_index()
+    }
+
+    pub fn get_disk_count(&self) -> Result<u32, ErroHandle> {
+        let _ctx = self.enter_ctx();
+        self.disk_control.get_num_images()
+    }
+
+    fn get_memory_region(
+        &self,
+        region: MemoryRegion,
+        offset: usize,
+        len: usize,
+    ) -> Result<*mut u8, ErroHandle> {
+        let size = self.get_memory_size(region)?;
+        let data = unsafe { self.raw.retro_get_memory_data(region.id()) };
+
+        if size == 0 || data.is_null() {
+            return Err(ErroHandle {
+                message: format!("O núcleo nao expõe a memoria {:?}", region),
+            });
+        }
+
+        if offset.checked_add(len).map_or(true, |end| end > size) {
+            return Err(ErroHandle {
+                message: format!(
+                    "Acesso fora da memoria {:?}: offset {} + {} bytes, tamanho {}",
+                    region, offset, len, size
+                ),
+            });
+        }
+
+        Ok(data as *mut u8)
+    }
+}
+
+impl Drop for RetroCore {
+    fn drop(&mut self) {
+        core_env::unregister_loaded_core(self);
+    }
 }
 
 #[cfg(test)]
@@ -8,35 +8,34 @@ use crate::libretro_sys::binding_libretro::{
     RETRO_SIMD_MMX, RETRO_SIMD_SSE, RETRO_SIMD_SSE2, RETRO_SIMD_SSE3, RETRO_SIMD_SSE4,
     RETRO_SIMD_SSE42,
 };
-
-static mut LAST_COUNTER: Option<*mut retro_perf_counter> = None;
+use crate::{core_env::get_core_ctx, managers::perf_manager::PerfManager};
 
 pub unsafe extern "C" fn core_get_perf_counter() -> retro_perf_tick_t {
     SDL_GetPerformanceCounter() as retro_perf_tick_t
 }
 
+//os contadores pertencem ao core e precisam ser alterados no próprio lugar, nunca copiados
 pub unsafe extern "C" fn core_perf_register(counter_raw: *mut retro_perf_counter) {
-    let mut counter = *counter_raw;
-    counter.registered = true;
-    LAST_COUNTER = Some(counter_raw);
+    if let Some(core_ctx) = get_core_ctx() {
+        if let Err(e) = core_ctx.perf.register(counter_raw) {
+            println!("core_perf_register -> {:?}", e);
+        }
+    }
 }
 
 pub unsafe extern "C" fn core_perf_start(counter_raw: *mut retro_perf_counter) {
-    let mut counter = *counter_raw;
-    if counter.registered {
-        counter.start = core_get_perf_counter();
-    }
+    PerfManager::start(counter_raw, core_get_perf_counter());
 }
 
 pub unsafe extern "C" fn core_perf_stop(counter_raw: *mut retro_perf_counter) {
-    let mut counter = *counter_raw;
-    counter.total = core_get_perf_counter() - counter.start;
+    PerfManager::stop(counter_raw, core_get_perf_counter());
 }
 
 pub unsafe extern "C" fn core_perf_log() {
-    if let Some(counter_raw) = LAST_COUNTER {
-        let counter = *counter_raw;
-        println!("[timer] {:?}", counter);
+    if let Some(core_ctx) = get_core_ctx() {
+        if let Err(e) = core_ctx.log_perf_report() {
+            println!("core_perf_log -> {:?}", e);
+        }
     }
 }
 
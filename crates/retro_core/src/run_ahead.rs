@@ -0,0 +1,134 @@
+use generics::erro_handle::ErroHandle;
+use libretro_sys::binding_libretro::retro_savestate_context;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    RwLock,
+};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RunAheadMode {
+    #[doc = "o próprio core volta ao state salvo depois de gerar os frames escondidos"]
+    #[default]
+    SameInstance,
+    #[doc = "os frames escondidos sao gerados por uma segunda copia do core. evita que o áudio e os
+    efeitos de carregar um state afetem o core principal, mas usa o dobro de memoria"]
+    SecondInstance,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RunAheadConfig {
+    #[doc = "quantidade de frames gerados a frente do jogo. 0 desabilita o run-ahead"]
+    pub frames: u32,
+    pub mode: RunAheadMode,
+}
+
+/// Informa ao core se o áudio e o vídeo do frame atual serão usados e por que um state esta sendo
+/// criado. Usado em RETRO_ENVIRONMENT_GET_AUDIO_VIDEO_ENABLE e RETRO_ENVIRONMENT_GET_SAVESTATE_CONTEXT.
+///
+/// Durante os frames escondidos do run-ahead as callbacks de áudio e vídeo também sao ignoradas,
+/// já que nem todo core respeita esses valores.
+#[derive(Debug)]
+pub struct FrameOutput {
+    video: AtomicBool,
+    audio: AtomicBool,
+    savestate_context: RwLock<retro_savestate_context>,
+}
+
+impl Default for FrameOutput {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FrameOutput {
+    pub fn new() -> Self {
+        Self {
+            video: AtomicBool::new(true),
+            audio: AtomicBool::new(true),
+            savestate_context: RwLock::new(retro_savestate_context::RETRO_SAVESTATE_CONTEXT_NORMAL),
+        }
+    }
+
+    pub fn set_enabled(&self, video: bool, audio: bool) {
+        self.video.store(video, Ordering::SeqCst);
+        self.audio.store(audio, Ordering::SeqCst);
+    }
+
+    pub fn is_video_enabled(&self) -> bool {
+        self.video.load(Ordering::SeqCst)
+    }
+
+    pub fn is_audio_enabled(&self) -> bool {
+        self.audio.load(Ordering::SeqCst)
+    }
+
+    pub fn set_savestate_context(
+        &self,
+        context: retro_savestate_context,
+    ) -> Result<(), ErroHandle> {
+        *self.savestate_context.write()? = context;
+        Ok(())
+    }
+
+    pub fn get_savestate_context(&self) -> Result<retro_savestate_context, ErroHandle> {
+        Ok(*self.savestate_context.read()?)
+    }
+
+    #[doc = "bit 0: vídeo, bit 1: áudio, bit 2: states rápidos, bit 3: áudio desligado de vez"]
+    pub fn get_av_enable_flags(&self) -> Result<u32, ErroHandle> {
+        let mut flags = 0;
+
+        if self.is_video_enabled() {
+            flags |= 1 << 0;
+        }
+
+        if self.is_audio_enabled() {
+            flags |= 1 << 1;
+        } else {
+            //o áudio dos frames escondidos nunca sera reproduzido
+            flags |= 1 << 3;
+        }
+
+        //fora do run-ahead o áudio e o vídeo estão sempre habilitados. Os states do run-ahead nunca
+        //sao salvos em disco, então o core pode pular algumas verificações
+        let run_ahead = !self.is_video_enabled()
+            || !self.is_audio_enabled()
+            || self.get_savestate_context()?
+                != retro_savestate_context::RETRO_SAVESTATE_CONTEXT_NORMAL;
+
+        if run_ahead {
+            flags |= 1 << 2;
+        }
+
+        Ok(flags)
+    }
+}
+
+#[cfg(test)]
+mod test_run_ahead {
+    use super::FrameOutput;
+    use libretro_sys::binding_libretro::retro_savestate_context;
+
+    #[test]
+    fn av_enable_flags() {
+        let output = FrameOutput::new();
+        assert_eq!(output.get_av_enable_flags().unwrap(), 0b0011);
+
+        output.set_enabled(false, false);
+        output
+            .set_savestate_context(
+                retro_savestate_context::RETRO_SAVESTATE_CONTEXT_RUNAHEAD_SAME_INSTANCE,
+            )
+            .unwrap();
+        assert_eq!(output.get_av_enable_flags().unwrap(), 0b1100);
+
+        output.set_enabled(true, false);
+        assert_eq!(output.get_av_enable_flags().unwrap(), 0b1101);
+
+        output
+            .set_savestate_context(retro_savestate_context::RETRO_SAVESTATE_CONTEXT_NORMAL)
+            .unwrap();
+        output.set_enabled(false, true);
+        assert_eq!(output.get_av_enable_flags().unwrap(), 0b0110);
+    }
+}
@@ -0,0 +1,183 @@
+use generics::erro_handle::ErroHandle;
+use libretro_sys::binding_libretro::{
+    retro_sensor_action::{self, *},
+    RETRO_SENSOR_ACCELEROMETER_X, RETRO_SENSOR_ACCELEROMETER_Z, RETRO_SENSOR_GYROSCOPE_X,
+    RETRO_SENSOR_GYROSCOPE_Z, RETRO_SENSOR_ILLUMINANCE,
+};
+use std::{
+    fmt::Debug,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, RwLock,
+    },
+};
+
+#[doc = "aceleração da gravidade em m/s², usada como valor padrão do eixo Z do acelerômetro"]
+pub const STANDARD_GRAVITY: f32 = 9.80665;
+
+#[doc = "quantidade de ids RETRO_SENSOR_* (acelerômetro X/Y/Z, giroscópio X/Y/Z e iluminação)"]
+pub const SENSOR_ID_COUNT: usize = RETRO_SENSOR_ILLUMINANCE as usize + 1;
+
+/// Sensores de movimento e luz usados por jogos como WarioWare: Twisted! e Kirby Tilt 'n' Tumble.
+///
+/// Usado em RETRO_ENVIRONMENT_GET_SENSOR_INTERFACE. Os valores seguem o padrão da libretro:
+/// aceleração em m/s², giroscópio em rad/s e iluminação em lux.
+pub trait SensorProvider: Debug {
+    #[doc = "o core pede para ligar ou desligar um sensor. retorne false se o sensor nao existir"]
+    fn set_sensor_state(
+        &self,
+        port: u32,
+        action: retro_sensor_action,
+        rate: u32,
+    ) -> Result<bool, ErroHandle>;
+
+    #[doc = "*id* e um dos RETRO_SENSOR_*. chamada dentro do retro_run, então evite trabalhos pesados aqui"]
+    fn get_sensor_input(&self, port: u32, id: u32) -> Result<f32, ErroHandle>;
+}
+
+#[doc = "grupo de sensores (acelerômetro, giroscópio ou iluminação) ao qual o *id* pertence"]
+pub fn sensor_group(id: u32) -> Option<usize> {
+    match id {
+        RETRO_SENSOR_ACCELEROMETER_X..=RETRO_SENSOR_ACCELEROMETER_Z => Some(0),
+        RETRO_SENSOR_GYROSCOPE_X..=RETRO_SENSOR_GYROSCOPE_Z => Some(1),
+        RETRO_SENSOR_ILLUMINANCE => Some(2),
+        _ => None,
+    }
+}
+
+#[doc = "grupo de sensores afetado pela ação e se ele deve ser ligado"]
+pub fn sensor_action_group(action: retro_sensor_action) -> Option<(usize, bool)> {
+    match action {
+        RETRO_SENSOR_ACCELEROMETER_ENABLE => Some((0, true)),
+        RETRO_SENSOR_ACCELEROMETER_DISABLE => Some((0, false)),
+        RETRO_SENSOR_GYROSCOPE_ENABLE => Some((1, true)),
+        RETRO_SENSOR_GYROSCOPE_DISABLE => Some((1, false)),
+        RETRO_SENSOR_ILLUMINANCE_ENABLE => Some((2, true)),
+        RETRO_SENSOR_ILLUMINANCE_DISABLE => Some((2, false)),
+        _ => None,
+    }
+}
+
+/// Sensores virtuais com valores fixos, o mesmo para todas as portas.
+///
+/// Pode ser clonado e alterado de qualquer thread, então também serve para roteirizar os
+/// movimentos em testes. Um sensor desligado pelo core sempre retorna 0.
+#[derive(Clone, Debug)]
+pub struct ConstantSensorProvider {
+    values: Arc<RwLock<[f32; SENSOR_ID_COUNT]>>,
+    enabled: Arc<[AtomicBool; 3]>,
+}
+
+impl Default for ConstantSensorProvider {
+    fn default() -> Self {
+        //aparelho parado e deitado, com a tela para cima
+        let mut values = [0.0; SENSOR_ID_COUNT];
+        values[RETRO_SENSOR_ACCELEROMETER_Z as usize] = STANDARD_GRAVITY;
+
+        Self {
+            values: Arc::new(RwLock::new(values)),
+            enabled: Arc::new([
+                AtomicBool::new(false),
+                AtomicBool::new(false),
+                AtomicBool::new(false),
+            ]),
+        }
+    }
+}
+
+impl ConstantSensorProvider {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_value(&self, id: u32, value: f32) -> Result<(), ErroHandle> {
+        if sensor_group(id).is_none() {
+            return Err(ErroHandle::new(&format!("sensor desconhecido: {id}")));
+        }
+
+        self.values.write()?[id as usize] = value;
+
+        Ok(())
+    }
+
+    pub fn is_enabled(&self, id: u32) -> bool {
+        sensor_group(id).is_some_and(|group| self.enabled[group].load(Ordering::SeqCst))
+    }
+}
+
+impl SensorProvider for ConstantSensorProvider {
+    fn set_sensor_state(
+        &self,
+        _port: u32,
+        action: retro_sensor_action,
+        _rate: u32,
+    ) -> Result<bool, ErroHandle> {
+        match sensor_action_group(action) {
+            Some((group, enabled)) => {
+                self.enabled[group].store(enabled, Ordering::SeqCst);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    fn get_sensor_input(&self, _port: u32, id: u32) -> Result<f32, ErroHandle> {
+        if !self.is_enabled(id) {
+            return Ok(0.0);
+        }
+
+        Ok(self.values.read()?[id as usize])
+    }
+}
+
+#[cfg(test)]
+mod test_sensor {
+    use super::{ConstantSensorProvider, SensorProvider, STANDARD_GRAVITY};
+    use libretro_sys::binding_libretro::{
+        retro_sensor_action::*, RETRO_SENSOR_ACCELEROMETER_X, RETRO_SENSOR_ACCELEROMETER_Z,
+        RETRO_SENSOR_GYROSCOPE_Z,
+    };
+
+    #[test]
+    fn constant_provider() {
+        let provider = ConstantSensorProvider::new();
+        let script = provider.clone();
+
+        //o core ainda nao ligou o acelerômetro
+        assert_eq!(
+            provider
+                .get_sensor_input(0, RETRO_SENSOR_ACCELEROMETER_Z)
+                .unwrap(),
+            0.0
+        );
+
+        assert!(provider
+            .set_sensor_state(0, RETRO_SENSOR_ACCELEROMETER_ENABLE, 60)
+            .unwrap());
+        assert_eq!(
+            provider
+                .get_sensor_input(0, RETRO_SENSOR_ACCELEROMETER_Z)
+                .unwrap(),
+            STANDARD_GRAVITY
+        );
+
+        script.set_value(RETRO_SENSOR_ACCELEROMETER_X, 2.5).unwrap();
+        assert_eq!(
+            provider
+                .get_sensor_input(0, RETRO_SENSOR_ACCELEROMETER_X)
+                .unwrap(),
+            2.5
+        );
+
+        //o giroscópio continua desligado
+        script.set_value(RETRO_SENSOR_GYROSCOPE_Z, 1.0).unwrap();
+        assert_eq!(
+            provider
+                .get_sensor_input(0, RETRO_SENSOR_GYROSCOPE_Z)
+                .unwrap(),
+            0.0
+        );
+
+        assert!(script.set_value(99, 1.0).is_err());
+    }
+}
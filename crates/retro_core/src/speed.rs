@@ -0,0 +1,161 @@
+use generics::erro_handle::ErroHandle;
+use libretro_sys::binding_libretro::{
+    retro_throttle_state, RETRO_THROTTLE_FAST_FORWARD, RETRO_THROTTLE_FRAME_STEPPING,
+    RETRO_THROTTLE_NONE, RETRO_THROTTLE_REWINDING, RETRO_THROTTLE_SLOW_MOTION,
+};
+use std::{
+    ops::RangeInclusive,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        RwLock,
+    },
+};
+
+#[doc = "menor multiplicador aceito. abaixo disso o jogo pareceria travado"]
+pub const MIN_SPEED_MULTIPLIER: f64 = 0.1;
+
+#[doc = "fora desse intervalo o áudio fica mudo, ja que a mudança no tom deixaria ele irreconhecível"]
+pub const AUDIBLE_SPEED_RANGE: RangeInclusive<f64> = 0.5..=2.0;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum EmulationSpeed {
+    #[default]
+    Normal,
+    #[doc = "multiplica o fps do core. valores abaixo de 1 deixam o jogo em câmera lenta. o áudio e
+    reproduzido na mesma velocidade, então o tom também muda (mais agudo ao acelerar e mais grave na
+    câmera lenta). fora de [AUDIBLE_SPEED_RANGE] o áudio fica mudo"]
+    Multiplier(f64),
+    #[doc = "roda o mais rápido possível. alguns frames nao sao exibidos e o áudio fica mudo"]
+    Unlimited,
+}
+
+impl EmulationSpeed {
+    #[doc = "None quando nao ha limite de velocidade"]
+    pub fn get_multiplier(&self) -> Option<f64> {
+        match self {
+            EmulationSpeed::Normal => Some(1.0),
+            EmulationSpeed::Multiplier(multiplier) => Some(multiplier.max(MIN_SPEED_MULTIPLIER)),
+            EmulationSpeed::Unlimited => None,
+        }
+    }
+
+    #[doc = "fps que o frontend tenta manter. None quando nao ha limite"]
+    pub fn get_target_fps(&self, core_fps: f64) -> Option<f64> {
+        self.get_multiplier()
+            .map(|multiplier| core_fps * multiplier)
+    }
+
+    #[doc = "multiplicador usado no áudio. None quando o áudio deve ficar mudo"]
+    pub fn get_audio_multiplier(&self) -> Option<f64> {
+        self.get_multiplier()
+            .filter(|multiplier| AUDIBLE_SPEED_RANGE.contains(multiplier))
+    }
+
+    pub fn is_fast_forward(&self) -> bool {
+        match self.get_multiplier() {
+            Some(multiplier) => multiplier > 1.0,
+            None => true,
+        }
+    }
+
+    pub fn is_slow_motion(&self) -> bool {
+        self.get_multiplier()
+            .is_some_and(|multiplier| multiplier < 1.0)
+    }
+}
+
+/// Velocidade em que o frontend esta chamando o retro_run. Usado em RETRO_ENVIRONMENT_GET_FASTFORWARDING,
+/// RETRO_ENVIRONMENT_GET_TARGET_REFRESH_RATE e RETRO_ENVIRONMENT_GET_THROTTLE_STATE.
+#[derive(Debug, Default)]
+pub struct SpeedState {
+    speed: RwLock<EmulationSpeed>,
+    frame_stepping: AtomicBool,
+    rewinding: AtomicBool,
+}
+
+impl SpeedState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_speed(&self, speed: EmulationSpeed) -> Result<(), ErroHandle> {
+        *self.speed.write()? = speed;
+        Ok(())
+    }
+
+    pub fn get_speed(&self) -> Result<EmulationSpeed, ErroHandle> {
+        Ok(*self.speed.read()?)
+    }
+
+    #[doc = "true enquanto o jogo estiver pausado ou avançando um frame por vez"]
+    pub fn set_frame_stepping(&self, frame_stepping: bool) {
+        self.frame_stepping.store(frame_stepping, Ordering::SeqCst);
+    }
+
+    pub fn set_rewinding(&self, rewinding: bool) {
+        self.rewinding.store(rewinding, Ordering::SeqCst);
+    }
+
+    pub fn get_throttle_state(&self, core_fps: f64) -> Result<retro_throttle_state, ErroHandle> {
+        let speed = self.get_speed()?;
+        let rate = speed.get_target_fps(core_fps).unwrap_or(0.0) as f32;
+
+        let (mode, rate) = if self.frame_stepping.load(Ordering::SeqCst) {
+            (RETRO_THROTTLE_FRAME_STEPPING, 0.0)
+        } else if self.rewinding.load(Ordering::SeqCst) {
+            (RETRO_THROTTLE_REWINDING, rate)
+        } else if speed.is_fast_forward() {
+            (RETRO_THROTTLE_FAST_FORWARD, rate)
+        } else if speed.is_slow_motion() {
+            (RETRO_THROTTLE_SLOW_MOTION, rate)
+        } else {
+            (RETRO_THROTTLE_NONE, rate)
+        };
+
+        Ok(retro_throttle_state { mode, rate })
+    }
+}
+
+#[cfg(test)]
+mod test_speed {
+    use super::{EmulationSpeed, SpeedState};
+    use libretro_sys::binding_libretro::{
+        RETRO_THROTTLE_FAST_FORWARD, RETRO_THROTTLE_FRAME_STEPPING, RETRO_THROTTLE_NONE,
+        RETRO_THROTTLE_SLOW_MOTION,
+    };
+
+    #[test]
+    fn throttle_state() {
+        let speed = SpeedState::new();
+
+        let state = speed.get_throttle_state(60.0).unwrap();
+        assert_eq!((state.mode, state.rate), (RETRO_THROTTLE_NONE, 60.0));
+
+        speed.set_speed(EmulationSpeed::Multiplier(0.5)).unwrap();
+        let state = speed.get_throttle_state(60.0).unwrap();
+        assert_eq!((state.mode, state.rate), (RETRO_THROTTLE_SLOW_MOTION, 30.0));
+
+        speed.set_speed(EmulationSpeed::Unlimited).unwrap();
+        let state = speed.get_throttle_state(60.0).unwrap();
+        assert_eq!((state.mode, state.rate), (RETRO_THROTTLE_FAST_FORWARD, 0.0));
+
+        speed.set_frame_stepping(true);
+        let state = speed.get_throttle_state(60.0).unwrap();
+        assert_eq!(
+            (state.mode, state.rate),
+            (RETRO_THROTTLE_FRAME_STEPPING, 0.0)
+        );
+    }
+
+    #[test]
+    fn audio_multiplier() {
+        assert_eq!(EmulationSpeed::Normal.get_audio_multiplier(), Some(1.0));
+        assert_eq!(
+            EmulationSpeed::Multiplier(1.5).get_audio_multiplier(),
+            Some(1.5)
+        );
+        assert_eq!(EmulationSpeed::Multiplier(0.2).get_audio_multiplier(), None);
+        assert_eq!(EmulationSpeed::Multiplier(4.0).get_audio_multiplier(), None);
+        assert_eq!(EmulationSpeed::Unlimited.get_audio_multiplier(), None);
+    }
+}
@@ -6,8 +6,9 @@ use generics::{
     erro_handle::ErroHandle,
 };
 use libretro_sys::binding_libretro::{
-    retro_controller_description, retro_controller_info, retro_subsystem_info,
-    retro_subsystem_memory_info, retro_subsystem_rom_info, retro_system_info, LibretroRaw,
+    retro_controller_description, retro_controller_info, retro_input_descriptor,
+    retro_subsystem_info, retro_subsystem_memory_info, retro_subsystem_rom_info, retro_system_info,
+    LibretroRaw,
 };
 use std::sync::{atomic::AtomicU8, Arc, RwLock};
 
@@ -32,6 +33,16 @@ pub struct ControllerDescription {
     pub id: Arc<u32>,
 }
 
+#[doc = "descreve o que um botão faz no jogo atual, como \"Pular\" ou \"Atirar\""]
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct InputDescriptor {
+    pub port: u32,
+    pub device: u32,
+    pub index: u32,
+    pub id: u32,
+    pub description: String,
+}
+
 #[derive(Default, Debug)]
 pub struct SubSystemRomInfo {
     pub desc: Arc<String>,
@@ -56,6 +67,7 @@ pub struct System {
     pub info: SysInfo,
     pub ports: RwLock<Vec<ControllerDescription>>,
     pub subsystem: RwLock<Vec<SubSystemInfo>>,
+    pub input_descriptors: RwLock<Vec<InputDescriptor>>,
     pub performance_level: AtomicU8,
 }
 
@@ -75,6 +87,7 @@ impl System {
             System {
                 ports: RwLock::new(Vec::new()),
                 subsystem: RwLock::new(Vec::new()),
+                input_descriptors: RwLock::new(Vec::new()),
                 performance_level: AtomicU8::new(0),
                 info: SysInfo {
                     library_name: Arc::new(get_str_from_ptr(sys_info.library_name)),
@@ -105,7 +118,17 @@ impl System {
             };
 
             for rom in raw_roms.iter().take(raw_sys.num_roms as usize) {
-                let memory = unsafe { *(rom.memory as *mut retro_subsystem_memory_info) };
+                //roms como a bios do Super Game Boy nao tem nenhuma memoria para ser salva
+                let memory = if rom.memory.is_null() || rom.num_memory == 0 {
+                    MemoryInfo::default()
+                } else {
+                    let memory = unsafe { *(rom.memory as *mut retro_subsystem_memory_info) };
+
+                    MemoryInfo {
+                        extension: Arc::new(get_str_from_ptr(memory.extension)),
+                        type_: Arc::new(memory.type_),
+                    }
+                };
 
                 roms.push(SubSystemRomInfo {
                     desc: Arc::new(get_str_from_ptr(rom.desc)),
@@ -114,10 +137,7 @@ impl System {
                     block_extract: Arc::new(rom.block_extract),
                     required: Arc::new(rom.required),
                     num_memory: Arc::new(rom.num_memory),
-                    memory: MemoryInfo {
-                        extension: Arc::new(get_str_from_ptr(memory.extension)),
-                        type_: Arc::new(memory.type_),
-                    },
+                    memory,
                 });
             }
 
@@ -165,11 +185,67 @@ impl System {
         }
         Ok(())
     }
+
+    #[doc = "a lista enviada pelo core termina com um item que tem *description* nulo"]
+    pub fn get_input_descriptors(
+        &self,
+        raw_descriptors: *const retro_input_descriptor,
+    ) -> Result<(), ErroHandle> {
+        let mut descriptors = self.input_descriptors.write()?;
+        descriptors.clear();
+
+        if raw_descriptors.is_null() {
+            return Ok(());
+        }
+
+        let mut index = 0;
+
+        loop {
+            let raw_descriptor = unsafe { *raw_descriptors.add(index) };
+
+            if raw_descriptor.description.is_null() {
+                break;
+            }
+
+            descriptors.push(InputDescriptor {
+                port: raw_descriptor.port,
+                device: raw_descriptor.device,
+                index: raw_descriptor.index,
+                id: raw_descriptor.id,
+                description: get_str_from_ptr(raw_descriptor.description),
+            });
+
+            index += 1;
+        }
+
+        Ok(())
+    }
+
+    #[doc = "retorna o nome que o core deu para o botão, se ele tiver informado algum"]
+    pub fn get_input_description(
+        &self,
+        port: u32,
+        device: u32,
+        index: u32,
+        id: u32,
+    ) -> Option<String> {
+        self.input_descriptors.read().ok()?.iter().find_map(|desc| {
+            if desc.port == port && desc.device == device && desc.index == index && desc.id == id {
+                Some(desc.description.clone())
+            } else {
+                None
+            }
+        })
+    }
 }
 
 #[cfg(test)]
 mod test_system {
     use crate::test_tools;
+    use libretro_sys::binding_libretro::{
+        retro_input_descriptor, RETRO_DEVICE_ID_JOYPAD_B, RETRO_DEVICE_JOYPAD,
+    };
+    use std::{ffi::CString, ptr::null};
 
     #[test]
     fn test_get_sys_info() {
@@ -190,4 +266,41 @@ mod test_system {
 
         assert_eq!(*sys.info.need_full_path, false);
     }
+
+    #[test]
+    fn test_input_descriptors() {
+        let core = test_tools::core::get_core_wrapper();
+        let jump = CString::new("Pular").unwrap();
+
+        let raw = [
+            retro_input_descriptor {
+                port: 0,
+                device: RETRO_DEVICE_JOYPAD,
+                index: 0,
+                id: RETRO_DEVICE_ID_JOYPAD_B,
+                description: jump.as_ptr(),
+            },
+            retro_input_descriptor {
+                port: 0,
+                device: 0,
+                index: 0,
+                id: 0,
+                description: null(),
+            },
+        ];
+
+        core.system.get_input_descriptors(raw.as_ptr()).unwrap();
+
+        assert_eq!(core.system.input_descriptors.read().unwrap().len(), 1);
+        assert_eq!(
+            core.system
+                .get_input_description(0, RETRO_DEVICE_JOYPAD, 0, RETRO_DEVICE_ID_JOYPAD_B),
+            Some("Pular".to_string())
+        );
+        assert_eq!(
+            core.system
+                .get_input_description(1, RETRO_DEVICE_JOYPAD, 0, RETRO_DEVICE_ID_JOYPAD_B),
+            None
+        );
+    }
 }
@@ -1,18 +1,34 @@
+use crate::camera::TestPatternCamera;
 use crate::core_env::{RetroControllerEnvCallbacks, RetroEnvCallbacks};
 use crate::graphic_api::GraphicApi;
+use crate::location::FixedLocationProvider;
+use crate::logger::StdoutLogger;
+use crate::message::RetroMessage;
+use crate::power::MockPowerProvider;
 use crate::retro_core::RetroCore;
+use crate::sensor::ConstantSensorProvider;
 use crate::test_tools::constants::CORE_TEST_RELATIVE_PATH;
 use crate::test_tools::paths::get_paths;
-use crate::{RetroAudioEnvCallbacks, RetroCoreIns, RetroVideoEnvCallbacks};
+use crate::vfs::MemoryVfs;
+use crate::{
+    RetroAudioEnvCallbacks, RetroCoreIns, RetroMessageEnvCallbacks, RetroVideoEnvCallbacks,
+};
 use generics::erro_handle::ErroHandle;
-use libretro_sys::binding_libretro::retro_rumble_effect;
-use std::ptr;
+use libretro_sys::binding_libretro::{retro_language, retro_rumble_effect};
+use std::{ptr, sync::Arc};
 
 pub fn get_callbacks() -> RetroEnvCallbacks {
     RetroEnvCallbacks {
         video: Box::new(Video {}),
         audio: Box::new(Audio {}),
         controller: Box::new(Controller {}),
+        message: Box::new(Message {}),
+        logger: Box::new(StdoutLogger),
+        vfs: Some(Arc::new(MemoryVfs::new())),
+        sensor: Some(Box::new(ConstantSensorProvider::new())),
+        location: Some(Box::new(FixedLocationProvider::default())),
+        camera: Some(Box::new(TestPatternCamera::new())),
+        power: Some(Box::new(MockPowerProvider::default())),
     }
 }
 
@@ -65,6 +81,15 @@ impl RetroAudioEnvCallbacks for Audio {
     }
 }
 
+struct Message;
+
+impl RetroMessageEnvCallbacks for Message {
+    fn message_callback(&self, message: RetroMessage) -> Result<(), ErroHandle> {
+        println!("message_callback -> {:?}", message);
+        Ok(())
+    }
+}
+
 struct Controller;
 
 impl RetroControllerEnvCallbacks for Controller {
@@ -96,6 +121,11 @@ impl RetroControllerEnvCallbacks for Controller {
 
         Ok(true)
     }
+
+    fn set_led_state(&self, led: i32, state: i32) -> Result<(), ErroHandle> {
+        println!("set_led_state -> led:{led} state:{state}");
+        Ok(())
+    }
 }
 
 pub fn get_core_wrapper() -> RetroCoreIns {
@@ -104,6 +134,7 @@ pub fn get_core_wrapper() -> RetroCoreIns {
         get_paths().unwrap(),
         get_callbacks(),
         GraphicApi::default(),
+        retro_language::RETRO_LANGUAGE_ENGLISH,
     )
     .unwrap()
 }
@@ -1,8 +1,15 @@
 use super::ffi_tools::make_c_string;
-use crate::system::SysInfo;
-use generics::constants::SAVE_EXTENSION_FILE;
+use crate::{
+    disk_control::DiskControl,
+    system::{SubSystemInfo, SysInfo},
+};
+use generics::constants::{
+    M3U_EXTENSION_FILE, RTC_EXTENSION_FILE, SAVE_EXTENSION_FILE, SAVE_RAM_EXTENSION_FILE,
+};
 use generics::erro_handle::ErroHandle;
-use libretro_sys::binding_libretro::{retro_game_info, LibretroRaw};
+use libretro_sys::binding_libretro::{
+    retro_game_info, LibretroRaw, RETRO_MEMORY_RTC, RETRO_MEMORY_SAVE_RAM,
+};
 use std::fs;
 use std::io::Write;
 use std::sync::Arc;
@@ -12,9 +19,15 @@ use std::{
     io::Read,
     os::raw::c_void,
     path::{Path, PathBuf},
-    ptr::null,
+    ptr::{null, slice_from_raw_parts, slice_from_raw_parts_mut},
 };
 
+#[doc = "memorias que o jogo usa para guardar o progresso do jogador por conta própria"]
+const BATTERY_MEMORIES: [(u32, &str); 2] = [
+    (RETRO_MEMORY_SAVE_RAM, SAVE_RAM_EXTENSION_FILE),
+    (RETRO_MEMORY_RTC, RTC_EXTENSION_FILE),
+];
+
 fn get_full_path(path: &str) -> Result<PathBuf, ErroHandle> {
     match PathBuf::from(path).canonicalize() {
         Ok(full_path) => Ok(full_path),
@@ -47,11 +60,10 @@ fn valid_rom_extension(valid_extensions: &String, path: &Path) -> Result<(), Err
     Ok(())
 }
 
-fn get_save_path(
+fn get_rom_save_dir(
     save_dir: &String,
     sys_info: &SysInfo,
     rom_name: &String,
-    slot: usize,
 ) -> Result<PathBuf, ErroHandle> {
     let mut path = PathBuf::from(save_dir);
 
@@ -62,53 +74,293 @@ fn get_save_path(
         fs::create_dir_all(&path)?;
     }
 
+    Ok(path)
+}
+
+fn get_save_path(
+    save_dir: &String,
+    sys_info: &SysInfo,
+    rom_name: &String,
+    slot: usize,
+) -> Result<PathBuf, ErroHandle> {
+    let mut path = get_rom_save_dir(save_dir, sys_info, rom_name)?;
+
     let file_name = format!("{}.{}", slot, SAVE_EXTENSION_FILE);
     path.push(file_name);
 
     Ok(path)
 }
 
+fn get_battery_save_path(
+    save_dir: &String,
+    sys_info: &SysInfo,
+    rom_name: &String,
+    extension: &str,
+) -> Result<PathBuf, ErroHandle> {
+    let mut path = get_rom_save_dir(save_dir, sys_info, rom_name)?;
+
+    let file_name = format!("{}.{}", rom_name, extension);
+    path.push(file_name);
+
+    Ok(path)
+}
+
+//alguns cores mudam o tamanho da SRAM entre versões, então apenas a parte que cabe
+//na memoria atual e copiada
+fn read_battery_file(path: &Path, memory: &mut [u8]) -> Result<(), ErroHandle> {
+    let buff = fs::read(path)?;
+
+    let len = buff.len().min(memory.len());
+    memory[..len].copy_from_slice(&buff[..len]);
+
+    Ok(())
+}
+
+#[doc = "retorna false quando o arquivo ja tinha o mesmo conteúdo e nada foi escrito"]
+fn write_battery_file(path: &Path, memory: &[u8]) -> Result<bool, ErroHandle> {
+    //o save automático roda a cada poucos segundos, então o disco so e usado se a memoria mudou
+    if fs::read(path).is_ok_and(|current| current == memory) {
+        return Ok(false);
+    }
+
+    //se o processo for encerrado no meio da escrita apenas o arquivo temporário fica incompleto,
+    //ja que o rename substitui o save antigo de uma vez
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push(".tmp");
+    let temp_path = PathBuf::from(temp_path);
+
+    let mut file = File::create(&temp_path)?;
+    file.write_all(memory)?;
+    file.sync_all()?;
+    drop(file);
+
+    fs::rename(&temp_path, path)?;
+
+    Ok(true)
+}
+
+fn is_m3u(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case(M3U_EXTENSION_FILE))
+}
+
+fn get_extension(path: &Path) -> String {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn support_extension(valid_extensions: &str, extension: &str) -> bool {
+    valid_extensions
+        .split('|')
+        .any(|ext| ext.eq_ignore_ascii_case(extension))
+}
+
+#[doc = "lê os discos de uma playlist m3u. Os caminhos relativos partem da pasta da playlist"]
+fn read_m3u(path: &Path) -> Result<Vec<PathBuf>, ErroHandle> {
+    let content = fs::read_to_string(path)?;
+    let base_dir = path.parent().unwrap_or(Path::new(""));
+
+    Ok(parse_m3u(&content, base_dir))
+}
+
+fn parse_m3u(content: &str, base_dir: &Path) -> Vec<PathBuf> {
+    content
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        //o RetroArch permite dar um nome ao disco usando "disco.cue|nome"
+        .map(|line| line.split('|').next().unwrap_or(line).trim())
+        .map(|line| base_dir.join(line))
+        .collect()
+}
+
+/// Mantém vivos os dados apontados por [retro_game_info] enquanto ele estiver sendo usado pelo core.
+pub struct GameInfoData {
+    path: Option<CString>,
+    meta: CString,
+    data: Vec<u8>,
+}
+
+impl GameInfoData {
+    #[doc = "se *need_full_path* for false o arquivo inteiro e carregado na memoria"]
+    pub fn new(path: &Path, need_full_path: bool) -> Result<Self, ErroHandle> {
+        let mut data = Vec::new();
+
+        if !need_full_path {
+            let mut file = File::open(path)?;
+
+            data = Vec::with_capacity(file.metadata()?.len() as usize);
+            file.read_to_end(&mut data)?;
+        }
+
+        Ok(Self {
+            path: Some(make_c_string(path.to_str().ok_or(ErroHandle::new(
+                "nao foi possível transforma o PathBuf da rom para uma string",
+            ))?)?),
+            meta: CString::new("")?,
+            data,
+        })
+    }
+
+    #[doc = "usado quando nenhum conteúdo e enviado ao core, como em um conteúdo opcional de um subsistema"]
+    pub fn empty() -> Result<Self, ErroHandle> {
+        Ok(Self {
+            path: None,
+            meta: CString::new("")?,
+            data: Vec::new(),
+        })
+    }
+
+    pub fn get_path(&self) -> String {
+        match &self.path {
+            Some(path) => path.to_string_lossy().to_string(),
+            None => String::new(),
+        }
+    }
+
+    pub fn as_raw(&self) -> retro_game_info {
+        retro_game_info {
+            data: if self.data.is_empty() {
+                null()
+            } else {
+                self.data.as_ptr() as *const c_void
+            },
+            meta: self.meta.as_ptr(),
+            path: match &self.path {
+                Some(path) => path.as_ptr(),
+                None => null(),
+            },
+            size: self.data.len(),
+        }
+    }
+}
+
 pub struct RomTools;
 
 impl RomTools {
     pub fn try_load_game(
         libretro_raw: &Arc<LibretroRaw>,
         sys_info: &SysInfo,
+        disk_control: &DiskControl,
         path: &str,
     ) -> Result<bool, ErroHandle> {
         let f_path = get_full_path(path)?;
 
+        //se o core nao entende uma playlist m3u, o primeiro disco e carregado e os outros sao
+        //adicionados usando a interface de troca de discos
+        if is_m3u(&f_path) && !support_extension(&sys_info.valid_extensions, M3U_EXTENSION_FILE) {
+            let discs = read_m3u(&f_path)?;
+            let (first_disc, other_discs) = discs
+                .split_first()
+                .ok_or(ErroHandle::new("A playlist m3u nao tem nenhum disco"))?;
+
+            valid_rom_extension(&sys_info.valid_extensions, first_disc)?;
+
+            let game_info = GameInfoData::new(first_disc, *sys_info.need_full_path)?;
+            let state = unsafe { libretro_raw.retro_load_game(&game_info.as_raw()) };
+
+            if state && !other_discs.is_empty() {
+                let mut images = Vec::with_capacity(other_discs.len());
+
+                for disc in other_discs {
+                    images.push(GameInfoData::new(disc, true)?);
+                }
+
+                //o primeiro disco ja foi carregado, então o jogo ainda pode ser iniciado
+                if let Err(e) = disk_control.append_images(&images) {
+                    println!("{:?}", e);
+                }
+            }
+
+            return Ok(state);
+        }
+
         valid_rom_extension(&sys_info.valid_extensions, &f_path)?;
 
-        let mut buf = Vec::new();
-        let meta = CString::new("")?;
-        let path = make_c_string(f_path.to_str().ok_or(ErroHandle::new(
-            "nao foi possível transforma o PathBuf da rom para uma string",
-        ))?)?;
-        let mut size = 0;
+        let game_info = GameInfoData::new(&f_path, *sys_info.need_full_path)?;
+        let state = unsafe { libretro_raw.retro_load_game(&game_info.as_raw()) };
 
-        if !*sys_info.need_full_path {
-            let mut file = File::open(&f_path)?;
+        Ok(state)
+    }
 
-            size = file.metadata()?.len() as usize;
+    #[doc = "inicia o core sem nenhum conteúdo. So funciona em cores que enviaram RETRO_ENVIRONMENT_SET_SUPPORT_NO_GAME"]
+    pub fn try_load_no_game(libretro_raw: &Arc<LibretroRaw>) -> bool {
+        unsafe { libretro_raw.retro_load_game(null()) }
+    }
 
-            buf = Vec::with_capacity(size);
+    #[doc = "valida as roms de acordo com as regras do subsistema e prepara os dados para o core"]
+    pub fn get_special_games_info(
+        subsystem: &SubSystemInfo,
+        roms: &[String],
+    ) -> Result<Vec<GameInfoData>, ErroHandle> {
+        let roms_info = subsystem.roms.read()?;
 
-            file.read_to_end(&mut buf)?;
+        if roms.len() > roms_info.len() {
+            return Err(ErroHandle {
+                message: format!(
+                    "O subsistema {} aceita no máximo {} roms, mas {} foram enviadas",
+                    subsystem.ident,
+                    roms_info.len(),
+                    roms.len()
+                ),
+            });
         }
 
-        let game_info = retro_game_info {
-            data: if buf.is_empty() {
-                null()
-            } else {
-                buf.as_ptr() as *const c_void
-            },
-            meta: meta.as_ptr(),
-            path: path.as_ptr(),
-            size,
-        };
+        let mut games_info = Vec::with_capacity(roms_info.len());
+
+        for (index, rom_info) in roms_info.iter().enumerate() {
+            let rom = roms.get(index).filter(|rom| !rom.is_empty());
+
+            match rom {
+                Some(rom) => {
+                    let f_path = get_full_path(rom)?;
+
+                    if !support_extension(&rom_info.valid_extensions, &get_extension(&f_path)) {
+                        return Err(ErroHandle {
+                            message: format!(
+                                "Extensão invalida para \"{}\": valores esperados -> {}; rom -> {}",
+                                rom_info.desc,
+                                rom_info.valid_extensions,
+                                f_path.display()
+                            ),
+                        });
+                    }
+
+                    games_info.push(GameInfoData::new(&f_path, *rom_info.need_full_path)?);
+                }
+                None if *rom_info.required => {
+                    return Err(ErroHandle {
+                        message: format!(
+                            "O conteúdo \"{}\" e obrigatório no subsistema {}",
+                            rom_info.desc, subsystem.ident
+                        ),
+                    });
+                }
+                None => games_info.push(GameInfoData::empty()?),
+            }
+        }
 
-        let state = unsafe { libretro_raw.retro_load_game(&game_info) };
+        Ok(games_info)
+    }
+
+    pub fn try_load_game_special(
+        libretro_raw: &Arc<LibretroRaw>,
+        subsystem_id: u32,
+        games_info: &[GameInfoData],
+    ) -> Result<bool, ErroHandle> {
+        let raw_games_info: Vec<retro_game_info> =
+            games_info.iter().map(|info| info.as_raw()).collect();
+
+        let state = unsafe {
+            libretro_raw.retro_load_game_special(
+                subsystem_id,
+                raw_games_info.as_ptr(),
+                raw_games_info.len(),
+            )
+        };
 
         Ok(state)
     }
@@ -131,13 +383,8 @@ impl RomTools {
         Ok(name)
     }
 
-    pub fn create_save_state(
-        libretro_raw: &Arc<LibretroRaw>,
-        save_dir: &String,
-        sys_info: &SysInfo,
-        rom_name: &String,
-        slot: usize,
-    ) -> Result<PathBuf, ErroHandle> {
+    #[doc = "retorna uma copia do estado atual do core. usado pelos saves states e pelo rewind"]
+    pub fn serialize(libretro_raw: &Arc<LibretroRaw>) -> Result<Vec<u8>, ErroHandle> {
         let size = unsafe { libretro_raw.retro_serialize_size() };
         let mut data = vec![0u8; size];
 
@@ -149,6 +396,39 @@ impl RomTools {
             });
         }
 
+        Ok(data)
+    }
+
+    pub fn unserialize(libretro_raw: &Arc<LibretroRaw>, data: &[u8]) -> Result<(), ErroHandle> {
+        let core_expect_size = unsafe { libretro_raw.retro_serialize_size() };
+
+        if data.len() != core_expect_size {
+            return Err(ErroHandle {
+                message: "o state escolhido nao e correspondente ao core".to_string(),
+            });
+        }
+
+        let suss =
+            unsafe { libretro_raw.retro_unserialize(data.as_ptr() as *const c_void, data.len()) };
+
+        if !suss {
+            return Err(ErroHandle {
+                message: "o core nao pode carregar o state escolhido".to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
+    pub fn create_save_state(
+        libretro_raw: &Arc<LibretroRaw>,
+        save_dir: &String,
+        sys_info: &SysInfo,
+        rom_name: &String,
+        slot: usize,
+    ) -> Result<PathBuf, ErroHandle> {
+        let data = Self::serialize(libretro_raw)?;
+
         let save_path = get_save_path(save_dir, sys_info, rom_name, slot)?;
 
         match File::create(&save_path) {
@@ -181,26 +461,199 @@ impl RomTools {
         let mut buff = Vec::new();
         save_file.read_to_end(&mut buff)?;
 
-        let core_expect_size = unsafe { libretro_raw.retro_serialize_size() };
-        let buffer_size = buff.len();
+        Self::unserialize(libretro_raw, &buff)?;
 
-        if buffer_size != core_expect_size {
-            return Err(ErroHandle {
-                message: "o state escolhido nao e correspondente ao core".to_string(),
-            });
+        Ok(())
+    }
+
+    #[doc = "copia o conteúdo dos arquivos .srm e .rtc da rom para a memoria do core.
+    deve ser chamado logo depois da rom ser carregada"]
+    pub fn load_battery_saves(
+        libretro_raw: &Arc<LibretroRaw>,
+        save_dir: &String,
+        sys_info: &SysInfo,
+        rom_name: &String,
+    ) -> Result<(), ErroHandle> {
+        for (memory_id, extension) in BATTERY_MEMORIES {
+            let size = unsafe { libretro_raw.retro_get_memory_size(memory_id) };
+            let data = unsafe { libretro_raw.retro_get_memory_data(memory_id) };
+
+            if size == 0 || data.is_null() {
+                continue;
+            }
+
+            let save_path = get_battery_save_path(save_dir, sys_info, rom_name, extension)?;
+
+            if !save_path.exists() {
+                continue;
+            }
+
+            let memory = unsafe { &mut *slice_from_raw_parts_mut(data as *mut u8, size) };
+            read_battery_file(&save_path, memory)?;
         }
 
-        unsafe {
-            let suss =
-                libretro_raw.retro_unserialize(buff.as_mut_ptr() as *mut c_void, buffer_size);
+        Ok(())
+    }
+
+    #[doc = "salva a memoria da bateria (SRAM e RTC) do core nos arquivos .srm e .rtc da rom"]
+    pub fn save_battery_saves(
+        libretro_raw: &Arc<LibretroRaw>,
+        save_dir: &String,
+        sys_info: &SysInfo,
+        rom_name: &String,
+    ) -> Result<(), ErroHandle> {
+        for (memory_id, extension) in BATTERY_MEMORIES {
+            let size = unsafe { libretro_raw.retro_get_memory_size(memory_id) };
+            let data = unsafe { libretro_raw.retro_get_memory_data(memory_id) };
 
-            if !suss {
-                return Err(ErroHandle {
-                    message: "o core nao pode carregar o state escolhido".to_string(),
-                });
+            if size == 0 || data.is_null() {
+                continue;
             }
+
+            let save_path = get_battery_save_path(save_dir, sys_info, rom_name, extension)?;
+            let memory = unsafe { &*slice_from_raw_parts(data as *const u8, size) };
+
+            write_battery_file(&save_path, memory)?;
         }
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod test_game_tools {
+    use super::{parse_m3u, read_battery_file, write_battery_file, RomTools};
+    use crate::{
+        system::{SubSystemInfo, SubSystemRomInfo},
+        test_tools::paths::get_paths,
+    };
+    use std::{
+        fs,
+        path::{Path, PathBuf},
+        sync::{Arc, RwLock},
+    };
+
+    //um subsistema como o Super Game Boy: a rom principal e obrigatória e a segunda e opcional
+    fn create_subsystem() -> SubSystemInfo {
+        SubSystemInfo {
+            ident: Arc::new("sgb".to_string()),
+            roms: RwLock::new(vec![
+                SubSystemRomInfo {
+                    desc: Arc::new("Base".to_string()),
+                    valid_extensions: Arc::new("gb|gbc".to_string()),
+                    need_full_path: Arc::new(true),
+                    required: Arc::new(true),
+                    ..Default::default()
+                },
+                SubSystemRomInfo {
+                    desc: Arc::new("Link".to_string()),
+                    valid_extensions: Arc::new("gb".to_string()),
+                    need_full_path: Arc::new(false),
+                    required: Arc::new(false),
+                    ..Default::default()
+                },
+            ]),
+            ..Default::default()
+        }
+    }
+
+    fn create_rom(name: &str) -> String {
+        let paths = get_paths().unwrap();
+        let path = PathBuf::from(paths.temps.as_str()).join(name);
+        fs::write(&path, [1, 2, 3, 4]).unwrap();
+
+        path.to_string_lossy().to_string()
+    }
+
+    #[test]
+    fn special_games_info() {
+        let subsystem = create_subsystem();
+        let base = create_rom("special_base.GB");
+        let link = create_rom("special_link.gb");
+
+        //a extensão e comparada sem diferenciar maiúsculas
+        let games = RomTools::get_special_games_info(&subsystem, &[base.clone()]).unwrap();
+
+        assert_eq!(games.len(), 2);
+        assert_eq!(
+            PathBuf::from(games[0].get_path()),
+            PathBuf::from(&base).canonicalize().unwrap()
+        );
+        assert_eq!(games[1].get_path(), "");
+
+        //sem need_full_path o conteúdo da rom e enviado ao core
+        let games = RomTools::get_special_games_info(&subsystem, &[base, link]).unwrap();
+        assert_eq!(games[1].as_raw().size, 4);
+    }
+
+    #[test]
+    fn special_games_missing_required() {
+        let subsystem = create_subsystem();
+        let link = create_rom("special_missing.gb");
+
+        assert!(RomTools::get_special_games_info(&subsystem, &[]).is_err());
+        assert!(RomTools::get_special_games_info(&subsystem, &[String::new(), link]).is_err());
+    }
+
+    #[test]
+    fn special_games_wrong_extension() {
+        let subsystem = create_subsystem();
+        let base = create_rom("special_wrong.gb");
+        let wrong = create_rom("special_wrong.gbc");
+
+        //a segunda rom so aceita .gb
+        assert!(RomTools::get_special_games_info(&subsystem, &[base.clone(), wrong]).is_err());
+
+        //roms a mais que o subsistema aceita
+        assert!(
+            RomTools::get_special_games_info(&subsystem, &[base.clone(), base.clone(), base])
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn battery_file_round_trip() {
+        let paths = get_paths().unwrap();
+        let path = PathBuf::from(paths.save.as_str()).join("battery_round_trip.srm");
+        let _ = fs::remove_file(&path);
+
+        let sram: Vec<u8> = (0..64).collect();
+        assert!(write_battery_file(&path, &sram).unwrap());
+
+        //a memoria nao mudou, então o arquivo nao precisa ser escrito novamente
+        assert!(!write_battery_file(&path, &sram).unwrap());
+        assert!(!PathBuf::from(format!("{}.tmp", path.display())).exists());
+
+        let mut memory = vec![0; 64];
+        read_battery_file(&path, &mut memory).unwrap();
+        assert_eq!(memory, sram);
+
+        //uma SRAM menor que o arquivo recebe apenas o inicio dele
+        let mut small = vec![0; 8];
+        read_battery_file(&path, &mut small).unwrap();
+        assert_eq!(small, sram[..8]);
+
+        let changed = vec![0xff; 64];
+        assert!(write_battery_file(&path, &changed).unwrap());
+        assert_eq!(fs::read(&path).unwrap(), changed);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn m3u_playlist() {
+        let content =
+            "#EXTM3U\n\nFF7 (Disc 1).cue\nFF7 (Disc 2).cue|Disco 2\n\n/roms/FF7 (Disc 3).cue\n";
+
+        let discs = parse_m3u(content, Path::new("/games/ff7"));
+
+        assert_eq!(
+            discs,
+            vec![
+                PathBuf::from("/games/ff7/FF7 (Disc 1).cue"),
+                PathBuf::from("/games/ff7/FF7 (Disc 2).cue"),
+                PathBuf::from("/roms/FF7 (Disc 3).cue"),
+            ]
+        );
+    }
+}
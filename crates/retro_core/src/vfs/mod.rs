@@ -0,0 +1,80 @@
+mod vfs_memory;
+mod vfs_observed;
+mod vfs_std;
+mod vfs_zip;
+
+pub use vfs_memory::MemoryVfs;
+pub use vfs_observed::{ObservedVfs, VfsAccess, VfsObserver, VfsOperation};
+pub use vfs_std::StdVfs;
+pub use vfs_zip::ZipVfs;
+
+use std::{
+    io::{self, Read, Seek, Write},
+    path::{Component, Path, PathBuf},
+};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct VfsOpenMode {
+    pub read: bool,
+    pub write: bool,
+    #[doc = "quando false um arquivo aberto para escrita e criado novamente, perdendo o conteúdo atual"]
+    pub update_existing: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VfsStat {
+    pub is_dir: bool,
+    pub size: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VfsDirEntry {
+    pub name: String,
+    pub is_dir: bool,
+}
+
+pub trait RetroVfsFile: Read + Write + Seek {
+    fn size(&mut self) -> io::Result<u64>;
+
+    fn truncate(&mut self, len: u64) -> io::Result<()>;
+}
+
+/// Todos os arquivos que o core abrir usando a interface VFS da libretro passam por aqui.
+///
+/// Permite controlar onde o core pode ler e escrever ([StdVfs]), rodar testes sem tocar no disco
+/// ([MemoryVfs]) ou ler uma rom direto de dentro de um arquivo zip ([ZipVfs]). Use [ObservedVfs]
+/// para saber quais arquivos o core esta acessando.
+///
+/// O core pode chamar a interface de qualquer thread, por isso o backend precisa ser [Send] e [Sync].
+pub trait RetroVfs: Send + Sync {
+    fn open(&self, path: &Path, mode: VfsOpenMode) -> io::Result<Box<dyn RetroVfsFile>>;
+
+    fn remove(&self, path: &Path) -> io::Result<()>;
+
+    fn rename(&self, old_path: &Path, new_path: &Path) -> io::Result<()>;
+
+    #[doc = "retorna None se o caminho nao existir"]
+    fn stat(&self, path: &Path) -> Option<VfsStat>;
+
+    #[doc = "deve retornar um erro do tipo AlreadyExists se a pasta ja existir"]
+    fn mkdir(&self, path: &Path) -> io::Result<()>;
+
+    fn read_dir(&self, path: &Path, include_hidden: bool) -> io::Result<Vec<VfsDirEntry>>;
+}
+
+#[doc = "resolve os '.' e '..' do caminho sem acessar o disco, ja que o arquivo pode ainda nao existir"]
+pub fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+
+    normalized
+}
@@ -0,0 +1,359 @@
+use super::{normalize_path, RetroVfs, RetroVfsFile, VfsDirEntry, VfsOpenMode, VfsStat};
+use std::{
+    collections::{HashMap, HashSet},
+    io::{self, ErrorKind, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, MutexGuard},
+};
+
+type FileData = Arc<Mutex<Vec<u8>>>;
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Arquivo aberto pelo [MemoryVfs]. Tambem usado pelo [super::ZipVfs] para entregar o conteúdo
+/// ja extraído de um arquivo zip.
+pub struct MemoryFile {
+    data: FileData,
+    pos: u64,
+    read: bool,
+    write: bool,
+}
+
+impl MemoryFile {
+    pub fn read_only(data: Vec<u8>) -> Self {
+        Self {
+            data: Arc::new(Mutex::new(data)),
+            pos: 0,
+            read: true,
+            write: false,
+        }
+    }
+}
+
+impl Read for MemoryFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if !self.read {
+            return Err(io::Error::new(
+                ErrorKind::PermissionDenied,
+                "arquivo aberto apenas para escrita",
+            ));
+        }
+
+        let data = lock(&self.data);
+        let start = (self.pos as usize).min(data.len());
+        let len = buf.len().min(data.len() - start);
+
+        buf[..len].copy_from_slice(&data[start..start + len]);
+        self.pos += len as u64;
+
+        Ok(len)
+    }
+}
+
+impl Write for MemoryFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if !self.write {
+            return Err(io::Error::new(
+                ErrorKind::PermissionDenied,
+                "arquivo aberto apenas para leitura",
+            ));
+        }
+
+        let mut data = lock(&self.data);
+        let start = self.pos as usize;
+        let end = start + buf.len();
+
+        if data.len() < end {
+            data.resize(end, 0);
+        }
+
+        data[start..end].copy_from_slice(buf);
+        self.pos = end as u64;
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Seek for MemoryFile {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let new_pos = match pos {
+            SeekFrom::Start(offset) => offset as i64,
+            SeekFrom::Current(offset) => self.pos as i64 + offset,
+            SeekFrom::End(offset) => lock(&self.data).len() as i64 + offset,
+        };
+
+        if new_pos < 0 {
+            return Err(io::Error::new(
+                ErrorKind::InvalidInput,
+                "posição antes do inicio do arquivo",
+            ));
+        }
+
+        self.pos = new_pos as u64;
+
+        Ok(self.pos)
+    }
+}
+
+impl RetroVfsFile for MemoryFile {
+    fn size(&mut self) -> io::Result<u64> {
+        Ok(lock(&self.data).len() as u64)
+    }
+
+    fn truncate(&mut self, len: u64) -> io::Result<()> {
+        if !self.write {
+            return Err(io::Error::new(
+                ErrorKind::PermissionDenied,
+                "arquivo aberto apenas para leitura",
+            ));
+        }
+
+        lock(&self.data).resize(len as usize, 0);
+
+        Ok(())
+    }
+}
+
+/// Sistema de arquivos que existe apenas na memoria. Ideal para testes.
+///
+/// Os clones compartilham os mesmos arquivos, então e possível inspecionar o que o core escreveu
+/// mesmo depois de entregar o [MemoryVfs] para o [crate::RetroCore].
+#[derive(Clone, Debug, Default)]
+pub struct MemoryVfs {
+    files: Arc<Mutex<HashMap<PathBuf, FileData>>>,
+    dirs: Arc<Mutex<HashSet<PathBuf>>>,
+}
+
+impl MemoryVfs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn insert_file(&self, path: impl AsRef<Path>, data: Vec<u8>) {
+        lock(&self.files).insert(normalize_path(path.as_ref()), Arc::new(Mutex::new(data)));
+    }
+
+    pub fn get_file(&self, path: impl AsRef<Path>) -> Option<Vec<u8>> {
+        lock(&self.files)
+            .get(&normalize_path(path.as_ref()))
+            .map(|data| lock(data).clone())
+    }
+
+    fn is_dir(&self, path: &Path) -> bool {
+        lock(&self.dirs).contains(path)
+            || lock(&self.files)
+                .keys()
+                .any(|file| file.starts_with(path) && file != path)
+    }
+}
+
+impl RetroVfs for MemoryVfs {
+    fn open(&self, path: &Path, mode: VfsOpenMode) -> io::Result<Box<dyn RetroVfsFile>> {
+        let path = normalize_path(path);
+        let mut files = lock(&self.files);
+
+        let data = match files.get(&path) {
+            Some(data) => {
+                if mode.write && !mode.update_existing {
+                    lock(data).clear();
+                }
+
+                data.clone()
+            }
+            None if mode.write && !mode.update_existing => {
+                let data: FileData = Arc::new(Mutex::new(Vec::new()));
+                files.insert(path, data.clone());
+                data
+            }
+            None => return Err(ErrorKind::NotFound.into()),
+        };
+
+        Ok(Box::new(MemoryFile {
+            data,
+            pos: 0,
+            read: mode.read,
+            write: mode.write,
+        }))
+    }
+
+    fn remove(&self, path: &Path) -> io::Result<()> {
+        let path = normalize_path(path);
+
+        if lock(&self.files).remove(&path).is_some() || lock(&self.dirs).remove(&path) {
+            Ok(())
+        } else {
+            Err(ErrorKind::NotFound.into())
+        }
+    }
+
+    fn rename(&self, old_path: &Path, new_path: &Path) -> io::Result<()> {
+        let mut files = lock(&self.files);
+
+        match files.remove(&normalize_path(old_path)) {
+            Some(data) => {
+                files.insert(normalize_path(new_path), data);
+                Ok(())
+            }
+            None => Err(ErrorKind::NotFound.into()),
+        }
+    }
+
+    fn stat(&self, path: &Path) -> Option<VfsStat> {
+        let path = normalize_path(path);
+
+        if let Some(data) = lock(&self.files).get(&path) {
+            return Some(VfsStat {
+                is_dir: false,
+                size: lock(data).len() as u64,
+            });
+        }
+
+        if self.is_dir(&path) {
+            return Some(VfsStat {
+                is_dir: true,
+                size: 0,
+            });
+        }
+
+        None
+    }
+
+    fn mkdir(&self, path: &Path) -> io::Result<()> {
+        let path = normalize_path(path);
+
+        if self.is_dir(&path) {
+            return Err(ErrorKind::AlreadyExists.into());
+        }
+
+        lock(&self.dirs).insert(path);
+
+        Ok(())
+    }
+
+    fn read_dir(&self, path: &Path, include_hidden: bool) -> io::Result<Vec<VfsDirEntry>> {
+        let path = normalize_path(path);
+
+        if !self.is_dir(&path) {
+            return Err(ErrorKind::NotFound.into());
+        }
+
+        let mut entries: Vec<VfsDirEntry> = Vec::new();
+
+        let files: Vec<PathBuf> = lock(&self.files).keys().cloned().collect();
+        let dirs: Vec<PathBuf> = lock(&self.dirs).iter().cloned().collect();
+
+        for (item, item_is_dir) in files
+            .iter()
+            .map(|f| (f, false))
+            .chain(dirs.iter().map(|d| (d, true)))
+        {
+            let relative = match item.strip_prefix(&path) {
+                Ok(relative) if relative.components().count() > 0 => relative,
+                _ => continue,
+            };
+
+            let mut components = relative.components();
+            let name = components
+                .next()
+                .map(|c| c.as_os_str().to_string_lossy().to_string())
+                .unwrap_or_default();
+
+            //arquivos em sub pastas fazem a sub pasta aparecer nessa lista
+            let is_dir = item_is_dir || components.next().is_some();
+
+            if !include_hidden && name.starts_with('.') {
+                continue;
+            }
+
+            if !entries.iter().any(|entry| entry.name == name) {
+                entries.push(VfsDirEntry { name, is_dir });
+            }
+        }
+
+        entries.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod test_vfs_memory {
+    use super::MemoryVfs;
+    use crate::vfs::{RetroVfs, VfsDirEntry, VfsOpenMode};
+    use std::{
+        io::{ErrorKind, Read, Seek, SeekFrom, Write},
+        path::Path,
+    };
+
+    const READ: VfsOpenMode = VfsOpenMode {
+        read: true,
+        write: false,
+        update_existing: false,
+    };
+
+    const WRITE: VfsOpenMode = VfsOpenMode {
+        read: false,
+        write: true,
+        update_existing: false,
+    };
+
+    #[test]
+    fn write_and_read() {
+        let vfs = MemoryVfs::new();
+
+        let mut file = vfs.open(Path::new("save/game.srm"), WRITE).unwrap();
+        file.write_all(b"tinic").unwrap();
+        file.seek(SeekFrom::Start(1)).unwrap();
+        file.write_all(b"I").unwrap();
+        drop(file);
+
+        assert_eq!(vfs.get_file("save/./game.srm").unwrap(), b"tInic");
+
+        let mut file = vfs.open(Path::new("save/game.srm"), READ).unwrap();
+        let mut content = String::new();
+        file.seek(SeekFrom::End(-3)).unwrap();
+        file.read_to_string(&mut content).unwrap();
+
+        assert_eq!(content, "nic");
+        assert_eq!(file.size().unwrap(), 5);
+        assert!(file.write(b"x").is_err());
+    }
+
+    #[test]
+    fn directories() {
+        let vfs = MemoryVfs::new();
+        vfs.insert_file("system/bios.bin", vec![0; 4]);
+        vfs.insert_file("system/.hidden", vec![]);
+        vfs.insert_file("system/psx/scph.bin", vec![]);
+
+        let entries = vfs.read_dir(Path::new("system"), false).unwrap();
+
+        assert_eq!(
+            entries,
+            vec![
+                VfsDirEntry {
+                    name: "bios.bin".to_string(),
+                    is_dir: false
+                },
+                VfsDirEntry {
+                    name: "psx".to_string(),
+                    is_dir: true
+                },
+            ]
+        );
+
+        assert_eq!(
+            vfs.mkdir(Path::new("system/psx")).unwrap_err().kind(),
+            ErrorKind::AlreadyExists
+        );
+        assert!(vfs.stat(Path::new("system/psx")).unwrap().is_dir);
+        assert_eq!(vfs.stat(Path::new("system/bios.bin")).unwrap().size, 4);
+        assert!(vfs.stat(Path::new("system/nada")).is_none());
+    }
+}
@@ -0,0 +1,172 @@
+use super::{RetroVfs, RetroVfsFile, VfsDirEntry, VfsOpenMode, VfsStat};
+use std::{
+    fmt::Debug,
+    io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VfsOperation {
+    Open(VfsOpenMode),
+    Remove,
+    Rename,
+    Stat,
+    Mkdir,
+    ReadDir,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VfsAccess {
+    pub operation: VfsOperation,
+    pub path: PathBuf,
+    #[doc = "o novo caminho quando a operação for um [VfsOperation::Rename]"]
+    pub new_path: Option<PathBuf>,
+    pub success: bool,
+}
+
+/// Recebe todos os acessos que o core faz aos arquivos através de um [ObservedVfs].
+///
+/// O core pode chamar a interface VFS de qualquer thread, então [VfsObserver::on_access] deve
+/// retornar rápido e nao pode esperar pela thread do jogo.
+pub trait VfsObserver: Debug + Send + Sync {
+    fn on_access(&self, access: &VfsAccess);
+}
+
+/// Envia cada operação para o *inner* e informa o resultado ao *observer*.
+pub struct ObservedVfs {
+    inner: Arc<dyn RetroVfs>,
+    observer: Arc<dyn VfsObserver>,
+}
+
+impl ObservedVfs {
+    pub fn new(inner: Arc<dyn RetroVfs>, observer: Arc<dyn VfsObserver>) -> Self {
+        Self { inner, observer }
+    }
+
+    fn notify(&self, operation: VfsOperation, path: &Path, new_path: Option<&Path>, success: bool) {
+        self.observer.on_access(&VfsAccess {
+            operation,
+            path: path.to_path_buf(),
+            new_path: new_path.map(Path::to_path_buf),
+            success,
+        });
+    }
+}
+
+impl RetroVfs for ObservedVfs {
+    fn open(&self, path: &Path, mode: VfsOpenMode) -> io::Result<Box<dyn RetroVfsFile>> {
+        let file = self.inner.open(path, mode);
+        self.notify(VfsOperation::Open(mode), path, None, file.is_ok());
+
+        file
+    }
+
+    fn remove(&self, path: &Path) -> io::Result<()> {
+        let result = self.inner.remove(path);
+        self.notify(VfsOperation::Remove, path, None, result.is_ok());
+
+        result
+    }
+
+    fn rename(&self, old_path: &Path, new_path: &Path) -> io::Result<()> {
+        let result = self.inner.rename(old_path, new_path);
+        self.notify(
+            VfsOperation::Rename,
+            old_path,
+            Some(new_path),
+            result.is_ok(),
+        );
+
+        result
+    }
+
+    fn stat(&self, path: &Path) -> Option<VfsStat> {
+        let stat = self.inner.stat(path);
+        self.notify(VfsOperation::Stat, path, None, stat.is_some());
+
+        stat
+    }
+
+    fn mkdir(&self, path: &Path) -> io::Result<()> {
+        let result = self.inner.mkdir(path);
+        self.notify(VfsOperation::Mkdir, path, None, result.is_ok());
+
+        result
+    }
+
+    fn read_dir(&self, path: &Path, include_hidden: bool) -> io::Result<Vec<VfsDirEntry>> {
+        let entries = self.inner.read_dir(path, include_hidden);
+        self.notify(VfsOperation::ReadDir, path, None, entries.is_ok());
+
+        entries
+    }
+}
+
+#[cfg(test)]
+mod test_vfs_observed {
+    use super::{ObservedVfs, VfsAccess, VfsObserver, VfsOperation};
+    use crate::vfs::{MemoryVfs, RetroVfs, VfsOpenMode};
+    use std::{
+        path::{Path, PathBuf},
+        sync::{Arc, Mutex},
+    };
+
+    #[derive(Debug, Default)]
+    struct RecordObserver {
+        accesses: Mutex<Vec<VfsAccess>>,
+    }
+
+    impl VfsObserver for RecordObserver {
+        fn on_access(&self, access: &VfsAccess) {
+            self.accesses.lock().unwrap().push(access.clone());
+        }
+    }
+
+    #[test]
+    fn notify_accesses() {
+        let memory = MemoryVfs::new();
+        memory.insert_file("save/game.srm", vec![0; 8]);
+
+        let observer = Arc::new(RecordObserver::default());
+        let vfs = ObservedVfs::new(Arc::new(memory), observer.clone());
+
+        let mode = VfsOpenMode {
+            read: true,
+            write: false,
+            update_existing: false,
+        };
+
+        assert!(vfs.open(Path::new("save/game.srm"), mode).is_ok());
+        assert!(vfs.stat(Path::new("save/missing.srm")).is_none());
+        assert!(vfs
+            .rename(Path::new("save/game.srm"), Path::new("save/old.srm"))
+            .is_ok());
+
+        let accesses = observer.accesses.lock().unwrap();
+
+        assert_eq!(
+            *accesses,
+            vec![
+                VfsAccess {
+                    operation: VfsOperation::Open(mode),
+                    path: PathBuf::from("save/game.srm"),
+                    new_path: None,
+                    success: true,
+                },
+                VfsAccess {
+                    operation: VfsOperation::Stat,
+                    path: PathBuf::from("save/missing.srm"),
+                    new_path: None,
+                    success: false,
+                },
+                VfsAccess {
+                    operation: VfsOperation::Rename,
+                    path: PathBuf::from("save/game.srm"),
+                    new_path: Some(PathBuf::from("save/old.srm")),
+                    success: true,
+                },
+            ]
+        );
+    }
+}
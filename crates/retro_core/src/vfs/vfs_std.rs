@@ -0,0 +1,199 @@
+use super::{normalize_path, RetroVfs, RetroVfsFile, VfsDirEntry, VfsOpenMode, VfsStat};
+use generics::retro_paths::RetroPaths;
+use std::{
+    env,
+    fs::{self, File, OpenOptions},
+    io::{self, ErrorKind},
+    path::{Path, PathBuf},
+};
+
+impl RetroVfsFile for File {
+    fn size(&mut self) -> io::Result<u64> {
+        Ok(self.metadata()?.len())
+    }
+
+    fn truncate(&mut self, len: u64) -> io::Result<()> {
+        self.set_len(len)
+    }
+}
+
+/// Usa o sistema de arquivos real.
+///
+/// Quando criado com [StdVfs::sandboxed] o core so consegue acessar as pastas de [RetroPaths] e as
+/// pastas adicionadas com [StdVfs::allow_dir].
+#[derive(Debug, Default)]
+pub struct StdVfs {
+    allowed_dirs: Option<Vec<PathBuf>>,
+}
+
+impl StdVfs {
+    #[doc = "nao impõe nenhuma restrição ao core"]
+    pub fn new() -> Self {
+        Self { allowed_dirs: None }
+    }
+
+    pub fn sandboxed(paths: &RetroPaths) -> Self {
+        let mut vfs = Self {
+            allowed_dirs: Some(Vec::new()),
+        };
+
+        vfs.allow_dir(paths.system.as_str());
+        vfs.allow_dir(paths.save.as_str());
+        vfs.allow_dir(paths.assets.as_str());
+        vfs.allow_dir(paths.temps.as_str());
+
+        vfs
+    }
+
+    #[doc = "permite que o core acesse *dir*, como a pasta onde esta a rom"]
+    pub fn allow_dir(&mut self, dir: impl AsRef<Path>) {
+        if let Some(allowed_dirs) = &mut self.allowed_dirs {
+            if let Ok(dir) = resolve(dir.as_ref()) {
+                allowed_dirs.push(dir);
+            }
+        }
+    }
+
+    fn check(&self, path: &Path) -> io::Result<PathBuf> {
+        let path = resolve(path)?;
+
+        match &self.allowed_dirs {
+            Some(allowed_dirs) if !allowed_dirs.iter().any(|dir| path.starts_with(dir)) => {
+                Err(io::Error::new(
+                    ErrorKind::PermissionDenied,
+                    format!("o core nao tem permissão para acessar {}", path.display()),
+                ))
+            }
+            _ => Ok(path),
+        }
+    }
+}
+
+#[doc = "caminho absoluto e sem links simbólicos, mesmo se o arquivo ainda nao existir"]
+fn resolve(path: &Path) -> io::Result<PathBuf> {
+    let path = if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        env::current_dir()?.join(path)
+    };
+
+    let path = normalize_path(&path);
+
+    //so a parte do caminho que ja existe pode ser resolvida
+    for ancestor in path.ancestors() {
+        if let Ok(real) = ancestor.canonicalize() {
+            let rest = path.strip_prefix(ancestor).unwrap_or(Path::new(""));
+            return Ok(real.join(rest));
+        }
+    }
+
+    Ok(path)
+}
+
+impl RetroVfs for StdVfs {
+    fn open(&self, path: &Path, mode: VfsOpenMode) -> io::Result<Box<dyn RetroVfsFile>> {
+        let path = self.check(path)?;
+        let mut options = OpenOptions::new();
+
+        //mesmas regras usadas pelo RetroArch (rb, wb, w+b e r+b)
+        if !mode.write {
+            options.read(true);
+        } else if mode.update_existing {
+            options.read(true).write(true);
+        } else {
+            options
+                .read(mode.read)
+                .write(true)
+                .create(true)
+                .truncate(true);
+        }
+
+        Ok(Box::new(options.open(path)?))
+    }
+
+    fn remove(&self, path: &Path) -> io::Result<()> {
+        let path = self.check(path)?;
+
+        if path.is_dir() {
+            fs::remove_dir(path)
+        } else {
+            fs::remove_file(path)
+        }
+    }
+
+    fn rename(&self, old_path: &Path, new_path: &Path) -> io::Result<()> {
+        fs::rename(self.check(old_path)?, self.check(new_path)?)
+    }
+
+    fn stat(&self, path: &Path) -> Option<VfsStat> {
+        let metadata = fs::metadata(self.check(path).ok()?).ok()?;
+
+        Some(VfsStat {
+            is_dir: metadata.is_dir(),
+            size: metadata.len(),
+        })
+    }
+
+    fn mkdir(&self, path: &Path) -> io::Result<()> {
+        let path = self.check(path)?;
+
+        if path.is_dir() {
+            return Err(ErrorKind::AlreadyExists.into());
+        }
+
+        fs::create_dir_all(path)
+    }
+
+    fn read_dir(&self, path: &Path, include_hidden: bool) -> io::Result<Vec<VfsDirEntry>> {
+        let mut entries = Vec::new();
+
+        for entry in fs::read_dir(self.check(path)?)? {
+            let entry = entry?;
+            let name = entry.file_name().to_string_lossy().to_string();
+
+            if !include_hidden && name.starts_with('.') {
+                continue;
+            }
+
+            entries.push(VfsDirEntry {
+                name,
+                is_dir: entry.file_type()?.is_dir(),
+            });
+        }
+
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod test_vfs_std {
+    use super::StdVfs;
+    use crate::{
+        test_tools::paths::get_paths,
+        vfs::{RetroVfs, VfsOpenMode},
+    };
+    use std::{io::ErrorKind, path::Path};
+
+    #[test]
+    fn sandbox() {
+        let paths = get_paths().unwrap();
+        let vfs = StdVfs::sandboxed(&paths);
+
+        let mode = VfsOpenMode {
+            read: true,
+            write: true,
+            update_existing: false,
+        };
+
+        let save = Path::new(paths.save.as_str()).join("vfs_test.srm");
+        assert!(vfs.open(&save, mode).is_ok());
+        vfs.remove(&save).unwrap();
+
+        //tentar sair da pasta save usando '..' tambem deve ser bloqueado
+        let outside = Path::new(paths.save.as_str()).join("../../vfs_test.srm");
+        assert_eq!(
+            vfs.open(&outside, mode).err().unwrap().kind(),
+            ErrorKind::PermissionDenied
+        );
+    }
+}
@@ -0,0 +1,197 @@
+use super::{
+    normalize_path, vfs_memory::MemoryFile, RetroVfs, RetroVfsFile, VfsDirEntry, VfsOpenMode,
+    VfsStat,
+};
+use std::{
+    fs::File,
+    io::{self, ErrorKind, Read},
+    path::{Path, PathBuf},
+};
+use zip::ZipArchive;
+
+#[derive(Debug)]
+struct ZipEntry {
+    name: String,
+    is_dir: bool,
+    size: u64,
+}
+
+/// Mostra o conteúdo de um arquivo zip como se ele fosse uma pasta (*mount_dir*).
+///
+/// Os arquivos dentro do zip sao somente leitura. Qualquer caminho fora de *mount_dir* e enviado
+/// para o *fallback*, então o core ainda consegue usar as pastas system e save normalmente.
+pub struct ZipVfs {
+    archive_path: PathBuf,
+    mount_dir: PathBuf,
+    entries: Vec<ZipEntry>,
+    fallback: Box<dyn RetroVfs>,
+}
+
+fn read_only_error() -> io::Error {
+    io::Error::new(
+        ErrorKind::PermissionDenied,
+        "os arquivos dentro de um zip sao somente leitura",
+    )
+}
+
+impl ZipVfs {
+    pub fn new(
+        archive_path: impl AsRef<Path>,
+        mount_dir: impl AsRef<Path>,
+        fallback: Box<dyn RetroVfs>,
+    ) -> io::Result<Self> {
+        let mut archive = ZipArchive::new(File::open(archive_path.as_ref())?)?;
+        let mut entries = Vec::with_capacity(archive.len());
+
+        for index in 0..archive.len() {
+            let file = archive.by_index(index)?;
+
+            entries.push(ZipEntry {
+                name: file.name().trim_end_matches('/').to_string(),
+                is_dir: file.is_dir(),
+                size: file.size(),
+            });
+        }
+
+        Ok(Self {
+            archive_path: archive_path.as_ref().to_path_buf(),
+            mount_dir: normalize_path(mount_dir.as_ref()),
+            entries,
+            fallback,
+        })
+    }
+
+    #[doc = "nome usado dentro do zip, ou None se o caminho estiver fora de mount_dir"]
+    fn entry_name(&self, path: &Path) -> Option<String> {
+        let relative = normalize_path(path)
+            .strip_prefix(&self.mount_dir)
+            .ok()?
+            .to_path_buf();
+
+        let name: Vec<String> = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().to_string())
+            .collect();
+
+        Some(name.join("/"))
+    }
+
+    fn is_dir(&self, name: &str) -> bool {
+        let prefix = format!("{}/", name);
+
+        name.is_empty()
+            || self.entries.iter().any(|entry| {
+                (entry.is_dir && entry.name == name) || entry.name.starts_with(&prefix)
+            })
+    }
+}
+
+impl RetroVfs for ZipVfs {
+    fn open(&self, path: &Path, mode: VfsOpenMode) -> io::Result<Box<dyn RetroVfsFile>> {
+        let name = match self.entry_name(path) {
+            Some(name) => name,
+            None => return self.fallback.open(path, mode),
+        };
+
+        if mode.write {
+            return Err(read_only_error());
+        }
+
+        let mut archive = ZipArchive::new(File::open(&self.archive_path)?)?;
+        let mut file = archive.by_name(&name)?;
+
+        let mut data = Vec::with_capacity(file.size() as usize);
+        file.read_to_end(&mut data)?;
+
+        Ok(Box::new(MemoryFile::read_only(data)))
+    }
+
+    fn remove(&self, path: &Path) -> io::Result<()> {
+        match self.entry_name(path) {
+            Some(_) => Err(read_only_error()),
+            None => self.fallback.remove(path),
+        }
+    }
+
+    fn rename(&self, old_path: &Path, new_path: &Path) -> io::Result<()> {
+        if self.entry_name(old_path).is_some() || self.entry_name(new_path).is_some() {
+            return Err(read_only_error());
+        }
+
+        self.fallback.rename(old_path, new_path)
+    }
+
+    fn stat(&self, path: &Path) -> Option<VfsStat> {
+        let name = match self.entry_name(path) {
+            Some(name) => name,
+            None => return self.fallback.stat(path),
+        };
+
+        if self.is_dir(&name) {
+            return Some(VfsStat {
+                is_dir: true,
+                size: 0,
+            });
+        }
+
+        self.entries
+            .iter()
+            .find(|entry| entry.name == name)
+            .map(|entry| VfsStat {
+                is_dir: false,
+                size: entry.size,
+            })
+    }
+
+    fn mkdir(&self, path: &Path) -> io::Result<()> {
+        match self.entry_name(path) {
+            Some(name) if self.is_dir(&name) => Err(ErrorKind::AlreadyExists.into()),
+            Some(_) => Err(read_only_error()),
+            None => self.fallback.mkdir(path),
+        }
+    }
+
+    fn read_dir(&self, path: &Path, include_hidden: bool) -> io::Result<Vec<VfsDirEntry>> {
+        let name = match self.entry_name(path) {
+            Some(name) => name,
+            None => return self.fallback.read_dir(path, include_hidden),
+        };
+
+        if !self.is_dir(&name) {
+            return Err(ErrorKind::NotFound.into());
+        }
+
+        let prefix = if name.is_empty() {
+            String::new()
+        } else {
+            format!("{}/", name)
+        };
+
+        let mut entries: Vec<VfsDirEntry> = Vec::new();
+
+        for entry in &self.entries {
+            let relative = match entry.name.strip_prefix(&prefix) {
+                Some(relative) if !relative.is_empty() => relative,
+                _ => continue,
+            };
+
+            let (child, is_dir) = match relative.split_once('/') {
+                Some((child, _)) => (child, true),
+                None => (relative, entry.is_dir),
+            };
+
+            if !include_hidden && child.starts_with('.') {
+                continue;
+            }
+
+            if !entries.iter().any(|e| e.name == child) {
+                entries.push(VfsDirEntry {
+                    name: child.to_string(),
+                    is_dir,
+                });
+            }
+        }
+
+        Ok(entries)
+    }
+}
@@ -17,6 +17,10 @@ impl DeviceListener for DeviceEventHandle {
     fn button_pressed(&self, button: String, device: tinic::Device) {
         println!("{} pressed -> {}", device.name, button)
     }
+
+    fn led_state_changed(&self, led: i32, state: i32) {
+        println!("led {} -> {}", led, state)
+    }
 }
 
 #[tokio::main]
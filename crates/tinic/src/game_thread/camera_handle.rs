@@ -0,0 +1,39 @@
+use generics::{erro_handle::ErroHandle, types::ArcTMuxte};
+use retro_core::camera::{CameraFrame, CameraSource};
+
+pub type SharedCameraSource = ArcTMuxte<Option<Box<dyn CameraSource + Send>>>;
+
+//a fonte pode ser trocada pelo Tinic enquanto o jogo esta em execução
+#[derive(Debug)]
+pub struct CameraHandle {
+    source: SharedCameraSource,
+}
+
+impl CameraHandle {
+    pub fn new(source: SharedCameraSource) -> Self {
+        Self { source }
+    }
+}
+
+impl CameraSource for CameraHandle {
+    fn start(&self) -> Result<bool, ErroHandle> {
+        match &*self.source.try_load()? {
+            Some(source) => source.start(),
+            None => Ok(false),
+        }
+    }
+
+    fn stop(&self) -> Result<(), ErroHandle> {
+        match &*self.source.try_load()? {
+            Some(source) => source.stop(),
+            None => Ok(()),
+        }
+    }
+
+    fn next_frame(&self, width: u32, height: u32) -> Result<Option<CameraFrame>, ErroHandle> {
+        match &*self.source.try_load()? {
+            Some(source) => source.next_frame(width, height),
+            None => Ok(None),
+        }
+    }
+}
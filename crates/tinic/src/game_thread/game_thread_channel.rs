@@ -1,15 +1,31 @@
 use crate::thread_stack::game_stack::GameStackCommand::{
-    DeviceConnected, DisableFullScreen, EnableFullScreen, LoadGame, Pause, Reset, Resume, SaveState,
+    DeviceConnected, DisableFullScreen, EjectDisk, EnableFullScreen, FrameAdvance, GetCheats,
+    GetInputDescriptors, GetPerfReport, InsertDisk, LoadGame, Pause, PlayMovie, Reset, ResetCheats,
+    Resume, Rewind, SaveState, SetCheat, SetDiskIndex, SetRewindConfig, SetRunAheadConfig,
+    SetSpeed, StartMovieRecording, StopMovie, VerifyMovie,
 };
-use crate::thread_stack::game_stack::{GameStack, GameStackCommand};
+use crate::thread_stack::game_stack::{GameContent, GameStack, GameStackCommand};
 use crate::thread_stack::main_stack::MainStackCommand::{
-    GameLoaded, GameStateSaved, QuitSusses, SaveStateLoaded,
+    Cheats, GameLoaded, GameStateSaved, InputDescriptors, MovieVerified, PerfReport, QuitSusses,
+    SaveStateLoaded,
 };
 use crate::thread_stack::main_stack::{MainStack, MainStackCommand, SaveImg, SavePath};
 use crate::thread_stack::model_stack::{wait_response, RetroStackFn};
 use generics::retro_paths::RetroPaths;
+use libretro_sys::binding_libretro::retro_language;
+use retro_av::RetroAvMode;
 use retro_controllers::devices_manager::Device;
-use retro_core::option_manager::OptionManager;
+use retro_core::{
+    cheat_manager::Cheat,
+    movie_manager::{MovieOrigin, MovieVerification, StateHashConfig},
+    option_manager::OptionManager,
+    perf_manager::PerfCounterReport,
+    rewind_manager::RewindConfig,
+    run_ahead::RunAheadConfig,
+    speed::EmulationSpeed,
+    system::InputDescriptor,
+};
+use std::path::PathBuf;
 use std::sync::Arc;
 
 #[derive(Debug, Clone)]
@@ -34,11 +50,18 @@ impl GameThreadChannel {
     pub async fn load_game(
         &self,
         core_path: &str,
-        rom_path: &str,
+        content: GameContent,
         paths: RetroPaths,
+        av_mode: RetroAvMode,
+        language: retro_language,
     ) -> Option<Arc<OptionManager>> {
-        self.game_stack
-            .push(LoadGame(core_path.to_string(), rom_path.to_string(), paths));
+        self.game_stack.push(LoadGame(
+            core_path.to_string(),
+            content,
+            paths,
+            av_mode,
+            language,
+        ));
 
         let mut core_options: Option<Arc<OptionManager>> = None;
 
@@ -100,10 +123,137 @@ impl GameThreadChannel {
         self.game_stack.push(Reset);
     }
 
+    pub fn frame_advance(&self) {
+        self.game_stack.push(FrameAdvance);
+    }
+
+    pub fn set_speed(&self, speed: EmulationSpeed) {
+        self.game_stack.push(SetSpeed(speed));
+    }
+
     pub fn connect_device(&self, device: Device) {
         self.game_stack.push(DeviceConnected(device))
     }
 
+    pub fn rewind(&self, rewinding: bool) {
+        self.game_stack.push(Rewind(rewinding));
+    }
+
+    pub fn set_rewind_config(&self, config: RewindConfig) {
+        self.game_stack.push(SetRewindConfig(config));
+    }
+
+    pub fn set_run_ahead_config(&self, config: RunAheadConfig) {
+        self.game_stack.push(SetRunAheadConfig(config));
+    }
+
+    //############################ AÇÕES RELACIONAS AOS FILMES ####################################
+    pub fn start_movie_recording(
+        &self,
+        path: PathBuf,
+        origin: MovieOrigin,
+        hash_config: Option<StateHashConfig>,
+    ) {
+        self.game_stack
+            .push(StartMovieRecording(path, origin, hash_config));
+    }
+
+    pub fn play_movie(&self, path: PathBuf) {
+        self.game_stack.push(PlayMovie(path));
+    }
+
+    pub fn stop_movie(&self) {
+        self.game_stack.push(StopMovie);
+    }
+
+    pub async fn verify_movie(&self, path: PathBuf) -> Option<MovieVerification> {
+        self.game_stack.push(VerifyMovie(path));
+
+        let mut verification = None;
+
+        wait_response(&self.main_stack, |command| match command {
+            MovieVerified(v) => {
+                verification = *v;
+                true
+            }
+            _ => false,
+        });
+
+        verification
+    }
+
+    //############################ AÇÕES RELACIONAS AOS CHEATS ####################################
+    pub async fn get_cheats(&self) -> Vec<Cheat> {
+        self.game_stack.push(GetCheats);
+
+        let mut cheats = Vec::new();
+
+        wait_response(&self.main_stack, |command| match command {
+            Cheats(c) => {
+                cheats = c.clone();
+                true
+            }
+            _ => false,
+        });
+
+        cheats
+    }
+
+    pub fn set_cheat(&self, index: usize, enabled: bool) {
+        self.game_stack.push(SetCheat(index, enabled));
+    }
+
+    pub fn reset_cheats(&self) {
+        self.game_stack.push(ResetCheats);
+    }
+
+    //############################ AÇÕES RELACIONAS AOS DISCOS ####################################
+    pub fn eject_disk(&self) {
+        self.game_stack.push(EjectDisk);
+    }
+
+    pub fn insert_disk(&self) {
+        self.game_stack.push(InsertDisk);
+    }
+
+    pub fn set_disk_index(&self, index: u32) {
+        self.game_stack.push(SetDiskIndex(index));
+    }
+
+    //############################ AÇÕES RELACIONAS AOS INPUTS ####################################
+    pub async fn get_input_descriptors(&self) -> Vec<InputDescriptor> {
+        self.game_stack.push(GetInputDescriptors);
+
+        let mut descriptors = Vec::new();
+
+        wait_response(&self.main_stack, |command| match command {
+            InputDescriptors(d) => {
+                descriptors = d.clone();
+                true
+            }
+            _ => false,
+        });
+
+        descriptors
+    }
+
+    //############################ AÇÕES RELACIONAS AO DESEMPENHO #################################
+    pub async fn get_perf_report(&self) -> Vec<PerfCounterReport> {
+        self.game_stack.push(GetPerfReport);
+
+        let mut report = Vec::new();
+
+        wait_response(&self.main_stack, |command| match command {
+            PerfReport(r) => {
+                report = r.clone();
+                true
+            }
+            _ => false,
+        });
+
+        report
+    }
+
     pub async fn quit(&self) -> bool {
         self.game_stack.push(GameStackCommand::Quit);
 
@@ -1,14 +1,44 @@
 use super::game_thread_channel::GameThreadChannel;
 use super::stack_commands_handle::stack_commands_handle;
-use super::{game_thread_state::ThreadState, game_window_handle::game_window_handle};
-use generics::erro_handle::ErroHandle;
+use super::{
+    camera_handle::SharedCameraSource,
+    game_thread_state::ThreadState,
+    game_window_handle::game_window_handle,
+    message_handle::MessageListener,
+    power_handle::SharedPowerProvider,
+    sensor_handle::{SharedLocationProvider, SharedSensorProvider},
+    vfs_handle::{SharedVfsBackend, SharedVfsObserver, VfsBackend},
+};
+use generics::{
+    constants::MEMORY_LOG_CAPACITY,
+    erro_handle::ErroHandle,
+    types::{ArcTMuxte, TMutex},
+};
 use retro_controllers::RetroController;
+use retro_core::{
+    camera::CameraSource,
+    location::LocationProvider,
+    logger::{LogLevelFilter, MemoryLogger},
+    power::{PowerProvider, SysfsPowerProvider},
+    sensor::SensorProvider,
+    vfs::VfsObserver,
+};
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::{sync::Arc, thread};
 
 #[derive(Debug)]
 pub struct GameThread {
     is_running: Arc<AtomicBool>,
+    message_listener: ArcTMuxte<Option<Box<dyn MessageListener>>>,
+    osd_enabled: Arc<AtomicBool>,
+    sensor_provider: SharedSensorProvider,
+    location_provider: SharedLocationProvider,
+    camera_source: SharedCameraSource,
+    power_provider: SharedPowerProvider,
+    vfs_backend: SharedVfsBackend,
+    vfs_observer: SharedVfsObserver,
+    pub log_filter: LogLevelFilter,
+    pub log_memory: MemoryLogger,
     pub channel: GameThreadChannel,
 }
 
@@ -16,6 +46,16 @@ impl GameThread {
     pub fn new() -> Self {
         Self {
             is_running: Arc::new(AtomicBool::new(false)),
+            message_listener: TMutex::new(None),
+            osd_enabled: Arc::new(AtomicBool::new(true)),
+            sensor_provider: TMutex::new(None),
+            location_provider: TMutex::new(None),
+            camera_source: TMutex::new(None),
+            power_provider: TMutex::new(Some(Box::new(SysfsPowerProvider::new()))),
+            vfs_backend: TMutex::new(VfsBackend::default()),
+            vfs_observer: TMutex::new(None),
+            log_filter: LogLevelFilter::default(),
+            log_memory: MemoryLogger::new(MEMORY_LOG_CAPACITY),
             channel: GameThreadChannel::new(),
         }
     }
@@ -35,13 +75,69 @@ impl GameThread {
         self.is_running.load(Ordering::SeqCst)
     }
 
+    pub fn set_message_listener(&self, listener: Option<Box<dyn MessageListener>>) {
+        self.message_listener.store(listener);
+    }
+
+    pub fn set_osd_enabled(&self, enabled: bool) {
+        self.osd_enabled.store(enabled, Ordering::SeqCst);
+    }
+
+    pub fn set_sensor_provider(&self, provider: Option<Box<dyn SensorProvider + Send>>) {
+        self.sensor_provider.store(provider);
+    }
+
+    pub fn set_location_provider(&self, provider: Option<Box<dyn LocationProvider + Send>>) {
+        self.location_provider.store(provider);
+    }
+
+    pub fn set_camera_source(&self, source: Option<Box<dyn CameraSource + Send>>) {
+        self.camera_source.store(source);
+    }
+
+    pub fn set_power_provider(&self, provider: Option<Box<dyn PowerProvider + Send>>) {
+        self.power_provider.store(provider);
+    }
+
+    pub fn set_vfs_backend(&self, backend: VfsBackend) {
+        self.vfs_backend.store(backend);
+    }
+
+    pub fn set_vfs_observer(&self, observer: Option<Arc<dyn VfsObserver>>) {
+        self.vfs_observer.store(observer);
+    }
+
     fn spawn_game_thread(&self, controller_ctx: Arc<RetroController>) {
         let is_running = self.is_running.clone();
         let controller_ctx = controller_ctx.clone();
         let channel_notify = self.channel.get_notify();
+        let message_listener = self.message_listener.clone();
+        let osd_enabled = self.osd_enabled.clone();
+        let sensor_provider = self.sensor_provider.clone();
+        let location_provider = self.location_provider.clone();
+        let camera_source = self.camera_source.clone();
+        let power_provider = self.power_provider.clone();
+        let vfs_backend = self.vfs_backend.clone();
+        let vfs_observer = self.vfs_observer.clone();
+        let log_filter = self.log_filter.clone();
+        let log_memory = self.log_memory.clone();
 
         thread::spawn(move || {
-            let mut state = ThreadState::new(channel_notify, controller_ctx, is_running);
+            let mut state = ThreadState::new(
+                channel_notify,
+                controller_ctx,
+                is_running,
+                message_listener,
+                osd_enabled,
+                sensor_provider,
+                location_provider,
+                camera_source,
+                power_provider,
+                vfs_backend,
+                vfs_observer,
+                log_filter,
+                log_memory,
+            );
 
             while state.is_running() {
                 if let Err(e) = stack_commands_handle(&mut state) {
@@ -1,27 +1,78 @@
-use crate::thread_stack::game_stack::GameStackCommand::DeviceConnected;
+use crate::thread_stack::game_stack::{GameContent, GameStackCommand::DeviceConnected};
 use crate::thread_stack::main_stack::MainStackCommand::{
-    self, GameLoaded, GameStateSaved, SaveStateLoaded,
+    self, Cheats, GameLoaded, GameStateSaved, InputDescriptors, MovieVerified, PerfReport,
+    SaveStateLoaded,
 };
-use generics::constants::SAVE_IMAGE_EXTENSION_FILE;
-use generics::{constants::THREAD_SLEEP_TIME, erro_handle::ErroHandle, retro_paths::RetroPaths};
-use libretro_sys::binding_libretro::retro_hw_context_type::RETRO_HW_CONTEXT_OPENGL_CORE;
-use retro_av::{EventPump, RetroAv};
+use generics::constants::{BATTERY_SAVE_INTERVAL, SAVE_IMAGE_EXTENSION_FILE};
+use generics::{
+    constants::THREAD_SLEEP_TIME,
+    erro_handle::ErroHandle,
+    retro_paths::RetroPaths,
+    types::{ArcTMuxte, TMutex},
+};
+use libretro_sys::binding_libretro::{
+    retro_hw_context_type::RETRO_HW_CONTEXT_OPENGL_CORE, retro_language,
+};
+use retro_av::{create_av_ctx, EventPump, RetroAvCtx, RetroAvMode};
 use retro_controllers::{devices_manager::Device, RetroController};
 use retro_core::{
-    graphic_api::GraphicApi, option_manager::OptionManager, RetroCore, RetroCoreIns,
-    RetroEnvCallbacks,
+    av_info::AvInfo,
+    camera::CameraSource,
+    graphic_api::GraphicApi,
+    location::LocationProvider,
+    logger::{FileLogger, LogLevelFilter, MemoryLogger, MultiLogger, StdoutLogger},
+    message::RetroMessage,
+    movie_manager::{Movie, MovieMode, MovieOrigin, StateHashConfig},
+    option_manager::OptionManager,
+    rewind_manager::{RewindConfig, RewindManager},
+    run_ahead::{RunAheadConfig, RunAheadMode},
+    speed::EmulationSpeed,
+    RetroCore, RetroCoreIns, RetroEnvCallbacks,
 };
 use std::{
-    path::PathBuf,
+    fs,
+    path::{Path, PathBuf},
     sync::{
-        atomic::{AtomicBool, Ordering},
+        atomic::{AtomicBool, AtomicUsize, Ordering},
         Arc,
     },
     thread,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
+use super::camera_handle::{CameraHandle, SharedCameraSource};
 use super::game_thread_channel::GameThreadGenericNotify;
+use super::message_handle::{CoreMessageQueue, IgnoredMessages, MessageListener};
+use super::power_handle::{PowerHandle, SharedPowerProvider};
+use super::sensor_handle::{
+    LocationHandle, SensorHandle, SharedLocationProvider, SharedSensorProvider,
+};
+use super::vfs_handle::{create_observed_vfs, SharedVfsBackend, SharedVfsObserver};
+
+//necessário para criar a segunda instancia do run-ahead depois que a rom ja foi carregada
+struct LoadedContent {
+    core_path: String,
+    content: GameContent,
+    paths: RetroPaths,
+    language: retro_language,
+}
+
+//a segunda instancia do run-ahead usa uma copia do core e uma pasta save própria dentro de temps,
+//assim a SRAM especulativa nunca sobrescreve a do jogador. a pasta e removida junto com o core
+struct RunAheadCore {
+    core: RetroCoreIns,
+    dir: PathBuf,
+}
+
+impl Drop for RunAheadCore {
+    fn drop(&mut self) {
+        let _ = self.core.de_init();
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+//cada segunda instancia precisa de uma pasta diferente, mesmo com mais de um Tinic no processo
+static RUN_AHEAD_DIR_COUNT: AtomicUsize = AtomicUsize::new(0);
 
 pub struct ThreadState {
     pub channel_notify: GameThreadGenericNotify,
@@ -29,9 +80,32 @@ pub struct ThreadState {
     pub pause_request_new_frames: bool,
     pub use_full_screen_mode: bool,
     pub event_pump: Option<EventPump>,
+    last_battery_save: Instant,
     controller_ctx: Arc<RetroController>,
     retro_core: Option<RetroCoreIns>,
-    retro_av: Option<RetroAv>,
+    retro_av: Option<Box<dyn RetroAvCtx>>,
+    pending_messages: ArcTMuxte<Vec<RetroMessage>>,
+    message_listener: ArcTMuxte<Option<Box<dyn MessageListener>>>,
+    osd_enabled: Arc<AtomicBool>,
+    sensor_provider: SharedSensorProvider,
+    location_provider: SharedLocationProvider,
+    camera_source: SharedCameraSource,
+    power_provider: SharedPowerProvider,
+    vfs_backend: SharedVfsBackend,
+    vfs_observer: SharedVfsObserver,
+    log_filter: LogLevelFilter,
+    log_memory: MemoryLogger,
+    rewind: RewindManager,
+    rewinding: bool,
+    //cores com retro_serialize_size igual a 0 nao suportam states, nem rewind e run-ahead
+    serialize_supported: bool,
+    run_ahead: RunAheadConfig,
+    run_ahead_core: Option<RunAheadCore>,
+    loaded_content: Option<LoadedContent>,
+    speed: EmulationSpeed,
+    frame_advance: bool,
+    //onde o filme sendo gravado sera salvo
+    movie_path: Option<PathBuf>,
 }
 
 impl ThreadState {
@@ -43,14 +117,16 @@ impl ThreadState {
     pub fn load_game(
         &mut self,
         core_path: String,
-        rom_path: String,
+        content: GameContent,
         paths: RetroPaths,
+        av_mode: RetroAvMode,
+        language: retro_language,
     ) -> Result<(), ErroHandle> {
         if self.retro_core.is_some() {
             return Ok(());
         }
 
-        match self.create_retro_contexts(core_path, rom_path, paths) {
+        match self.create_retro_contexts(core_path, content, paths, av_mode, language) {
             Ok(option) => self
                 .channel_notify
                 .notify_main_stack(GameLoaded(Some(option))),
@@ -97,9 +173,7 @@ impl ThreadState {
                 let mut img_path: PathBuf = saved_path.clone();
                 img_path.set_extension(SAVE_IMAGE_EXTENSION_FILE);
 
-                retro_av
-                    .video
-                    .print_screen(&img_path, &retro_core.av_info)?;
+                retro_av.print_screen(&img_path, &retro_core.av_info)?;
 
                 self.channel_notify
                     .notify_main_stack(GameStateSaved(Some((saved_path, img_path))));
@@ -118,12 +192,58 @@ impl ThreadState {
         self.controller_ctx.resume_thread_events()?;
         self.pause_request_new_frames = true;
 
+        if let Some(retro_core) = &self.retro_core {
+            retro_core.set_audio_state(false)?;
+            retro_core.speed.set_frame_stepping(true);
+        }
+
         Ok(())
     }
 
     pub fn resume(&mut self) {
         self.controller_ctx.stop_thread_events();
         self.pause_request_new_frames = false;
+        self.frame_advance = false;
+
+        //o tempo que o jogo ficou pausado nao deve ser enviado para o core
+        if let Some(retro_core) = &self.retro_core {
+            retro_core.speed.set_frame_stepping(false);
+
+            if let Err(e) = retro_core
+                .reset_frame_time()
+                .and_then(|_| retro_core.set_audio_state(true))
+            {
+                println!("resume -> {:?}", e);
+            }
+        }
+    }
+
+    pub fn frame_advance(&mut self) -> Result<(), ErroHandle> {
+        if self.retro_core.is_none() {
+            return Ok(());
+        }
+
+        if !self.pause_request_new_frames {
+            self.pause()?;
+        }
+
+        self.frame_advance = true;
+
+        Ok(())
+    }
+
+    pub fn set_speed(&mut self, speed: EmulationSpeed) -> Result<(), ErroHandle> {
+        self.speed = speed;
+
+        if let Some(retro_av) = &mut self.retro_av {
+            retro_av.set_speed(speed);
+        }
+
+        if let Some(retro_core) = &self.retro_core {
+            retro_core.speed.set_speed(speed)?;
+        }
+
+        Ok(())
     }
 
     pub fn reset(&self) -> Result<(), ErroHandle> {
@@ -133,14 +253,14 @@ impl ThreadState {
     }
 
     pub fn enable_full_screen(&mut self) -> Result<(), ErroHandle> {
-        self.try_get_retro_av_ctx()?.video.enable_full_screen()?;
+        self.try_get_retro_av_ctx()?.enable_full_screen()?;
         self.use_full_screen_mode = true;
 
         Ok(())
     }
 
     pub fn disable_full_screen(&mut self) -> Result<(), ErroHandle> {
-        self.try_get_retro_av_ctx()?.video.disable_full_screen()?;
+        self.try_get_retro_av_ctx()?.disable_full_screen()?;
         self.use_full_screen_mode = false;
 
         Ok(())
@@ -150,25 +270,261 @@ impl ThreadState {
         self.try_get_retro_core_ctx()?
             .connect_controller(device.retro_port, device.retro_type)?;
 
+        if let Some(run_ahead) = &self.run_ahead_core {
+            run_ahead
+                .core
+                .connect_controller(device.retro_port, device.retro_type)?;
+        }
+
         Ok(())
     }
+
+    //um cheat invalido nao deve encerrar o jogo
+    pub fn set_cheat(&self, index: usize, enabled: bool) {
+        if let Err(e) = self.try_set_cheat(index, enabled) {
+            println!("set_cheat -> {:?}", e);
+        }
+    }
+
+    pub fn reset_cheats(&self) {
+        if let Err(e) = self.try_reset_cheats() {
+            println!("reset_cheats -> {:?}", e);
+        }
+    }
+
+    //a segunda instancia gera o frame exibido, então também precisa receber os cheats
+    fn try_set_cheat(&self, index: usize, enabled: bool) -> Result<(), ErroHandle> {
+        self.try_get_retro_core_ctx()?.set_cheat(index, enabled)?;
+
+        if let Some(run_ahead) = &self.run_ahead_core {
+            run_ahead.core.set_cheat(index, enabled)?;
+        }
+
+        Ok(())
+    }
+
+    fn try_reset_cheats(&self) -> Result<(), ErroHandle> {
+        self.try_get_retro_core_ctx()?.reset_cheats()?;
+
+        if let Some(run_ahead) = &self.run_ahead_core {
+            run_ahead.core.reset_cheats()?;
+        }
+
+        Ok(())
+    }
+
+    pub fn send_cheats(&self) -> Result<(), ErroHandle> {
+        //a thread main sempre espera por uma resposta, mesmo que nenhuma rom esteja carregada
+        let cheats = match &self.retro_core {
+            Some(retro_core) => retro_core.get_cheats()?,
+            None => Vec::new(),
+        };
+
+        self.channel_notify.notify_main_stack(Cheats(cheats));
+
+        Ok(())
+    }
+
+    pub fn send_input_descriptors(&self) -> Result<(), ErroHandle> {
+        let descriptors = match &self.retro_core {
+            Some(retro_core) => retro_core.system.input_descriptors.read()?.clone(),
+            None => Vec::new(),
+        };
+
+        self.channel_notify
+            .notify_main_stack(InputDescriptors(descriptors));
+
+        Ok(())
+    }
+
+    pub fn send_perf_report(&self) -> Result<(), ErroHandle> {
+        let report = match &self.retro_core {
+            Some(retro_core) => retro_core.get_perf_report()?,
+            None => Vec::new(),
+        };
+
+        self.channel_notify.notify_main_stack(PerfReport(report));
+
+        Ok(())
+    }
+
+    pub fn set_rewinding(&mut self, rewinding: bool) {
+        self.rewinding = rewinding;
+
+        if let Some(retro_core) = &self.retro_core {
+            retro_core
+                .speed
+                .set_rewinding(rewinding && self.serialize_supported);
+        }
+    }
+
+    pub fn set_rewind_config(&mut self, config: RewindConfig) {
+        self.rewind.set_config(config);
+        self.check_serialize_support();
+    }
+
+    pub fn set_run_ahead_config(&mut self, config: RunAheadConfig) {
+        self.run_ahead = config;
+        self.check_serialize_support();
+        self.update_run_ahead_core();
+    }
+
+    //um filme invalido ou incompatível com o core nao deve encerrar o jogo
+    pub fn start_movie_recording(
+        &mut self,
+        path: PathBuf,
+        origin: MovieOrigin,
+        hash_config: Option<StateHashConfig>,
+    ) {
+        self.stop_movie();
+
+        match self
+            .try_get_retro_core_ctx()
+            .and_then(|core| core.start_movie_recording(origin, hash_config))
+        {
+            Ok(_) => {
+                self.movie_path.replace(path);
+            }
+            Err(e) => println!("start_movie_recording -> {:?}", e),
+        }
+    }
+
+    pub fn play_movie(&mut self, path: PathBuf) {
+        self.stop_movie();
+
+        if let Err(e) = Movie::load(&path).and_then(|movie| {
+            self.try_get_retro_core_ctx()
+                .and_then(|core| core.play_movie(movie))
+        }) {
+            println!("play_movie -> {:?}", e);
+        }
+    }
+
+    pub fn verify_movie(&mut self, path: PathBuf) {
+        self.stop_movie();
+
+        let verification = Movie::load(&path).and_then(|movie| {
+            let retro_core = self.try_get_retro_core_ctx()?;
+            let verification = retro_core.verify_movie(movie)?;
+
+            //o tempo gasto na verificação nao deve ser enviado para o core
+            retro_core.reset_frame_time()?;
+
+            Ok(verification)
+        });
+
+        //o rewind recomeça a partir do jogo restaurado depois da verificação
+        self.rewind.clear();
+
+        //a thread main sempre espera por uma resposta
+        match verification {
+            Ok(verification) => self
+                .channel_notify
+                .notify_main_stack(MovieVerified(Some(verification))),
+            Err(e) => {
+                println!("verify_movie -> {:?}", e);
+                self.channel_notify.notify_main_stack(MovieVerified(None));
+            }
+        }
+    }
+
+    pub fn stop_movie(&mut self) {
+        let retro_core = match &self.retro_core {
+            Some(retro_core) => retro_core,
+            None => return,
+        };
+
+        let movie = match retro_core.stop_movie() {
+            Ok(movie) => movie,
+            Err(e) => {
+                println!("stop_movie -> {:?}", e);
+                return;
+            }
+        };
+
+        if let (Some(movie), Some(path)) = (movie, self.movie_path.take()) {
+            if let Err(e) = movie.save(&path) {
+                println!("stop_movie -> {:?}", e);
+            }
+        }
+    }
+
+    //nem todo core suporta a troca de discos, então um erro aqui nao deve encerrar o jogo
+    pub fn eject_disk(&self) {
+        if let Err(e) = self
+            .try_get_retro_core_ctx()
+            .and_then(|core| core.eject_disk())
+        {
+            println!("eject_disk -> {:?}", e);
+        }
+    }
+
+    pub fn insert_disk(&self) {
+        if let Err(e) = self
+            .try_get_retro_core_ctx()
+            .and_then(|core| core.insert_disk())
+        {
+            println!("insert_disk -> {:?}", e);
+        }
+    }
+
+    pub fn set_disk_index(&self, index: u32) {
+        if let Err(e) = self
+            .try_get_retro_core_ctx()
+            .and_then(|core| core.set_disk_index(index))
+        {
+            println!("set_disk_index -> {:?}", e);
+        }
+    }
 }
 
 impl ThreadState {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         channel_notify: GameThreadGenericNotify,
         controller_ctx: Arc<RetroController>,
         is_running: Arc<AtomicBool>,
+        message_listener: ArcTMuxte<Option<Box<dyn MessageListener>>>,
+        osd_enabled: Arc<AtomicBool>,
+        sensor_provider: SharedSensorProvider,
+        location_provider: SharedLocationProvider,
+        camera_source: SharedCameraSource,
+        power_provider: SharedPowerProvider,
+        vfs_backend: SharedVfsBackend,
+        vfs_observer: SharedVfsObserver,
+        log_filter: LogLevelFilter,
+        log_memory: MemoryLogger,
     ) -> Self {
         Self {
+            log_filter,
+            log_memory,
+            rewind: RewindManager::new(RewindConfig::default()),
+            rewinding: false,
+            serialize_supported: false,
+            run_ahead: RunAheadConfig::default(),
+            run_ahead_core: None,
+            loaded_content: None,
+            speed: EmulationSpeed::default(),
+            frame_advance: false,
+            movie_path: None,
             channel_notify,
             controller_ctx,
             is_running,
+            message_listener,
+            osd_enabled,
+            sensor_provider,
+            location_provider,
+            camera_source,
+            power_provider,
+            vfs_backend,
+            vfs_observer,
+            pending_messages: TMutex::new(Vec::new()),
             pause_request_new_frames: false,
             use_full_screen_mode: false,
             retro_av: None,
             retro_core: None,
             event_pump: None,
+            last_battery_save: Instant::now(),
         }
     }
 
@@ -185,9 +541,9 @@ impl ThreadState {
         }
     }
 
-    pub fn try_get_retro_av_ctx(&self) -> Result<&RetroAv, ErroHandle> {
+    pub fn try_get_retro_av_ctx(&self) -> Result<&dyn RetroAvCtx, ErroHandle> {
         match &self.retro_av {
-            Some(retro_av) => Ok(retro_av),
+            Some(retro_av) => Ok(retro_av.as_ref()),
             None => Err(ErroHandle {
                 message: "erro ao tentar recuperar retro_av".to_string(),
             }),
@@ -197,52 +553,399 @@ impl ThreadState {
     fn create_retro_contexts(
         &mut self,
         core_path: String,
-        rom_path: String,
+        content: GameContent,
         paths: RetroPaths,
+        av_mode: RetroAvMode,
+        language: retro_language,
     ) -> Result<Arc<OptionManager>, ErroHandle> {
-        let mut retro_av = RetroAv::new()?;
+        //no modo headless nenhuma janela e criada, então o core não pode usar a GPU
+        let graphic_api = match av_mode {
+            RetroAvMode::Window => GraphicApi::with(RETRO_HW_CONTEXT_OPENGL_CORE),
+            RetroAvMode::Headless | RetroAvMode::HeadlessFreeRun => GraphicApi::default(),
+        };
+
+        let mut retro_av = create_av_ctx(av_mode)?;
 
         //configura as callbacks para o core
-        let (video_cb, audio_cb) = retro_av.get_core_cb();
+        let (video, audio) = retro_av.get_core_cb();
         let controller_cb = self.controller_ctx.get_core_cb();
 
+        let vfs = create_observed_vfs(&self.vfs_backend, &self.vfs_observer, &paths, &content)?;
+
+        let logger = self.create_logger(&core_path, &content, &paths);
+
+        let sensor = SensorHandle::new(
+            self.sensor_provider.clone(),
+            self.controller_ctx.get_sensor_cb(),
+        );
+
+        //a localização e a câmera so sao oferecidas se houver uma fonte quando a rom for carregada
+        let location = match &*self.location_provider.try_load()? {
+            Some(_) => Some(
+                Box::new(LocationHandle::new(self.location_provider.clone()))
+                    as Box<dyn LocationProvider>,
+            ),
+            None => None,
+        };
+
+        let camera =
+            match &*self.camera_source.try_load()? {
+                Some(_) => Some(Box::new(CameraHandle::new(self.camera_source.clone()))
+                    as Box<dyn CameraSource>),
+                None => None,
+            };
+
         let callbacks = RetroEnvCallbacks {
             controller: Box::new(controller_cb),
-            video: Box::new(video_cb),
-            audio: Box::new(audio_cb),
+            video,
+            audio,
+            message: Box::new(CoreMessageQueue::new(self.pending_messages.clone())),
+            logger: Box::new(logger),
+            vfs: Some(vfs),
+            sensor: Some(Box::new(sensor)),
+            location,
+            camera,
+            power: Some(Box::new(PowerHandle::new(self.power_provider.clone()))),
         };
 
-        let retro_core = RetroCore::new(
-            &core_path,
-            paths,
-            callbacks,
-            GraphicApi::with(RETRO_HW_CONTEXT_OPENGL_CORE),
-        )?;
+        let retro_core =
+            RetroCore::new(&core_path, paths.clone(), callbacks, graphic_api, language)?;
+
+        let av_info = load_content(&retro_core, &content)?;
+        let pump_event = retro_av.build(&av_info)?;
 
-        let av_info = retro_core.load_game(&rom_path)?;
-        let pump_event = retro_av.build_window(&av_info)?;
+        retro_av.set_speed(self.speed);
+        retro_core.speed.set_speed(self.speed)?;
+
+        //a saída de áudio ja esta pronta, então o core pode começar a usar a callback de áudio
+        retro_core.set_audio_state(true)?;
 
         let op_manager = retro_core.options.clone();
 
         self.retro_core.replace(retro_core);
-        self.event_pump.replace(pump_event);
+        self.event_pump = pump_event;
+        self.last_battery_save = Instant::now();
         self.retro_av.replace(retro_av);
 
+        self.loaded_content.replace(LoadedContent {
+            core_path,
+            content,
+            paths,
+            language,
+        });
+
+        self.rewind.clear();
+        self.check_serialize_support();
+        self.update_run_ahead_core();
+
         Ok(op_manager)
     }
 
+    fn update_run_ahead_core(&mut self) {
+        let use_second_instance = self.serialize_supported
+            && self.run_ahead.frames > 0
+            && self.run_ahead.mode == RunAheadMode::SecondInstance;
+
+        if !use_second_instance {
+            self.run_ahead_core.take();
+            return;
+        }
+
+        if self.run_ahead_core.is_some() {
+            return;
+        }
+
+        match self.create_run_ahead_core() {
+            Ok(core) => {
+                self.run_ahead_core.replace(core);
+            }
+            Err(e) => {
+                println!(
+                    "run_ahead -> {:?}, os frames serão gerados pelo core principal",
+                    e
+                );
+            }
+        }
+    }
+
+    fn create_run_ahead_core(&self) -> Result<RunAheadCore, ErroHandle> {
+        let retro_core = self.try_get_retro_core_ctx()?;
+
+        let loaded = match &self.loaded_content {
+            Some(loaded) => loaded,
+            None => return Err(ErroHandle::new("Nenhuma rom foi carregada")),
+        };
+
+        //as duas instancias nao podem dividir o mesmo contexto da GPU
+        if retro_core
+            .av_info
+            .video
+            .graphic_api
+            .hw_render
+            .load(Ordering::SeqCst)
+        {
+            return Err(ErroHandle::new(
+                "A segunda instancia nao suporta cores que renderizam na GPU",
+            ));
+        }
+
+        let dir = PathBuf::from(&*loaded.paths.temps)
+            .join("run_ahead")
+            .join(format!(
+                "{}_{}",
+                std::process::id(),
+                RUN_AHEAD_DIR_COUNT.fetch_add(1, Ordering::SeqCst)
+            ));
+
+        //uma pasta com o mesmo nome pode ter sobrado de um processo que foi encerrado a força
+        let _ = fs::remove_dir_all(&dir);
+
+        match self.create_run_ahead_core_in(&dir, loaded) {
+            Ok(core) => Ok(RunAheadCore { core, dir }),
+            Err(e) => {
+                let _ = fs::remove_dir_all(&dir);
+                Err(e)
+            }
+        }
+    }
+
+    fn create_run_ahead_core_in(
+        &self,
+        dir: &Path,
+        loaded: &LoadedContent,
+    ) -> Result<RetroCoreIns, ErroHandle> {
+        let retro_av = self.try_get_retro_av_ctx()?;
+
+        //o sistema retorna a mesma biblioteca quando ela e carregada duas vezes, então as variáveis
+        //globais do core seriam compartilhadas. Por isso a segunda instancia usa uma copia do core
+        let core_copy = dir.join(
+            PathBuf::from(&loaded.core_path)
+                .file_name()
+                .ok_or(ErroHandle::new("erro ao tentar recuperar o nome do core"))?,
+        );
+
+        let save_dir = dir.join("save");
+        fs::create_dir_all(&save_dir)?;
+        fs::copy(&loaded.core_path, &core_copy)?;
+
+        let paths = RetroPaths {
+            save: Arc::new(save_dir.to_string_lossy().to_string()),
+            ..loaded.paths.clone()
+        };
+
+        let (video, audio) = retro_av.get_core_cb();
+
+        let callbacks = RetroEnvCallbacks {
+            controller: Box::new(self.controller_ctx.get_core_cb()),
+            video,
+            audio,
+            message: Box::new(IgnoredMessages),
+            logger: Box::new(MultiLogger::new(self.log_filter.clone()).add(Box::new(StdoutLogger))),
+            vfs: Some(create_observed_vfs(
+                &self.vfs_backend,
+                &self.vfs_observer,
+                &paths,
+                &loaded.content,
+            )?),
+            sensor: Some(Box::new(SensorHandle::new(
+                self.sensor_provider.clone(),
+                self.controller_ctx.get_sensor_cb(),
+            ))),
+            location: None,
+            camera: None,
+            power: Some(Box::new(PowerHandle::new(self.power_provider.clone()))),
+        };
+
+        let core = RetroCore::new(
+            &core_copy.to_string_lossy(),
+            paths,
+            callbacks,
+            GraphicApi::default(),
+            loaded.language,
+        )?;
+
+        if let Err(e) = load_content(&core, &loaded.content) {
+            let _ = core.de_init();
+            return Err(e);
+        }
+
+        for gamepad in self.controller_ctx.get_list()? {
+            let device = Device::from_gamepad(&gamepad);
+            core.connect_controller(device.retro_port, device.retro_type)?;
+        }
+
+        Ok(core)
+    }
+
+    fn check_serialize_support(&mut self) {
+        let retro_core = match &self.retro_core {
+            Some(retro_core) => retro_core,
+            None => return,
+        };
+
+        self.serialize_supported = match retro_core.get_serialize_size() {
+            Ok(size) => size > 0,
+            Err(e) => {
+                println!("check_serialize_support -> {:?}", e);
+                false
+            }
+        };
+
+        if self.serialize_supported {
+            return;
+        }
+
+        if self.rewind.get_config().enabled {
+            println!("rewind -> o core atual nao suporta states, o rewind foi desabilitado");
+        }
+
+        if self.run_ahead.frames > 0 {
+            println!("run_ahead -> o core atual nao suporta states, o run-ahead foi desabilitado");
+        }
+    }
+
+    //os logs da sessão ficam em "temps/logs/{core}/{rom}.log"
+    fn create_logger(
+        &self,
+        core_path: &str,
+        content: &GameContent,
+        paths: &RetroPaths,
+    ) -> MultiLogger {
+        let logger = MultiLogger::new(self.log_filter.clone())
+            .add(Box::new(StdoutLogger))
+            .add(Box::new(self.log_memory.clone()));
+
+        let core_name = get_file_stem(core_path);
+        let rom_name = match content {
+            GameContent::Rom(rom_path) => get_file_stem(rom_path),
+            GameContent::Subsystem(_, roms) => roms
+                .iter()
+                .find(|rom| !rom.is_empty())
+                .map_or("subsystem".to_string(), |rom| get_file_stem(rom)),
+            GameContent::NoContent => "no_content".to_string(),
+        };
+
+        match FileLogger::for_session(paths, &core_name, &rom_name) {
+            Ok(file_logger) => logger.add(Box::new(file_logger)),
+            Err(e) => {
+                println!("create_logger -> {:?}", e);
+                logger
+            }
+        }
+    }
+
+    //as mensagens sempre vão para o listener, mas so aparecem na tela se o OSD estiver habilitado
+    fn dispatch_core_messages(&self) {
+        let messages: Vec<RetroMessage> = match self.pending_messages.try_load() {
+            Ok(mut pending) => pending.drain(..).collect(),
+            Err(e) => {
+                println!("dispatch_core_messages -> {:?}", e);
+                return;
+            }
+        };
+
+        if messages.is_empty() {
+            return;
+        }
+
+        let show_on_screen = self.osd_enabled.load(Ordering::SeqCst);
+
+        for message in messages {
+            if show_on_screen && message.show_on_screen() {
+                if let Some(retro_av) = &self.retro_av {
+                    if let Err(e) = retro_av.show_message(&message) {
+                        println!("show_message -> {:?}", e);
+                    }
+                }
+            }
+
+            match self.message_listener.try_load() {
+                Ok(listener) => {
+                    if let Some(listener) = &*listener {
+                        listener.on_message(message);
+                    }
+                }
+                Err(e) => println!("on_message -> {:?}", e),
+            }
+        }
+    }
+
     pub fn try_render_frame(&mut self) -> Result<(), ErroHandle> {
         if let Some(retro_core) = &self.retro_core {
+            //as opções podem ser alteradas com o jogo pausado
+            retro_core.update_option_display()?;
+
             if let Some(retro_av) = &mut self.retro_av {
-                if !retro_av.sync() || self.pause_request_new_frames {
+                let frame_advance = self.pause_request_new_frames && self.frame_advance;
+
+                if !frame_advance && (!retro_av.sync() || self.pause_request_new_frames) {
                     return Ok(());
                 }
 
+                if frame_advance {
+                    self.frame_advance = false;
+                    //o tempo desde o ultimo frame nao deve ser enviado para o core
+                    retro_core.reset_frame_time()?;
+                }
+
+                //voltar no tempo faria o filme perder a sincronia com o jogo
+                let movie_mode = retro_core.movie.get_mode()?;
+                let rewinding =
+                    self.rewinding && self.serialize_supported && movie_mode == MovieMode::Idle;
+
+                if rewinding {
+                    //quando nao houver mais states o jogo fica parado no mais antigo
+                    match self.rewind.pop()? {
+                        Some(state) => retro_core.unserialize(state)?,
+                        None => return Ok(()),
+                    }
+                }
+
+                // Informa ao core quanto tempo passou desde o ultimo frame
+                retro_core.update_frame_time()?;
                 // Pede para core gerar novos buffers de video e audio
-                retro_core.run()?;
+                //a segunda instancia do run-ahead leria os inputs do jogador no lugar dos do filme
+                if rewinding
+                    || !self.serialize_supported
+                    || self.run_ahead.frames == 0
+                    || movie_mode == MovieMode::Playing
+                {
+                    retro_core.run()?;
+                } else if let Some(run_ahead) = &self.run_ahead_core {
+                    retro_core.run_ahead_with(&run_ahead.core, self.run_ahead.frames)?;
+                } else {
+                    retro_core.run_ahead(self.run_ahead.frames)?;
+                }
+
+                if !rewinding && self.serialize_supported && self.rewind.should_capture() {
+                    match retro_core.serialize() {
+                        Ok(state) => self.rewind.push(state),
+                        Err(e) => println!("rewind -> {:?}", e),
+                    }
+                }
+
+                //cores que usam RETRO_ENVIRONMENT_SET_AUDIO_CALLBACK geram o áudio separado do frame
+                let audio_status = retro_av.get_audio_buffer_status();
+                retro_core.report_audio_buffer_status(audio_status)?;
+
+                if !audio_status.active || audio_status.occupancy < 100 {
+                    retro_core.run_audio_callback()?;
+                }
+
                 // Exibe os buffers gerados pelo core
                 retro_av.get_new_frame()?;
             }
+
+            self.dispatch_core_messages();
+
+            //evita que o progresso do jogador seja perdido caso o processo seja encerrado
+            if self.last_battery_save.elapsed() >= Duration::from_secs(BATTERY_SAVE_INTERVAL) {
+                self.last_battery_save = Instant::now();
+
+                if let Err(e) = retro_core.save_battery() {
+                    println!("save_battery -> {:?}", e);
+                }
+            }
         } else {
             //WITHOUT THIS, WI HAVE A HIGH CPU UTILIZATION!
             thread::sleep(Duration::from_millis(THREAD_SLEEP_TIME));
@@ -252,6 +955,20 @@ impl ThreadState {
     }
 }
 
+fn load_content(retro_core: &RetroCore, content: &GameContent) -> Result<Arc<AvInfo>, ErroHandle> {
+    match content {
+        GameContent::Rom(rom_path) => retro_core.load_game(rom_path),
+        GameContent::Subsystem(ident, roms) => retro_core.load_game_special(ident, roms),
+        GameContent::NoContent => retro_core.load_no_game(),
+    }
+}
+
+fn get_file_stem(path: &str) -> String {
+    PathBuf::from(path)
+        .file_stem()
+        .map_or(path.to_string(), |stem| stem.to_string_lossy().to_string())
+}
+
 impl Drop for ThreadState {
     fn drop(&mut self) {
         self.channel_notify.clear_game_stack();
@@ -260,7 +977,13 @@ impl Drop for ThreadState {
         //thread de inputs novamente.
         let _ = self.controller_ctx.resume_thread_events();
 
+        //o filme sendo gravado e salvo antes do core ser descarregado
+        self.stop_movie();
+
         //retro-core nao implementa drop então chamar de_init() depois de terminar de usar é necessário.
+        //a segunda instancia e descarregada antes, ja que ela tem uma copia do core principal
+        self.run_ahead_core.take();
+
         if let Some(core) = self.retro_core.take() {
             let _ = core.de_init();
         }
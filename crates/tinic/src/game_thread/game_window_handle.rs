@@ -44,6 +44,17 @@ pub fn game_window_handle(state: &mut ThreadState) {
                 ..
             } => channel_notify.notify_game_stack(GameStackCommand::Reset),
 
+            //o jogo volta no tempo enquanto a tecla estiver pressionada
+            Event::KeyDown {
+                keycode: Some(Keycode::Backspace),
+                repeat: false,
+                ..
+            } => channel_notify.notify_game_stack(GameStackCommand::Rewind(true)),
+            Event::KeyUp {
+                keycode: Some(Keycode::Backspace),
+                ..
+            } => channel_notify.notify_game_stack(GameStackCommand::Rewind(false)),
+
             Event::KeyDown {
                 keycode: Some(Keycode::F11),
                 ..
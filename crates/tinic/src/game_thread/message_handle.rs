@@ -0,0 +1,37 @@
+use generics::{erro_handle::ErroHandle, types::ArcTMuxte};
+use retro_core::{message::RetroMessage, RetroMessageEnvCallbacks};
+use std::fmt::Debug;
+
+/// Recebe as mensagens que o core deseja mostrar para o jogador, como "Disk 2 inserted".
+pub trait MessageListener: Debug + Send {
+    #[doc = "chamada na thread do jogo depois que o frame e gerado"]
+    fn on_message(&self, message: RetroMessage);
+}
+
+//o core envia as mensagens dentro do retro_run, então elas sao guardadas aqui
+//e entregues pela thread do jogo depois que o frame termina
+pub struct CoreMessageQueue {
+    pending: ArcTMuxte<Vec<RetroMessage>>,
+}
+
+impl CoreMessageQueue {
+    pub fn new(pending: ArcTMuxte<Vec<RetroMessage>>) -> Self {
+        Self { pending }
+    }
+}
+
+impl RetroMessageEnvCallbacks for CoreMessageQueue {
+    fn message_callback(&self, message: RetroMessage) -> Result<(), ErroHandle> {
+        self.pending.try_load()?.push(message);
+        Ok(())
+    }
+}
+
+//a segunda instancia do run-ahead envia as mesmas mensagens que o core principal
+pub struct IgnoredMessages;
+
+impl RetroMessageEnvCallbacks for IgnoredMessages {
+    fn message_callback(&self, _message: RetroMessage) -> Result<(), ErroHandle> {
+        Ok(())
+    }
+}
@@ -1,5 +1,10 @@
+mod camera_handle;
 mod game_thread_channel;
 pub mod game_thread_handle;
 mod game_thread_state;
 mod game_window_handle;
+pub mod message_handle;
+mod power_handle;
+mod sensor_handle;
 mod stack_commands_handle;
+pub mod vfs_handle;
@@ -0,0 +1,25 @@
+use generics::{erro_handle::ErroHandle, types::ArcTMuxte};
+use retro_core::power::{DevicePower, PowerProvider};
+
+pub type SharedPowerProvider = ArcTMuxte<Option<Box<dyn PowerProvider + Send>>>;
+
+//o provedor pode ser trocado pelo Tinic enquanto o jogo esta em execução
+#[derive(Debug)]
+pub struct PowerHandle {
+    provider: SharedPowerProvider,
+}
+
+impl PowerHandle {
+    pub fn new(provider: SharedPowerProvider) -> Self {
+        Self { provider }
+    }
+}
+
+impl PowerProvider for PowerHandle {
+    fn get_power(&self) -> Result<Option<DevicePower>, ErroHandle> {
+        match &*self.provider.try_load()? {
+            Some(provider) => provider.get_power(),
+            None => Ok(None),
+        }
+    }
+}
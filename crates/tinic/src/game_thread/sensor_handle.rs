@@ -0,0 +1,89 @@
+use generics::{erro_handle::ErroHandle, types::ArcTMuxte};
+use libretro_sys::binding_libretro::retro_sensor_action;
+use retro_controllers::GamepadTiltSensor;
+use retro_core::{
+    location::{LocationProvider, RetroLocation},
+    sensor::SensorProvider,
+};
+
+pub type SharedSensorProvider = ArcTMuxte<Option<Box<dyn SensorProvider + Send>>>;
+pub type SharedLocationProvider = ArcTMuxte<Option<Box<dyn LocationProvider + Send>>>;
+
+//o provedor pode ser trocado pelo Tinic enquanto o jogo esta em execução, então o core
+//recebe esse intermediário no lugar dele
+#[derive(Debug)]
+pub struct SensorHandle {
+    provider: SharedSensorProvider,
+    gamepad: GamepadTiltSensor,
+}
+
+impl SensorHandle {
+    pub fn new(provider: SharedSensorProvider, gamepad: GamepadTiltSensor) -> Self {
+        Self { provider, gamepad }
+    }
+}
+
+impl SensorProvider for SensorHandle {
+    fn set_sensor_state(
+        &self,
+        port: u32,
+        action: retro_sensor_action,
+        rate: u32,
+    ) -> Result<bool, ErroHandle> {
+        //os dois precisam saber quais sensores estão ligados caso o provedor seja removido
+        let gamepad_supported = self.gamepad.set_sensor_state(port, action, rate)?;
+
+        match &*self.provider.try_load()? {
+            Some(provider) => provider.set_sensor_state(port, action, rate),
+            None => Ok(gamepad_supported),
+        }
+    }
+
+    fn get_sensor_input(&self, port: u32, id: u32) -> Result<f32, ErroHandle> {
+        match &*self.provider.try_load()? {
+            Some(provider) => provider.get_sensor_input(port, id),
+            None => self.gamepad.get_sensor_input(port, id),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct LocationHandle {
+    provider: SharedLocationProvider,
+}
+
+impl LocationHandle {
+    pub fn new(provider: SharedLocationProvider) -> Self {
+        Self { provider }
+    }
+}
+
+impl LocationProvider for LocationHandle {
+    fn start(&self) -> Result<bool, ErroHandle> {
+        match &*self.provider.try_load()? {
+            Some(provider) => provider.start(),
+            None => Ok(false),
+        }
+    }
+
+    fn stop(&self) -> Result<(), ErroHandle> {
+        match &*self.provider.try_load()? {
+            Some(provider) => provider.stop(),
+            None => Ok(()),
+        }
+    }
+
+    fn get_position(&self) -> Result<Option<RetroLocation>, ErroHandle> {
+        match &*self.provider.try_load()? {
+            Some(provider) => provider.get_position(),
+            None => Ok(None),
+        }
+    }
+
+    fn set_interval(&self, interval_ms: u32, interval_distance: u32) -> Result<(), ErroHandle> {
+        match &*self.provider.try_load()? {
+            Some(provider) => provider.set_interval(interval_ms, interval_distance),
+            None => Ok(()),
+        }
+    }
+}
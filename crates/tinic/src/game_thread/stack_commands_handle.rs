@@ -1,7 +1,9 @@
 use super::game_thread_state::ThreadState;
 use crate::thread_stack::game_stack::GameStackCommand::{
-    DeviceConnected, DisableFullScreen, EnableFullScreen, LoadGame, LoadState, Pause, Quit, Reset,
-    Resume, SaveState,
+    DeviceConnected, DisableFullScreen, EjectDisk, EnableFullScreen, FrameAdvance, GetCheats,
+    GetInputDescriptors, GetPerfReport, InsertDisk, LoadGame, LoadState, Pause, PlayMovie, Quit,
+    Reset, ResetCheats, Resume, Rewind, SaveState, SetCheat, SetDiskIndex, SetRewindConfig,
+    SetRunAheadConfig, SetSpeed, StartMovieRecording, StopMovie, VerifyMovie,
 };
 use generics::erro_handle::ErroHandle;
 
@@ -9,15 +11,36 @@ pub fn stack_commands_handle(state: &mut ThreadState) -> Result<(), ErroHandle>
     for cmd in state.channel_notify.read_game_stack() {
         match cmd {
             Quit => state.quit(),
-            LoadGame(core_path, rom_path, paths) => state.load_game(core_path, rom_path, paths)?,
+            LoadGame(core_path, content, paths, av_mode, language) => {
+                state.load_game(core_path, content, paths, av_mode, language)?
+            }
             SaveState(slot) => state.save_state(slot)?,
             LoadState(slot) => state.load_state(slot)?,
             Pause => state.pause()?,
             Resume => state.resume(),
+            FrameAdvance => state.frame_advance()?,
+            SetSpeed(speed) => state.set_speed(speed)?,
             Reset => state.reset()?,
             EnableFullScreen => state.enable_full_screen()?,
             DisableFullScreen => state.disable_full_screen()?,
             DeviceConnected(device) => state.connect_device(device)?,
+            SetCheat(index, enabled) => state.set_cheat(index, enabled),
+            ResetCheats => state.reset_cheats(),
+            GetCheats => state.send_cheats()?,
+            EjectDisk => state.eject_disk(),
+            InsertDisk => state.insert_disk(),
+            SetDiskIndex(index) => state.set_disk_index(index),
+            GetInputDescriptors => state.send_input_descriptors()?,
+            GetPerfReport => state.send_perf_report()?,
+            Rewind(rewinding) => state.set_rewinding(rewinding),
+            SetRewindConfig(config) => state.set_rewind_config(config),
+            SetRunAheadConfig(config) => state.set_run_ahead_config(config),
+            StartMovieRecording(path, origin, hash_config) => {
+                state.start_movie_recording(path, origin, hash_config)
+            }
+            PlayMovie(path) => state.play_movie(path),
+            StopMovie => state.stop_movie(),
+            VerifyMovie(path) => state.verify_movie(path),
         }
     }
 
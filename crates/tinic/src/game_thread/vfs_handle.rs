@@ -0,0 +1,123 @@
+use crate::thread_stack::game_stack::GameContent;
+use generics::{erro_handle::ErroHandle, retro_paths::RetroPaths, types::ArcTMuxte};
+use retro_core::vfs::{ObservedVfs, RetroVfs, StdVfs, VfsAccess, VfsObserver, ZipVfs};
+use std::{
+    fmt::{Debug, Formatter},
+    path::PathBuf,
+    sync::Arc,
+};
+
+pub type SharedVfsBackend = ArcTMuxte<VfsBackend>;
+pub type SharedVfsObserver = ArcTMuxte<Option<Arc<dyn VfsObserver>>>;
+
+#[derive(Clone, Default)]
+pub enum VfsBackend {
+    #[doc = "o core so pode acessar as pastas do [crate::RetroPaths] e as pastas onde estão as roms"]
+    #[default]
+    Sandboxed,
+    #[doc = "o core pode acessar qualquer arquivo do sistema"]
+    Unrestricted,
+    #[doc = "os arquivos de *archive* aparecem dentro de *mount_dir* como somente leitura. os outros
+    caminhos usam o mesmo acesso de [VfsBackend::Sandboxed]"]
+    Archive {
+        archive: PathBuf,
+        mount_dir: PathBuf,
+    },
+    #[doc = "usado por todos os cores carregados, como um [retro_core::vfs::MemoryVfs] em testes"]
+    Custom(Arc<dyn RetroVfs>),
+}
+
+impl Debug for VfsBackend {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VfsBackend::Sandboxed => write!(f, "Sandboxed"),
+            VfsBackend::Unrestricted => write!(f, "Unrestricted"),
+            VfsBackend::Archive { archive, mount_dir } => f
+                .debug_struct("Archive")
+                .field("archive", archive)
+                .field("mount_dir", mount_dir)
+                .finish(),
+            VfsBackend::Custom(_) => write!(f, "Custom"),
+        }
+    }
+}
+
+impl VfsBackend {
+    pub fn create(
+        &self,
+        paths: &RetroPaths,
+        content: &GameContent,
+    ) -> Result<Arc<dyn RetroVfs>, ErroHandle> {
+        let vfs: Arc<dyn RetroVfs> = match self {
+            VfsBackend::Sandboxed => Arc::new(create_sandboxed_vfs(paths, content)),
+            VfsBackend::Unrestricted => Arc::new(StdVfs::new()),
+            VfsBackend::Archive { archive, mount_dir } => Arc::new(ZipVfs::new(
+                archive,
+                mount_dir,
+                Box::new(create_sandboxed_vfs(paths, content)),
+            )?),
+            VfsBackend::Custom(vfs) => vfs.clone(),
+        };
+
+        Ok(vfs)
+    }
+}
+
+//o core so pode acessar as pastas do RetroPaths e as pastas onde estão as roms
+fn create_sandboxed_vfs(paths: &RetroPaths, content: &GameContent) -> StdVfs {
+    let mut vfs = StdVfs::sandboxed(paths);
+
+    let roms: Vec<&String> = match content {
+        GameContent::Rom(rom_path) => vec![rom_path],
+        GameContent::Subsystem(_, roms) => roms.iter().collect(),
+        GameContent::NoContent => Vec::new(),
+    };
+
+    for rom in roms {
+        if let Some(rom_dir) = PathBuf::from(rom).parent() {
+            vfs.allow_dir(rom_dir);
+        }
+    }
+
+    vfs
+}
+
+//o observer pode ser trocado pelo Tinic enquanto o jogo esta em execução
+#[derive(Debug)]
+pub struct VfsObserverHandle {
+    observer: SharedVfsObserver,
+}
+
+impl VfsObserverHandle {
+    pub fn new(observer: SharedVfsObserver) -> Self {
+        Self { observer }
+    }
+}
+
+impl VfsObserver for VfsObserverHandle {
+    fn on_access(&self, access: &VfsAccess) {
+        //o observer e clonado para que o mutex nao fique travado enquanto ele e chamado
+        let observer = match self.observer.try_load() {
+            Ok(observer) => observer.clone(),
+            Err(_) => return,
+        };
+
+        if let Some(observer) = observer {
+            observer.on_access(access);
+        }
+    }
+}
+
+pub fn create_observed_vfs(
+    backend: &SharedVfsBackend,
+    observer: &SharedVfsObserver,
+    paths: &RetroPaths,
+    content: &GameContent,
+) -> Result<Arc<dyn RetroVfs>, ErroHandle> {
+    let vfs = backend.try_load()?.create(paths, content)?;
+
+    Ok(Arc::new(ObservedVfs::new(
+        vfs,
+        Arc::new(VfsObserverHandle::new(observer.clone())),
+    )))
+}
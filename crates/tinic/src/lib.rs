@@ -11,10 +11,39 @@ mod tinic;
 
 pub use tokio;
 
+pub use game_thread::message_handle::MessageListener;
+pub use game_thread::vfs_handle::VfsBackend;
+
 pub use generics::retro_paths::RetroPaths;
+pub use libretro_sys::binding_libretro::{
+    retro_language, retro_log_level, retro_power_state, retro_sensor_action,
+};
+pub use retro_av::{ImageDirCamera, RetroAvMode};
 pub use retro_controllers::{
     devices_manager::{Device, DeviceListener, DeviceStateListener},
     GamepadKeyMap, RetroController,
 };
-pub use retro_core::{args_manager, test_tools};
+pub use retro_core::{
+    args_manager,
+    camera::{CameraFrame, CameraSource, TestPatternCamera},
+    cheat_manager::Cheat,
+    location::{FixedLocationProvider, LocationProvider, RetroLocation},
+    logger::RetroLogEntry,
+    memory_manager::MemoryRegion,
+    message::RetroMessage,
+    movie_manager::{
+        InputSample, Movie, MovieDesync, MovieMode, MovieOrigin, MovieStart, MovieVerification,
+        StateHash, StateHashConfig, StateHashSource, MOVIE_VERSION,
+    },
+    option_manager::OptionVisibilityListener,
+    perf_manager::{format_perf_report, PerfCounterReport},
+    power::{DevicePower, MockPowerProvider, PowerProvider, SysfsPowerProvider},
+    rewind_manager::RewindConfig,
+    run_ahead::{RunAheadConfig, RunAheadMode},
+    sensor::{ConstantSensorProvider, SensorProvider},
+    speed::EmulationSpeed,
+    system::InputDescriptor,
+    test_tools,
+    vfs::{MemoryVfs, RetroVfs, VfsAccess, VfsObserver, VfsOperation},
+};
 pub use tinic::Tinic;
@@ -1,22 +1,72 @@
 use crate::thread_stack::model_stack::{ModelStackManager, RetroStackFn};
 use generics::retro_paths::RetroPaths;
+use libretro_sys::binding_libretro::retro_language;
+use retro_av::RetroAvMode;
 use retro_controllers::devices_manager::Device;
+use retro_core::{
+    movie_manager::{MovieOrigin, StateHashConfig},
+    rewind_manager::RewindConfig,
+    run_ahead::RunAheadConfig,
+    speed::EmulationSpeed,
+};
+use std::path::PathBuf;
 
 //facilita o reconhecimento dos atributos usando a intellisense da ide
 type CorePath = String;
 type RomPath = String;
 type Slot = usize;
+type CheatIndex = usize;
+type DiskIndex = u32;
+type SubsystemIdent = String;
+type MoviePath = PathBuf;
 
 #[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GameContent {
+    Rom(RomPath),
+    #[doc = "roms carregadas juntas por um subsistema do core (ex: Super Game Boy)"]
+    Subsystem(SubsystemIdent, Vec<RomPath>),
+    #[doc = "inicia apenas o core, para os que suportam RETRO_ENVIRONMENT_SET_SUPPORT_NO_GAME"]
+    NoContent,
+}
+
+#[derive(Clone, Debug, PartialEq)]
 pub enum GameStackCommand {
-    LoadGame(CorePath, RomPath, RetroPaths),
+    LoadGame(
+        CorePath,
+        GameContent,
+        RetroPaths,
+        RetroAvMode,
+        retro_language,
+    ),
     SaveState(Slot),
     LoadState(Slot),
     Pause,
     Resume,
+    #[doc = "pausa o jogo, se necessário, e gera apenas um frame"]
+    FrameAdvance,
+    SetSpeed(EmulationSpeed),
     EnableFullScreen,
     DisableFullScreen,
     DeviceConnected(Device),
+    SetCheat(CheatIndex, bool),
+    ResetCheats,
+    GetCheats,
+    EjectDisk,
+    InsertDisk,
+    SetDiskIndex(DiskIndex),
+    GetInputDescriptors,
+    GetPerfReport,
+    #[doc = "true enquanto o jogador segura o botão de voltar no tempo"]
+    Rewind(bool),
+    SetRewindConfig(RewindConfig),
+    SetRunAheadConfig(RunAheadConfig),
+    #[doc = "o filme e salvo em *MoviePath* quando a gravação termina"]
+    StartMovieRecording(MoviePath, MovieOrigin, Option<StateHashConfig>),
+    PlayMovie(MoviePath),
+    #[doc = "reproduz o filme sem exibir os frames e compara os hashes gravados"]
+    VerifyMovie(MoviePath),
+    #[doc = "encerra a gravação ou a reprodução do filme atual"]
+    StopMovie,
     Reset,
     Quit,
 }
@@ -1,5 +1,8 @@
 use crate::thread_stack::model_stack::{ModelStackManager, RetroStackFn};
-use retro_core::option_manager::OptionManager;
+use retro_core::{
+    cheat_manager::Cheat, movie_manager::MovieVerification, option_manager::OptionManager,
+    perf_manager::PerfCounterReport, system::InputDescriptor,
+};
 use std::path::PathBuf;
 use std::sync::Arc;
 
@@ -11,6 +14,10 @@ pub enum MainStackCommand {
     GameLoaded(Option<Arc<OptionManager>>),
     GameStateSaved(Option<(SavePath, SaveImg)>),
     SaveStateLoaded(bool),
+    Cheats(Vec<Cheat>),
+    InputDescriptors(Vec<InputDescriptor>),
+    PerfReport(Vec<PerfCounterReport>),
+    MovieVerified(Option<MovieVerification>),
     QuitSusses(bool),
 }
 
@@ -1,12 +1,36 @@
 use crate::{
-    game_thread::game_thread_handle::GameThread,
+    game_thread::{
+        game_thread_handle::GameThread, message_handle::MessageListener, vfs_handle::VfsBackend,
+    },
     generics::{erro_handle::ErroHandle, retro_paths::RetroPaths},
+    libretro_sys::binding_libretro::{retro_language, retro_log_level},
+    retro_av::RetroAvMode,
     retro_controllers::{
         devices_manager::{Device, DeviceListener},
         RetroController,
     },
-    retro_core::{option_manager::OptionManager, test_tools},
-    thread_stack::main_stack::{SaveImg, SavePath},
+    retro_core::{
+        camera::CameraSource,
+        cheat_manager::Cheat,
+        language::get_system_language,
+        location::LocationProvider,
+        logger::RetroLogEntry,
+        movie_manager::{MovieOrigin, MovieVerification, StateHashConfig},
+        option_manager::OptionManager,
+        perf_manager::PerfCounterReport,
+        power::PowerProvider,
+        rewind_manager::RewindConfig,
+        run_ahead::RunAheadConfig,
+        sensor::SensorProvider,
+        speed::EmulationSpeed,
+        system::InputDescriptor,
+        test_tools,
+        vfs::VfsObserver,
+    },
+    thread_stack::{
+        game_stack::GameContent,
+        main_stack::{SaveImg, SavePath},
+    },
     tinic_super::{core_info::CoreInfo, core_info_helper::CoreInfoHelper},
 };
 use std::path::PathBuf;
@@ -17,6 +41,8 @@ pub struct Tinic {
     pub core_options: Option<Arc<OptionManager>>,
     game_thread: Arc<GameThread>,
     retro_paths: Option<RetroPaths>,
+    av_mode: RetroAvMode,
+    language: retro_language,
 }
 
 impl Drop for Tinic {
@@ -39,26 +65,123 @@ impl Tinic {
             core_options: None,
             controller,
             retro_paths: None,
+            av_mode: RetroAvMode::default(),
+            language: get_system_language(),
         })
     }
 
+    #[doc = "recebe as mensagens enviadas pelo core, como \"Disk 2 inserted\". use None para remover o listener"]
+    pub fn set_message_listener(&self, listener: Option<Box<dyn MessageListener>>) {
+        self.game_thread.set_message_listener(listener);
+    }
+
+    #[doc = "valores do acelerômetro, giroscópio e iluminação enviados ao core. quando None os
+    sensores de movimento sao simulados pelo analógico direito dos gamepads"]
+    pub fn set_sensor_provider(&self, provider: Option<Box<dyn SensorProvider + Send>>) {
+        self.game_thread.set_sensor_provider(provider);
+    }
+
+    #[doc = "posição do jogador enviada ao core. quando None o core nao recebe a interface de
+    localização na próxima vez que uma rom for carregada"]
+    pub fn set_location_provider(&self, provider: Option<Box<dyn LocationProvider + Send>>) {
+        self.game_thread.set_location_provider(provider);
+    }
+
+    #[doc = "imagens enviadas aos cores que usam a câmera, como o Game Boy Camera. veja [crate::ImageDirCamera]
+    e [crate::TestPatternCamera]. quando None o core nao recebe a interface da câmera na próxima vez que
+    uma rom for carregada"]
+    pub fn set_camera_source(&self, source: Option<Box<dyn CameraSource + Send>>) {
+        self.game_thread.set_camera_source(source);
+    }
+
+    #[doc = "estado da bateria informado aos cores que simulam a bateria dos portáteis. o padrão e
+    [crate::SysfsPowerProvider], que lê o estado do aparelho no Linux. use None para desativar"]
+    pub fn set_power_provider(&self, provider: Option<Box<dyn PowerProvider + Send>>) {
+        self.game_thread.set_power_provider(provider);
+    }
+
+    #[doc = "define como o core acessa os arquivos pela interface VFS da libretro. o padrão e
+    [crate::VfsBackend::Sandboxed]. a mudança vale a partir da próxima rom carregada"]
+    pub fn set_vfs_backend(&self, backend: VfsBackend) {
+        self.game_thread.set_vfs_backend(backend);
+    }
+
+    #[doc = "informa cada arquivo que o core abrir, remover ou listar pela interface VFS. pode ser
+    trocado enquanto o jogo esta em execução. use None para desativar"]
+    pub fn set_vfs_observer(&self, observer: Option<Arc<dyn VfsObserver>>) {
+        self.game_thread.set_vfs_observer(observer);
+    }
+
+    #[doc = "exibe as mensagens do core por cima do jogo. habilitado por padrão"]
+    pub fn set_osd_enabled(&self, enabled: bool) {
+        self.game_thread.set_osd_enabled(enabled);
+    }
+
     pub fn set_tinic_dir(&mut self, retro_paths: RetroPaths) {
         self.retro_paths.replace(retro_paths);
     }
 
+    #[doc = "define como os frames e o áudio serão exibidos nas próximas vezes que uma rom for carregada.
+    use RetroAvMode::Headless para executar em servidores ou em CI, onde nao ha um display"]
+    pub fn set_av_mode(&mut self, av_mode: RetroAvMode) {
+        self.av_mode = av_mode;
+    }
+
+    #[doc = "idioma informado ao core e usado nos textos das opções. o padrão e o idioma do sistema.
+    so tem efeito na próxima vez que uma rom for carregada"]
+    pub fn set_language(&mut self, language: retro_language) {
+        self.language = language;
+    }
+
+    pub fn get_language(&self) -> retro_language {
+        self.language
+    }
+
+    #[doc = "nível mínimo das mensagens do core que serão registradas. pode ser alterado durante o jogo"]
+    pub fn set_log_level(&self, level: retro_log_level) {
+        self.game_thread.log_filter.set(level);
+    }
+
+    pub fn get_log_level(&self) -> retro_log_level {
+        self.game_thread.log_filter.get()
+    }
+
+    #[doc = "ultimas mensagens enviadas pelo core, da mais antiga para a mais nova. os logs completos
+    ficam na pasta *temps/logs* de RetroPaths"]
+    pub fn get_core_logs(&self) -> Vec<RetroLogEntry> {
+        self.game_thread.log_memory.get_entries()
+    }
+
+    pub fn clear_core_logs(&self) {
+        self.game_thread.log_memory.clear();
+    }
+
     pub async fn load_game(&mut self, core_path: &str, rom_path: &str) -> Result<bool, ErroHandle> {
-        let retro_path = self.try_get_retro_path()?.clone();
-        self.game_thread.start(self.controller.clone())?;
+        self.load_content(core_path, GameContent::Rom(rom_path.to_string()))
+            .await
+    }
 
-        let core_options = self
-            .game_thread
-            .channel
-            .load_game(core_path, rom_path, retro_path)
-            .await;
+    #[doc = "carrega varias roms usando um subsistema do core, como o Super Game Boy ou o Sufami Turbo.
+    as roms devem seguir a ordem definida pelo subsistema; use uma string vazia para pular uma rom opcional"]
+    pub async fn load_game_special(
+        &mut self,
+        core_path: &str,
+        subsystem_ident: &str,
+        roms: &[&str],
+    ) -> Result<bool, ErroHandle> {
+        let roms = roms.iter().map(|rom| rom.to_string()).collect();
 
-        self.core_options = core_options;
+        self.load_content(
+            core_path,
+            GameContent::Subsystem(subsystem_ident.to_string(), roms),
+        )
+        .await
+    }
 
-        Ok(self.core_options.is_some())
+    #[doc = "inicia um core que nao precisa de uma rom, como o 2048 ou o DOSBox.
+    retorna false se o core nao suportar ser iniciado sem conteúdo"]
+    pub async fn load_no_game(&mut self, core_path: &str) -> Result<bool, ErroHandle> {
+        self.load_content(core_path, GameContent::NoContent).await
     }
 
     pub fn pause(&self) {
@@ -69,6 +192,16 @@ impl Tinic {
         self.game_thread.channel.resume_game();
     }
 
+    #[doc = "pausa o jogo e gera apenas o próximo frame. use [Tinic::resume] para voltar ao normal"]
+    pub fn frame_advance(&self) {
+        self.game_thread.channel.frame_advance();
+    }
+
+    #[doc = "acelera (fast-forward) ou deixa o jogo em câmera lenta. o core também e informado da velocidade"]
+    pub fn set_speed(&self, speed: EmulationSpeed) {
+        self.game_thread.channel.set_speed(speed);
+    }
+
     pub async fn save_state(&self, slot: usize) -> Option<(SavePath, SaveImg)> {
         self.game_thread.channel.save_state(slot).await
     }
@@ -85,6 +218,94 @@ impl Tinic {
         self.game_thread.channel.reset_game();
     }
 
+    #[doc = "o jogo volta no tempo enquanto *rewinding* for true. O rewind precisa ser habilitado
+    antes em [Tinic::set_rewind_config]"]
+    pub fn rewind(&self, rewinding: bool) {
+        self.game_thread.channel.rewind(rewinding);
+    }
+
+    #[doc = "o rewind e desabilitado automaticamente para cores que nao suportam states"]
+    pub fn set_rewind_config(&self, config: RewindConfig) {
+        self.game_thread.channel.set_rewind_config(config);
+    }
+
+    #[doc = "gera alguns frames a frente do jogo para diminuir o atraso dos inputs. Assim como o rewind,
+    so funciona em cores que suportam states. A segunda instancia nao esta disponível para cores que
+    renderizam na GPU e nesse caso o core principal e usado"]
+    pub fn set_run_ahead_config(&self, config: RunAheadConfig) {
+        self.game_thread.channel.set_run_ahead_config(config);
+    }
+
+    #[doc = "grava os inputs de cada frame em *path*, que e escrito quando [Tinic::stop_movie] e chamado
+    ou o jogo e fechado. Enquanto um filme estiver ativo o rewind fica desabilitado.
+    Com *hash_config* o filme também guarda hashes do estado do jogo, veja [Tinic::verify_movie]"]
+    pub fn start_movie_recording(
+        &self,
+        path: PathBuf,
+        origin: MovieOrigin,
+        hash_config: Option<StateHashConfig>,
+    ) {
+        self.game_thread
+            .channel
+            .start_movie_recording(path, origin, hash_config);
+    }
+
+    #[doc = "reproduz um filme gravado por [Tinic::start_movie_recording]. Os inputs do jogador voltam a
+    ser usados quando o filme termina"]
+    pub fn play_movie(&self, path: PathBuf) {
+        self.game_thread.channel.play_movie(path);
+    }
+
+    pub fn stop_movie(&self) {
+        self.game_thread.channel.stop_movie();
+    }
+
+    #[doc = "reproduz o filme o mais rápido possível e informa o primeiro frame em que o jogo ficou
+    diferente da gravação. Serve para encontrar cores nao determinísticos ou mudanças depois de
+    atualizar um core. None se o filme nao puder ser verificado"]
+    pub async fn verify_movie(&self, path: PathBuf) -> Option<MovieVerification> {
+        self.game_thread.channel.verify_movie(path).await
+    }
+
+    #[doc = "retorna os cheats do arquivo .cht da rom atual, que fica na pasta *cheats* de RetroPaths"]
+    pub async fn get_cheats(&self) -> Vec<Cheat> {
+        self.game_thread.channel.get_cheats().await
+    }
+
+    pub fn set_cheat(&self, index: usize, enabled: bool) {
+        self.game_thread.channel.set_cheat(index, enabled);
+    }
+
+    pub fn reset_cheats(&self) {
+        self.game_thread.channel.reset_cheats();
+    }
+
+    #[doc = "abre a bandeja de discos. Necessário antes de usar [Tinic::set_disk_index]"]
+    pub fn eject_disk(&self) {
+        self.game_thread.channel.eject_disk();
+    }
+
+    #[doc = "fecha a bandeja de discos"]
+    pub fn insert_disk(&self) {
+        self.game_thread.channel.insert_disk();
+    }
+
+    #[doc = "troca o disco em jogos com mais de um disco, como os carregados por uma playlist m3u"]
+    pub fn set_disk_index(&self, index: u32) {
+        self.game_thread.channel.set_disk_index(index);
+    }
+
+    #[doc = "nomes que o core deu para cada botão no jogo atual. Quando um botão nao estiver na lista use [crate::GamepadKeyMap::get_key_name_from_retro_button]"]
+    pub async fn get_input_descriptors(&self) -> Vec<InputDescriptor> {
+        self.game_thread.channel.get_input_descriptors().await
+    }
+
+    #[doc = "tempo gasto em cada contador de desempenho do core atual, do mais lento para o mais rápido.
+    use [crate::format_perf_report] para exibir o relatório como uma tabela"]
+    pub async fn get_perf_report(&self) -> Vec<PerfCounterReport> {
+        self.game_thread.channel.get_perf_report().await
+    }
+
     pub async fn quit(&mut self) -> bool {
         if self.game_thread.is_running() {
             self.core_options.take();
@@ -124,6 +345,25 @@ impl Tinic {
 }
 
 impl Tinic {
+    async fn load_content(
+        &mut self,
+        core_path: &str,
+        content: GameContent,
+    ) -> Result<bool, ErroHandle> {
+        let retro_path = self.try_get_retro_path()?.clone();
+        self.game_thread.start(self.controller.clone())?;
+
+        let core_options = self
+            .game_thread
+            .channel
+            .load_game(core_path, content, retro_path, self.av_mode, self.language)
+            .await;
+
+        self.core_options = core_options;
+
+        Ok(self.core_options.is_some())
+    }
+
     fn try_get_retro_path(&mut self) -> Result<&RetroPaths, ErroHandle> {
         let retro_paths = &mut self.retro_paths;
 
@@ -163,4 +403,8 @@ impl DeviceListener for TinicDeviceHandle {
     fn button_pressed(&self, button: String, device: Device) {
         self.extern_listener.button_pressed(button, device);
     }
+
+    fn led_state_changed(&self, led: i32, state: i32) {
+        self.extern_listener.led_state_changed(led, state);
+    }
 }